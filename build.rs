@@ -0,0 +1,138 @@
+//! Embeds every mesh from the assets directory into the binary. Each obj, ply or stl file
+//! is parsed by the same loaders as files given at runtime, and becomes a module named after
+//! the file with a MESHES static holding every object of it. Vertices and indices are static
+//! arrays, so nothing is parsed when the tracer runs and a broken asset fails the build
+
+// loaders only need cgmath, everything they have for the tracer itself is unused here
+#[allow(dead_code)]
+#[path = "src/mesh_data.rs"]
+mod mesh_data;
+#[allow(dead_code)]
+#[path = "src/obj_parser.rs"]
+mod obj_parser;
+#[allow(dead_code)]
+#[path = "src/ply_loader.rs"]
+mod ply_loader;
+#[allow(dead_code)]
+#[path = "src/stl_loader.rs"]
+mod stl_loader;
+#[allow(dead_code)]
+#[path = "src/vector_utils.rs"]
+mod vector_utils;
+
+use {
+    std::{
+        env,
+        fmt::Write as _,
+        fs,
+        io::BufReader,
+        path::Path
+    },
+    mesh_data::{MeshData, Shading},
+    obj_parser::parse_obj,
+    ply_loader::parse_ply,
+    stl_loader::parse_stl
+};
+
+const ASSETS_DIR: &str = "assets";
+const SHARED_SOURCES: [&str; 5] = [
+    "src/mesh_data.rs",
+    "src/obj_parser.rs",
+    "src/ply_loader.rs",
+    "src/stl_loader.rs",
+    "src/vector_utils.rs"
+];
+
+fn load(path: &Path, extension: &str) -> Result<Vec<MeshData>, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    match extension {
+        // materials of assets are left to the scene which places them
+        "obj" => parse_obj(BufReader::new(bytes.as_slice()))
+            .map(|geometry| geometry.parts.into_iter().map(|part| part.mesh).collect())
+            .map_err(|e| e.to_string()),
+        "ply" => parse_ply(bytes.as_slice()).map(|mesh| vec![mesh]).map_err(|e| e.to_string()),
+        _ => parse_stl(&bytes).map(|mesh| vec![mesh]).map_err(|e| e.to_string())
+    }
+}
+
+/// Debug formatting gives the shortest literal which reads back as the same float
+fn float(value: f32) -> String {
+    assert!(value.is_finite(), "asset has a coordinate which is not finite");
+    format!("{:?}", value)
+}
+
+fn write_mesh(out: &mut String, id: usize, mesh: &MeshData) {
+    writeln!(out, "    static VERTICES_{}: [VertexDescription; {}] = [", id, mesh.vertices.len()).unwrap();
+    for v in &mesh.vertices {
+        let (p, n, uv) = (v.position, v.normal, v.uv);
+        writeln!(
+            out,
+            "        VertexDescription {{ position: Vector3::new({}, {}, {}), normal: Vector3::new({}, {}, {}), uv: Vector2::new({}, {}) }},",
+            float(p.x), float(p.y), float(p.z), float(n.x), float(n.y), float(n.z), float(uv.x), float(uv.y)
+        ).unwrap();
+    }
+    writeln!(out, "    ];").unwrap();
+    writeln!(out, "    static INDICES_{}: [usize; {}] = [", id, mesh.indices.len()).unwrap();
+    for face in mesh.indices.chunks(3) {
+        writeln!(out, "        {},", face.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ")).unwrap();
+    }
+    writeln!(out, "    ];").unwrap();
+    writeln!(out, "    static TANGENTS_{}: [Vector4<f32>; {}] = [", id, mesh.tangents.len()).unwrap();
+    for t in &mesh.tangents {
+        writeln!(out, "        Vector4::new({}, {}, {}, {}),", float(t.x), float(t.y), float(t.z), float(t.w)).unwrap();
+    }
+    writeln!(out, "    ];").unwrap();
+    writeln!(out, "    static COLORS_{}: [Vector3<f32>; {}] = [", id, mesh.colors.len()).unwrap();
+    for c in &mesh.colors {
+        writeln!(out, "        Vector3::new({}, {}, {}),", float(c.x), float(c.y), float(c.z)).unwrap();
+    }
+    writeln!(out, "    ];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", ASSETS_DIR);
+    for source in SHARED_SOURCES.iter() {
+        println!("cargo:rerun-if-changed={}", source);
+    }
+    let mut paths = fs::read_dir(ASSETS_DIR)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    paths.sort();
+
+    let mut out = String::new();
+    for path in paths {
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+        let extension = match extension.as_deref() {
+            Some(extension @ "obj") | Some(extension @ "ply") | Some(extension @ "stl") => extension.to_string(),
+            _ => continue
+        };
+        println!("cargo:rerun-if-changed={}", path.display());
+        let meshes = load(&path, &extension).unwrap_or_else(|e| panic!("asset {} is broken: {}", path.display(), e));
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let name = stem.replace(|c: char| !c.is_ascii_alphanumeric(), "_").to_lowercase();
+
+        writeln!(out, "pub mod {} {{", name).unwrap();
+        writeln!(out, "    use super::*;").unwrap();
+        writeln!(out, "    pub static MESHES: [MeshDescription<'static>; {}] = [", meshes.len()).unwrap();
+        for (id, mesh) in meshes.iter().enumerate() {
+            let shading = match mesh.shading {
+                Shading::Smooth => "Smooth",
+                Shading::Flat => "Flat"
+            };
+            writeln!(
+                out,
+                "        MeshDescription {{ vertices: &VERTICES_{0}, indices: &INDICES_{0}, triangle_count: {1}, shading: Shading::{2}, tangents: &TANGENTS_{0}, colors: &COLORS_{0} }},",
+                id,
+                mesh.indices.len() / 3,
+                shading
+            ).unwrap();
+        }
+        writeln!(out, "    ];").unwrap();
+        for (id, mesh) in meshes.iter().enumerate() {
+            write_mesh(&mut out, id, mesh);
+        }
+        writeln!(out, "}}").unwrap();
+    }
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("assets.rs");
+    fs::write(out_path, out).unwrap();
+}
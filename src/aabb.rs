@@ -1,6 +1,9 @@
 use cgmath::{Vector3, vec3};
 use crate::shape::Shape;
+use crate::csg::CsgOperation;
+use crate::metaballs::metaballs_bounds;
 use crate::ray::Ray;
+use crate::transform::Transform;
 
 #[derive(Clone, Copy)]
 pub struct AaBb {
@@ -11,117 +14,216 @@ pub struct AaBb {
 const EPS: f32 = 0.001;
 impl<'a> From<Shape<'a>> for Option<AaBb> {
     fn from(shape: Shape<'a>) -> Self {
-        match shape {
-            Shape::TriangleMesh { center, mesh, ..} => {
-                let (mut min, mut max) = (vec3(0.0, 0.0, 0.0), (vec3(0.0, 0.0, 0.0)));
-                for ix in 0..mesh.vertices.len() {
-                    let p = mesh.vertices[ix].position + center;
-                    if p.x < min.x {min.x = p.x}
-                    if p.x > max.x {max.x = p.x}
-                    if p.y < min.y {min.y = p.y}
-                    if p.y > max.y {max.y = p.y}
-                    if p.z < min.z {min.z = p.z}
-                    if p.z > max.z {max.z = p.z}
-                }
-                min -= vec3(EPS, EPS, EPS);
-                max += vec3(EPS, EPS, EPS);
-                Some(AaBb{min, max})
+        let aabb = match shape {
+            Shape::TriangleMesh { transform, mesh, ..} => {
+                AaBb::from_points(
+                    &mesh.vertices.iter().map(|v| transform.point(v.position)).collect::<Vec<_>>()
+                )
             },
-            _ => {
-                // Shapes different from TriangleMesh considered to be relatively equivalent to AaBb
-                // in the sense of difficulty to calculate hit so it does not provide AaBb
-                None
+            Shape::Triangle { vertices, transform, ..} => {
+                AaBb::from_points(&[
+                    transform.point(vertices[0].position),
+                    transform.point(vertices[1].position),
+                    transform.point(vertices[2].position)
+                ])
             },
-        }
+            Shape::Sphere { radius, transform, ..} => {
+                let center = transform.point(vec3(0.0, 0.0, 0.0));
+                let extent = ball_extent(&transform, radius);
+                Some(AaBb{min: center - extent, max: center + extent})
+            },
+            Shape::Disk { radius, transform, ..} => Some(disk_bounds(&transform, 0.0, radius)),
+            Shape::Annulus { outer_radius, transform, ..} => Some(disk_bounds(&transform, 0.0, outer_radius)),
+            Shape::Cylinder { radius, height, transform, ..} => {
+                Some(disk_bounds(&transform, -height / 2.0, radius).union(&disk_bounds(&transform, height / 2.0, radius)))
+            },
+            Shape::Cone { bottom_radius, top_radius, height, transform, ..} => {
+                // a cone is the hull of its caps, so it is bounded by them
+                let bottom = disk_bounds(&transform, -height / 2.0, bottom_radius);
+                Some(bottom.union(&disk_bounds(&transform, height / 2.0, top_radius)))
+            },
+            Shape::Capsule { radius, height, transform, ..} => {
+                let extent = ball_extent(&transform, radius);
+                let bottom = transform.point(vec3(0.0, -height / 2.0, 0.0));
+                let top = transform.point(vec3(0.0, height / 2.0, 0.0));
+                AaBb::from_points(&[bottom - extent, bottom + extent, top - extent, top + extent])
+            },
+            Shape::Torus { major_radius, minor_radius, transform, ..} => {
+                // the tube is swept along the major circle, so their extents add up
+                let ring = disk_bounds(&transform, 0.0, major_radius);
+                let extent = ball_extent(&transform, minor_radius);
+                Some(AaBb{min: ring.min - extent, max: ring.max + extent})
+            },
+            Shape::Quartic { bounds, transform, ..} => Some(transform.aabb(&bounds)),
+            Shape::Plane { .. } => return None,
+            Shape::Quad { corner, edge_u, edge_v, transform, ..} => {
+                AaBb::from_points(&[
+                    transform.point(corner),
+                    transform.point(corner + edge_u),
+                    transform.point(corner + edge_v),
+                    transform.point(corner + edge_u + edge_v)
+                ])
+            },
+            Shape::Cube { sizes, transform, ..} => {
+                let half_sizes = sizes / 2.0;
+                Some(transform.aabb(&AaBb{min: -half_sizes, max: half_sizes}))
+            },
+            Shape::Instance { transform, shape, ..} => {
+                // bounds of the shape are padded already
+                let aabb: Option<AaBb> = (*shape).into();
+                return aabb.map(|aabb| transform.aabb(&aabb));
+            },
+            Shape::DistanceField { bounds, transform, ..} => Some(transform.aabb(&bounds)),
+            Shape::Metaballs { sources, transform, ..} => metaballs_bounds(sources).map(|aabb| transform.aabb(&aabb)),
+            Shape::Heightfield { field, transform, ..} => Some(transform.aabb(&field.bounds())),
+            Shape::Voxels { grid, transform, ..} => Some(transform.aabb(&grid.bounds())),
+            Shape::Csg { operation, left, right, transform, ..} => {
+                let left: Option<AaBb> = (*left).into();
+                let right: Option<AaBb> = (*right).into();
+                // intersections and differences can't stick out of the left shape
+                let aabb = match operation {
+                    CsgOperation::Union => left.zip(right).map(|(left, right)| left.union(&right)),
+                    CsgOperation::Intersection => left.or(right),
+                    CsgOperation::Difference => left
+                };
+                return aabb.map(|aabb| transform.aabb(&aabb));
+            },
+            Shape::Group { transform, world } => {
+                if !world.is_bounded() {
+                    return None;
+                }
+                return world.bounds().map(|aabb| transform.aabb(&aabb));
+            }
+        };
+        aabb.map(|AaBb{min, max}| AaBb {
+            min: min - vec3(EPS, EPS, EPS),
+            max: max + vec3(EPS, EPS, EPS)
+        })
     }
 }
 
+/// Bounds of a disk of radius in the local xz plane at height y
+fn disk_bounds(transform: &Transform, y: f32, radius: f32) -> AaBb {
+    let center = transform.point(vec3(0.0, y, 0.0));
+    let extent = transform.axis_extents(&[Vector3::unit_x(), Vector3::unit_z()]) * radius;
+    AaBb{min: center - extent, max: center + extent}
+}
+
+/// Half sizes of bounds of a ball of radius, which is an ellipsoid after scaling
+fn ball_extent(transform: &Transform, radius: f32) -> Vector3<f32> {
+    transform.axis_extents(&[Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()]) * radius
+}
+
 impl AaBb {
-    pub fn is_point_inside(&self, p: &Vector3<f32>) -> bool {
-        p.x >= self.min.x && p.y >= self.min.y && p.z >= self.min.z &&
-        p.x <= self.max.x && p.y <= self.max.y && p.z <= self.max.z
+    pub fn from_points(points: &[Vector3<f32>]) -> Option<AaBb> {
+        points
+            .iter()
+            .map(|p| AaBb { min: *p, max: *p })
+            .fold(None, |acc, b| Some(acc.map_or(b, |a: AaBb| a.union(&b))))
     }
-    pub fn slice_by_x(&self) -> (AaBb, AaBb) {
-        let center_x = (self.max - self.min).x / 2.0;
-        (
-            AaBb{
-                min: self.min,
-                max: Vector3{x: center_x, ..self.max}
-            },
-            AaBb{
-                min: Vector3{x: center_x, ..self.min},
-                max: self.max
-            }
-        )
+    pub fn union(&self, other: &AaBb) -> AaBb {
+        AaBb {
+            min: vec3(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z)
+            ),
+            max: vec3(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z)
+            )
+        }
     }
-    pub fn slice_by_y(&self) -> (AaBb, AaBb) {
-        let center_y = (self.max - self.min).y / 2.0;
-        (
-            AaBb{
-                min: self.min,
-                max: Vector3{y: center_y, ..self.max}
-            },
-            AaBb{
-                min: Vector3{y: center_y, ..self.min},
-                max: self.max
-            }
-        )
+    pub fn centroid(&self) -> Vector3<f32> {
+        (self.min + self.max) * 0.5
     }
-    pub fn slice_by_z(&self) -> (AaBb, AaBb) {
-        let center_z = (self.max - self.min).z / 2.0;
-        (
-            AaBb{
-                min: self.min,
-                max: Vector3{z: center_z, ..self.max}
-            },
-            AaBb{
-                min: Vector3{z: center_z, ..self.min},
-                max: self.max
-            }
-        )
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
     }
-    pub fn slice_octal(&self) -> [AaBb; 8] {
-        let (top, bottom) = self.slice_by_y();
-        let (
-            (tl, tr),
-            (bl, br)
-        ) = (
-            top.slice_by_x(),
-            bottom.slice_by_x()
-        );
-        let (
-            (tlf, tlb), (trf, trb),
-            (blf, blb), (brf, brb)
-        ) = (
-            tl.slice_by_z(), tr.slice_by_z(),
-            bl.slice_by_z(), br.slice_by_z()
-        );
-        [
-            tlf, tlb, trf, trb,
-            blf, blb, brf, brb
-        ]
+    /// Returns the distance at which the ray enters the box if it happens inside [t_min, t_max].
+    /// Takes precomputed reciprocal of a ray direction since it is called many times per ray
+    pub fn hit_distance(&self, ray: &Ray, inv_dir: &Vector3<f32>, t_min: f32, t_max: f32) -> Option<f32> {
+        self.hit_interval(ray, inv_dir, t_min, t_max).map(|(near, _)| near)
     }
-    pub fn is_hit(&self, ray: &Ray) -> bool {
-        let (mut t_min, mut t_max) = (0.00001, 10000.0);
+    /// Part of [t_min, t_max] where the ray is inside of the box
+    pub fn hit_interval(&self, ray: &Ray, inv_dir: &Vector3<f32>, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let (mut t_min, mut t_max) = (t_min, t_max);
         for a in 0..3 {
-            let inv_d = 1.0 / ray.direction[a];
-            let (t0, t1) = if inv_d < 0.0 {
-                (
-                    (self.max[a] - ray.origin[a]) * inv_d,
-                    (self.min[a] - ray.origin[a]) * inv_d
-                )
-            } else {
-                (
-                    (self.min[a] - ray.origin[a]) * inv_d,
-                    (self.max[a] - ray.origin[a]) * inv_d
-                )
-            };
-            if t0 > t_min { t_min = t0}
-            if t1 < t_max { t_max = t1}
+            let t0 = (self.min[a] - ray.origin[a]) * inv_dir[a];
+            let t1 = (self.max[a] - ray.origin[a]) * inv_dir[a];
+            let (t0, t1) = if inv_dir[a] < 0.0 { (t1, t0) } else { (t0, t1) };
+            if t0 > t_min { t_min = t0 }
+            if t1 < t_max { t_max = t1 }
             if t_max < t_min {
-                return false;
+                return None;
+            }
+        }
+        Some((t_min, t_max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::{Quaternion, vec2},
+        rand::{Rng, SeedableRng, rngs::StdRng},
+        std::f32::consts::PI,
+        crate::material::{Albedo, Material, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    /// Points of circles of given radii around y at given heights, whose hull or sweep is the shape
+    fn circles(rings: &[(f32, f32)]) -> Vec<Vector3<f32>> {
+        let mut points = Vec::new();
+        for &(y, radius) in rings {
+            for i in 0..360 {
+                let angle = vec2((i as f32).to_radians().cos(), (i as f32).to_radians().sin());
+                points.push(vec3(angle.x * radius, y, angle.y * radius));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn round_shapes_are_bounded_tightly() {
+        let mut rng = StdRng::seed_from_u64(44);
+        for _ in 0..20 {
+            let rotation = Quaternion::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+            let scale = vec3(rng.gen_range(0.5, 2.0), rng.gen_range(0.5, 2.0), rng.gen_range(0.5, 2.0));
+            let transform = Transform::new(vec3(1.0, -2.0, 3.0), rotation, scale).unwrap();
+            let (capped, material) = (true, MATERIAL);
+
+            let cylinder = Shape::Cylinder { radius: 0.5, height: 2.0, capped, transform, material };
+            let cone = Shape::Cone { bottom_radius: 1.0, top_radius: 0.25, height: 1.5, capped, transform, material };
+            let torus = Shape::Torus { major_radius: 1.0, minor_radius: 0.25, transform, material };
+            let tube: Vec<_> = (0..72)
+                .map(|i| i as f32 * PI / 36.0)
+                .flat_map(|angle| circles(&[(0.25 * angle.sin(), 1.0 + 0.25 * angle.cos())]))
+                .collect();
+            let cases = [
+                (cylinder, circles(&[(-1.0, 0.5), (1.0, 0.5)])),
+                (cone, circles(&[(-0.75, 1.0), (0.75, 0.25)])),
+                (torus, tube)
+            ];
+            for (shape, points) in cases {
+                let aabb: Option<AaBb> = shape.into();
+                let aabb = aabb.unwrap();
+                let points = AaBb::from_points(&points.iter().map(|&p| transform.point(p)).collect::<Vec<_>>()).unwrap();
+                // bounds are padded by EPS and points are a degree or five apart
+                for a in 0..3 {
+                    assert!(aabb.min[a] <= points.min[a] && aabb.max[a] >= points.max[a]);
+                    assert!(points.min[a] - aabb.min[a] < 0.01 && aabb.max[a] - points.max[a] < 0.01);
+                }
             }
         }
-        true
     }
-}
\ No newline at end of file
+}
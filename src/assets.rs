@@ -0,0 +1,9 @@
+use {
+    cgmath::{Vector2, Vector3, Vector4},
+    crate::mesh_data::{MeshDescription, Shading, VertexDescription}
+};
+
+// meshes embedded from the assets directory by build.rs, a module per file with MESHES
+// describing every object of the file in static arrays
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
@@ -1,1367 +0,0 @@
-// directly exported from blender
-pub const VERTICES_0: &[((f32, f32, f32),(f32, f32, f32),(f32, f32))] = &[
-    ((0.046852, 0.192473, -0.041465), (0.163488, 0.855037, -0.492080), (0.0, 0.0)),
-    ((0.057643, 0.195451, -0.033808), (-0.044771, 0.891079, -0.451613), (0.0, 0.0)),
-    ((0.059459, 0.181714, -0.054399), (0.075686, 0.720573, -0.689200), (0.0, 0.0)),
-    ((0.143698, 0.292146, 0.077515), (-0.399609, 0.885830, -0.235725), (0.0, 0.0)),
-    ((0.159353, 0.290321, 0.074413), (0.779870, 0.590716, -0.206885), (0.0, 0.0)),
-    ((0.144432, 0.284869, 0.052073), (-0.220710, 0.922086, -0.317850), (0.0, 0.0)),
-    ((0.085800, 0.021732, 0.051433), (0.675832, 0.620960, 0.397015), (0.0, 0.0)),
-    ((0.085281, 0.003123, 0.054899), (0.440626, -0.897671, 0.000092), (0.0, 0.0)),
-    ((0.103727, 0.002677, 0.041500), (0.696829, -0.427320, 0.576006), (0.0, 0.0)),
-    ((0.054680, 0.124171, 0.074796), (0.507614, 0.370739, 0.777703), (0.0, 0.0)),
-    ((0.061275, 0.135731, 0.063019), (0.404340, 0.372082, 0.835475), (0.0, 0.0)),
-    ((0.055786, 0.158858, 0.056418), (0.109012, 0.485458, 0.867397), (0.0, 0.0)),
-    ((0.031301, 0.202747, -0.027378), (0.223884, 0.954955, -0.194586), (0.0, 0.0)),
-    ((0.034836, 0.185665, -0.059161), (0.285165, 0.720511, -0.632038), (0.0, 0.0)),
-    ((0.023868, 0.199639, -0.046072), (0.338054, 0.840022, -0.424268), (0.0, 0.0)),
-    ((0.056980, 0.199538, -0.020231), (-0.061220, 0.988861, -0.135441), (0.0, 0.0)),
-    ((0.006070, 0.203197, -0.047882), (0.130894, 0.890774, -0.435133), (0.0, 0.0)),
-    ((0.006667, 0.191799, -0.063500), (0.099948, 0.706412, -0.700674), (0.0, 0.0)),
-    ((0.114393, 0.218276, -0.060086), (-0.760186, 0.249367, -0.599902), (0.0, 0.0)),
-    ((0.096454, 0.206151, -0.046848), (-0.618000, 0.535325, -0.575701), (0.0, 0.0)),
-    ((0.087042, 0.207941, -0.026576), (-0.602741, 0.758599, -0.247383), (0.0, 0.0)),
-    ((-0.044166, 0.202922, -0.028668), (-0.429365, 0.890622, -0.149724), (0.0, 0.0)),
-    ((-0.019167, 0.203456, -0.048831), (-0.099033, 0.878414, -0.467483), (0.0, 0.0)),
-    ((-0.037699, 0.201393, -0.045352), (-0.367382, 0.844905, -0.388775), (0.0, 0.0)),
-    ((0.053116, 0.010122, -0.055832), (-0.191260, -0.974822, -0.114505), (0.0, 0.0)),
-    ((0.064526, 0.004321, -0.049168), (-0.260079, -0.965178, 0.027589), (0.0, 0.0)),
-    ((0.069195, 0.003630, -0.024632), (-0.186621, -0.981719, -0.037141), (0.0, 0.0)),
-    ((0.077042, 0.194286, -0.042424), (-0.284738, 0.730399, -0.620808), (0.0, 0.0)),
-    ((0.006272, 0.208757, -0.027372), (0.140294, 0.981811, -0.127750), (0.0, 0.0)),
-    ((0.186606, 0.213360, -0.004102), (0.902341, 0.114933, 0.415387), (0.0, 0.0)),
-    ((0.176312, 0.242725, -0.001894), (0.780725, 0.459334, 0.423597), (0.0, 0.0)),
-    ((0.177757, 0.223186, 0.006827), (0.794519, 0.167943, 0.583514), (0.0, 0.0)),
-    ((0.104455, 0.240160, -0.029020), (-0.843989, 0.355388, -0.401685), (0.0, 0.0)),
-    ((0.114705, 0.260781, -0.025831), (-0.557024, 0.801904, -0.215918), (0.0, 0.0)),
-    ((0.116764, 0.239781, -0.057074), (-0.756462, 0.311624, -0.574969), (0.0, 0.0)),
-    ((-0.019453, 0.210106, -0.027231), (-0.112247, 0.983703, -0.140263), (0.0, 0.0)),
-    ((0.162896, 0.080781, -0.001998), (0.774987, -0.619800, 0.123173), (0.0, 0.0)),
-    ((0.162242, 0.079958, -0.024777), (0.754295, -0.647877, -0.106021), (0.0, 0.0)),
-    ((0.175233, 0.100789, 0.001083), (0.885586, -0.408216, 0.221473), (0.0, 0.0)),
-    ((0.127378, 0.279083, 0.095534), (-0.938261, 0.081362, 0.336131), (0.0, 0.0)),
-    ((0.140236, 0.296487, 0.104074), (-0.647572, 0.702567, 0.294931), (0.0, 0.0)),
-    ((0.138585, 0.205160, 0.029769), (0.075625, 0.141392, 0.987030), (0.0, 0.0)),
-    ((0.166337, 0.207726, 0.021265), (0.560747, 0.042787, 0.826868), (0.0, 0.0)),
-    ((0.139605, 0.236296, 0.027100), (-0.230781, -0.074099, 0.970153), (0.0, 0.0)),
-    ((-0.019496, 0.210075, -0.001343), (-0.102512, 0.984832, 0.139744), (0.0, 0.0)),
-    ((0.141514, 0.189883, -0.099993), (-0.483047, -0.163884, -0.860103), (0.0, 0.0)),
-    ((0.145381, 0.208380, -0.096983), (-0.429090, 0.458937, -0.777947), (0.0, 0.0)),
-    ((0.161145, 0.212481, -0.099401), (0.104709, 0.348430, -0.931455), (0.0, 0.0)),
-    ((0.161324, 0.186883, -0.099187), (0.256630, -0.309153, -0.915708), (0.0, 0.0)),
-    ((0.136690, 0.213674, -0.085117), (-0.580432, 0.378155, -0.721122), (0.0, 0.0)),
-    ((0.165042, 0.236834, -0.081094), (0.241493, 0.654530, -0.716392), (0.0, 0.0)),
-    ((0.140323, 0.239168, -0.078260), (-0.288949, 0.525285, -0.800348), (0.0, 0.0)),
-    ((0.175019, 0.099813, -0.027581), (0.890164, -0.418989, -0.178838), (0.0, 0.0)),
-    ((0.006121, 0.208687, -0.001668), (0.130467, 0.984344, 0.118381), (0.0, 0.0)),
-    ((0.023954, 0.303785, 0.065853), (0.489029, -0.350688, 0.798639), (0.0, 0.0)),
-    ((0.014169, 0.302104, 0.066980), (-0.719474, -0.300333, 0.626179), (0.0, 0.0)),
-    ((0.016309, 0.289696, 0.052949), (-0.572161, -0.245521, -0.782495), (0.0, 0.0)),
-    ((-0.018900, 0.155332, -0.081900), (-0.116611, 0.489273, -0.864254), (0.0, 0.0)),
-    ((-0.021242, 0.176871, -0.071964), (-0.138981, 0.492294, -0.859249), (0.0, 0.0)),
-    ((0.006232, 0.175903, -0.074954), (0.031831, 0.480117, -0.876614), (0.0, 0.0)),
-    ((-0.038459, 0.123041, -0.097449), (-0.339061, 0.328745, -0.881436), (0.0, 0.0)),
-    ((-0.046542, 0.131382, -0.090653), (-0.335337, 0.394421, -0.855525), (0.0, 0.0)),
-    ((0.030706, 0.203107, -0.000124), (0.177770, 0.972686, 0.149113), (0.0, 0.0)),
-    ((0.115226, 0.029020, -0.052399), (0.552751, 0.779107, -0.295663), (0.0, 0.0)),
-    ((0.107595, 0.049969, -0.050114), (0.771722, -0.366100, -0.519974), (0.0, 0.0)),
-    ((0.116600, 0.020564, -0.078720), (0.149144, 0.556169, -0.817560), (0.0, 0.0)),
-    ((0.095153, 0.240808, -0.004075), (-0.982513, -0.173620, -0.066988), (0.0, 0.0)),
-    ((0.099552, 0.227990, -0.020937), (-0.933836, 0.346904, -0.087008), (0.0, 0.0)),
-    ((0.189476, 0.183514, -0.052760), (0.939482, -0.298685, -0.167791), (0.0, 0.0)),
-    ((0.188059, 0.182765, -0.077218), (0.849574, -0.389935, -0.355144), (0.0, 0.0)),
-    ((0.189608, 0.210630, -0.052427), (0.991668, 0.089969, -0.091952), (0.0, 0.0)),
-    ((-0.017783, 0.127701, -0.103149), (-0.200385, 0.413404, -0.888211), (0.0, 0.0)),
-    ((0.184541, 0.123931, -0.026314), (0.963622, -0.159520, -0.214423), (0.0, 0.0)),
-    ((-0.070605, 0.019409, -0.052099), (-0.471023, -0.682821, -0.558428), (0.0, 0.0)),
-    ((-0.099739, 0.026536, -0.046438), (-0.417158, -0.635273, -0.649892), (0.0, 0.0)),
-    ((-0.079076, 0.044036, -0.062279), (-0.550218, -0.296487, -0.780572), (0.0, 0.0)),
-    ((0.113160, 0.128701, -0.082976), (0.030671, 0.081606, -0.996185), (0.0, 0.0)),
-    ((0.141152, 0.126281, -0.078622), (0.339152, 0.012604, -0.940642), (0.0, 0.0)),
-    ((0.140339, 0.100420, -0.075097), (0.413862, -0.334147, -0.846767), (0.0, 0.0)),
-    ((0.139404, 0.157703, -0.075090), (0.067202, -0.214545, -0.974364), (0.0, 0.0)),
-    ((0.112770, 0.151112, -0.076660), (-0.038453, 0.350047, -0.935911), (0.0, 0.0)),
-    ((-0.020407, 0.021441, -0.087261), (-0.208502, -0.484329, -0.849635), (0.0, 0.0)),
-    ((-0.014747, 0.030073, -0.094638), (-0.175146, -0.481246, -0.858882), (0.0, 0.0)),
-    ((0.007555, 0.019347, -0.087478), (-0.023835, -0.428846, -0.903043), (0.0, 0.0)),
-    ((-0.018032, 0.047040, -0.101489), (-0.215949, -0.319895, -0.922483), (0.0, 0.0)),
-    ((0.113627, 0.098036, -0.081141), (0.101962, -0.266701, -0.958342), (0.0, 0.0)),
-    ((0.006214, 0.155333, -0.083010), (0.066042, 0.490738, -0.868770), (0.0, 0.0)),
-    ((-0.021890, 0.072032, -0.106213), (-0.240303, -0.132511, -0.961608), (0.0, 0.0)),
-    ((-0.021842, 0.101861, -0.107625), (-0.232887, 0.071718, -0.969848), (0.0, 0.0)),
-    ((0.006425, 0.072838, -0.108618), (0.085940, -0.185369, -0.978881), (0.0, 0.0)),
-    ((0.060539, 0.200294, 0.000169), (-0.129093, 0.979644, 0.153661), (0.0, 0.0)),
-    ((-0.019465, 0.191320, -0.063151), (-0.127995, 0.646199, -0.752342), (0.0, 0.0)),
-    ((0.005464, 0.048796, -0.102789), (0.135105, -0.330973, -0.933897), (0.0, 0.0)),
-    ((0.084888, 0.263326, 0.026581), (0.075259, -0.643666, 0.761559), (0.0, 0.0)),
-    ((0.075028, 0.277483, 0.037826), (0.378979, -0.344462, 0.858882), (0.0, 0.0)),
-    ((0.056797, 0.269427, 0.023705), (-0.433241, -0.831843, -0.346843), (0.0, 0.0)),
-    ((0.183265, 0.121853, -0.002753), (0.961852, -0.162755, 0.219825), (0.0, 0.0)),
-    ((-0.088793, 0.154135, -0.045613), (-0.822871, 0.391797, -0.411512), (0.0, 0.0)),
-    ((-0.092896, 0.154579, -0.025492), (-0.899503, 0.426740, -0.093570), (0.0, 0.0)),
-    ((-0.086346, 0.165747, -0.032908), (-0.796960, 0.588458, -0.136235), (0.0, 0.0)),
-    ((0.162705, 0.239202, 0.019132), (0.725852, 0.076327, 0.683554), (0.0, 0.0)),
-    ((0.031260, 0.011906, 0.026200), (-0.017182, -0.999847, 0.000610), (0.0, 0.0)),
-    ((0.057723, 0.011445, 0.026167), (0.077303, -0.932920, 0.351665), (0.0, 0.0)),
-    ((0.031619, 0.011411, 0.046876), (0.062960, -0.942656, -0.327738), (0.0, 0.0)),
-    ((-0.022769, 0.138967, -0.093044), (-0.245918, 0.584613, -0.773125), (0.0, 0.0)),
-    ((-0.047243, 0.185695, -0.058306), (-0.366955, 0.658040, -0.657491), (0.0, 0.0)),
-    ((0.085262, 0.018248, -0.078343), (0.184484, 0.145878, -0.971923), (0.0, 0.0)),
-    ((0.086308, 0.047205, -0.070926), (0.353526, 0.013092, -0.935301), (0.0, 0.0)),
-    ((-0.044339, 0.048215, 0.071859), (-0.425367, -0.175878, 0.887722), (0.0, 0.0)),
-    ((-0.044436, 0.075633, 0.075092), (-0.469863, -0.048830, 0.881375), (0.0, 0.0)),
-    ((-0.074931, 0.075485, 0.052315), (-0.682241, -0.084078, 0.726249), (0.0, 0.0)),
-    ((0.087339, 0.097315, -0.079737), (0.025575, -0.153356, -0.987823), (0.0, 0.0)),
-    ((0.110693, 0.081360, -0.072691), (0.197394, -0.516434, -0.833247), (0.0, 0.0)),
-    ((0.086297, 0.125703, -0.080920), (0.006500, 0.075655, -0.997101), (0.0, 0.0)),
-    ((0.088386, 0.153333, -0.074601), (-0.027131, 0.378338, -0.925260), (0.0, 0.0)),
-    ((0.114254, 0.167090, -0.068750), (-0.164892, 0.201605, -0.965484), (0.0, 0.0)),
-    ((0.086870, 0.179968, -0.056718), (-0.212745, 0.559709, -0.800897), (0.0, 0.0)),
-    ((0.109423, 0.182827, -0.064649), (-0.472427, 0.241981, -0.847468), (0.0, 0.0)),
-    ((-0.098671, 0.048393, -0.054748), (-0.385418, -0.058992, -0.920835), (0.0, 0.0)),
-    ((0.086106, 0.072679, -0.074883), (0.174841, -0.219611, -0.959777), (0.0, 0.0)),
-    ((0.081545, 0.165417, -0.068120), (-0.080264, 0.466689, -0.880734), (0.0, 0.0)),
-    ((0.061074, 0.266593, 0.008314), (-0.764061, -0.022797, -0.644734), (0.0, 0.0)),
-    ((0.088056, 0.288005, 0.005155), (0.039705, 0.660024, -0.750175), (0.0, 0.0)),
-    ((0.071869, 0.297068, 0.008858), (-0.476577, 0.462539, -0.747581), (0.0, 0.0)),
-    ((0.087263, 0.293387, 0.021917), (0.676748, 0.651509, 0.342784), (0.0, 0.0)),
-    ((0.057102, 0.044474, -0.072637), (0.316630, 0.215857, -0.923643), (0.0, 0.0)),
-    ((0.064755, 0.024443, -0.083616), (0.535142, 0.274361, -0.798944), (0.0, 0.0)),
-    ((0.060690, 0.099265, -0.081072), (0.398083, -0.166845, -0.902036), (0.0, 0.0)),
-    ((0.056419, 0.074037, -0.075421), (0.330546, -0.191778, -0.924070), (0.0, 0.0)),
-    ((0.057615, 0.129752, -0.083578), (0.305429, 0.255226, -0.917356), (0.0, 0.0)),
-    ((0.058298, 0.156359, -0.074217), (0.106632, 0.456740, -0.883175), (0.0, 0.0)),
-    ((0.006269, 0.098070, -0.111843), (0.009766, 0.042970, -0.999023), (0.0, 0.0)),
-    ((0.006229, 0.129527, -0.104629), (0.048067, 0.451399, -0.890988), (0.0, 0.0)),
-    ((-0.093705, 0.154729, -0.001969), (-0.892697, 0.445265, 0.069063), (0.0, 0.0)),
-    ((-0.089985, 0.148615, 0.021866), (-0.834376, 0.360057, 0.417280), (0.0, 0.0)),
-    ((-0.086277, 0.166137, 0.005971), (-0.793786, 0.586077, 0.162328), (0.0, 0.0)),
-    ((0.062226, 0.165358, -0.068024), (-0.030824, 0.616169, -0.786981), (0.0, 0.0)),
-    ((0.061515, 0.295469, 0.023716), (-0.453139, -0.042055, -0.890439), (0.0, 0.0)),
-    ((-0.047208, 0.156250, -0.077908), (-0.250984, 0.447890, -0.858119), (0.0, 0.0)),
-    ((0.056362, 0.017174, -0.097044), (0.532792, 0.095645, -0.840785), (0.0, 0.0)),
-    ((0.089492, 0.210822, -0.001159), (-0.646260, 0.726066, 0.234748), (0.0, 0.0)),
-    ((0.101272, 0.230532, 0.005299), (-0.730918, -0.045900, 0.680868), (0.0, 0.0)),
-    ((-0.096812, 0.126001, 0.024170), (-0.891476, 0.205786, 0.403577), (0.0, 0.0)),
-    ((-0.083688, 0.135257, 0.037962), (-0.726859, 0.265389, 0.633412), (0.0, 0.0)),
-    ((-0.078231, 0.162325, 0.027720), (-0.637532, 0.565722, 0.522935), (0.0, 0.0)),
-    ((0.116969, 0.256188, -0.050677), (-0.671255, 0.576556, -0.465804), (0.0, 0.0)),
-    ((0.109559, 0.047708, -0.025062), (0.913236, -0.376782, -0.155004), (0.0, 0.0)),
-    ((0.116759, 0.020993, -0.029579), (0.842067, 0.532670, -0.084536), (0.0, 0.0)),
-    ((0.039432, 0.045161, -0.082858), (0.502670, 0.067598, -0.861812), (0.0, 0.0)),
-    ((0.031513, 0.018482, -0.097678), (-0.355052, 0.255226, -0.899289), (0.0, 0.0)),
-    ((0.032710, 0.160635, -0.077575), (0.192206, 0.440870, -0.876705), (0.0, 0.0)),
-    ((0.034518, 0.139580, -0.086256), (0.257302, 0.551164, -0.793695), (0.0, 0.0)),
-    ((-0.050956, 0.114779, -0.093615), (-0.478988, 0.165838, -0.861995), (0.0, 0.0)),
-    ((-0.045246, 0.097843, -0.099112), (-0.452345, 0.008423, -0.891781), (0.0, 0.0)),
-    ((-0.043671, 0.203219, 0.000183), (-0.421522, 0.895993, 0.139592), (0.0, 0.0)),
-    ((0.030625, 0.021739, -0.079612), (-0.191260, -0.474715, -0.859066), (0.0, 0.0)),
-    ((0.137992, 0.264174, -0.028044), (-0.018555, 0.997833, -0.062899), (0.0, 0.0)),
-    ((0.164567, 0.256791, -0.053870), (0.322764, 0.878231, -0.352794), (0.0, 0.0)),
-    ((0.136734, 0.258822, -0.055916), (-0.137242, 0.855556, -0.499161), (0.0, 0.0)),
-    ((0.031042, 0.277900, 0.041580), (-0.934812, -0.345531, 0.081759), (0.0, 0.0)),
-    ((0.037713, 0.272638, 0.025779), (-0.596759, -0.551286, -0.583026), (0.0, 0.0)),
-    ((-0.128723, 0.045209, 0.002175), (-0.912473, -0.253334, 0.321177), (0.0, 0.0)),
-    ((-0.125887, 0.076203, 0.000124), (-0.706015, 0.621723, 0.339030), (0.0, 0.0)),
-    ((-0.130309, 0.075004, -0.024061), (-0.872219, 0.450240, -0.190954), (0.0, 0.0)),
-    ((0.183402, 0.155758, -0.025413), (0.972961, -0.175146, -0.150517), (0.0, 0.0)),
-    ((0.183202, 0.156662, -0.006422), (0.891659, -0.393628, 0.223579), (0.0, 0.0)),
-    ((0.177163, 0.155336, 0.004511), (0.865993, -0.027528, 0.499283), (0.0, 0.0)),
-    ((-0.131782, 0.046536, -0.025602), (-0.953246, -0.245888, -0.175542), (0.0, 0.0)),
-    ((0.024166, 0.179328, -0.069904), (0.270516, 0.560625, -0.782617), (0.0, 0.0)),
-    ((0.113120, 0.022025, 0.029824), (0.519211, 0.292825, 0.802881), (0.0, 0.0)),
-    ((0.112882, 0.001322, 0.027076), (0.260598, -0.814417, 0.518418), (0.0, 0.0)),
-    ((0.028107, 0.050883, -0.096407), (0.489242, -0.300119, -0.818842), (0.0, 0.0)),
-    ((0.056959, 0.277156, 0.041133), (0.098972, -0.614032, 0.783013), (0.0, 0.0)),
-    ((0.029767, 0.074017, -0.101532), (0.508530, -0.262032, -0.820185), (0.0, 0.0)),
-    ((0.033327, 0.097084, -0.107386), (0.423017, -0.064394, -0.903806), (0.0, 0.0)),
-    ((0.030557, 0.125556, -0.103195), (0.271523, 0.435286, -0.858333), (0.0, 0.0)),
-    ((0.084332, 0.079719, 0.047513), (0.275979, -0.370098, 0.887020), (0.0, 0.0)),
-    ((0.082120, 0.099490, 0.055072), (0.157262, -0.336192, 0.928556), (0.0, 0.0)),
-    ((0.063947, 0.072214, 0.054871), (0.682485, -0.221564, 0.696463), (0.0, 0.0)),
-    ((-0.062037, 0.026439, -0.068587), (-0.628285, -0.450209, -0.634449), (0.0, 0.0)),
-    ((-0.046279, 0.018585, -0.077867), (-0.490677, -0.451094, -0.745445), (0.0, 0.0)),
-    ((-0.122601, 0.047923, -0.044124), (-0.680288, -0.205603, -0.703482), (0.0, 0.0)),
-    ((-0.121667, 0.069192, -0.043981), (-0.569384, 0.446028, -0.690512), (0.0, 0.0)),
-    ((-0.097813, 0.072589, -0.050795), (-0.659352, 0.137638, -0.739097), (0.0, 0.0)),
-    ((-0.096630, 0.074108, 0.024708), (-0.738090, 0.137761, 0.660451), (0.0, 0.0)),
-    ((-0.097598, 0.100008, 0.024982), (-0.893918, -0.010590, 0.448042), (0.0, 0.0)),
-    ((-0.108935, 0.081766, 0.001066), (-0.718345, 0.474166, 0.509018), (0.0, 0.0)),
-    ((-0.046753, 0.042764, -0.086215), (-0.465651, -0.292337, -0.835261), (0.0, 0.0)),
-    ((-0.072644, 0.024624, 0.026761), (-0.460982, -0.630818, 0.624104), (0.0, 0.0)),
-    ((-0.070813, 0.005825, 0.003177), (-0.277383, -0.934233, 0.224097), (0.0, 0.0)),
-    ((-0.044251, 0.004610, 0.026472), (-0.267861, -0.940367, 0.209571), (0.0, 0.0)),
-    ((0.083039, 0.019381, 0.035040), (-0.155553, -0.554765, 0.817316), (0.0, 0.0)),
-    ((0.111764, 0.050727, 0.024568), (0.674154, -0.364940, 0.642079), (0.0, 0.0)),
-    ((0.043563, 0.318934, 0.034049), (-0.641469, -0.711081, -0.287759), (0.0, 0.0)),
-    ((0.056628, 0.314089, 0.026772), (0.163610, 0.583148, -0.795709), (0.0, 0.0)),
-    ((-0.038008, 0.053357, -0.094857), (-0.449446, -0.262185, -0.853938), (0.0, 0.0)),
-    ((-0.102831, 0.128185, 0.001235), (-0.969115, 0.211859, 0.126102), (0.0, 0.0)),
-    ((-0.100654, 0.016843, -0.024571), (-0.445967, -0.876370, -0.181799), (0.0, 0.0)),
-    ((-0.116995, 0.028352, 0.000923), (-0.666616, -0.691183, 0.279000), (0.0, 0.0)),
-    ((-0.121219, 0.030585, -0.025756), (-0.703238, -0.681997, -0.200751), (0.0, 0.0)),
-    ((0.086842, 0.264266, -0.002807), (-0.658986, 0.006653, -0.752098), (0.0, 0.0)),
-    ((0.137318, 0.176373, -0.090336), (-0.262246, -0.534196, -0.803613), (0.0, 0.0)),
-    ((-0.070928, 0.073841, -0.077358), (-0.595660, -0.143071, -0.790368), (0.0, 0.0)),
-    ((-0.051939, 0.073928, -0.091384), (-0.551347, -0.172704, -0.816187), (0.0, 0.0)),
-    ((-0.105486, 0.099858, 0.000042), (-0.948332, 0.222297, 0.226356), (0.0, 0.0)),
-    ((0.031747, 0.295979, 0.049024), (-0.702780, -0.206153, -0.680837), (0.0, 0.0)),
-    ((0.036135, 0.287015, 0.034409), (-0.721915, 0.398389, -0.565752), (0.0, 0.0)),
-    ((0.121248, 0.185583, -0.074587), (-0.635548, -0.085269, -0.767296), (0.0, 0.0)),
-    ((-0.068228, 0.049953, -0.074575), (-0.584735, -0.241462, -0.774407), (0.0, 0.0)),
-    ((0.121409, 0.203757, -0.074788), (-0.809931, 0.420942, -0.408368), (0.0, 0.0)),
-    ((-0.085661, 0.125245, -0.066617), (-0.757744, 0.194281, -0.622913), (0.0, 0.0)),
-    ((-0.077757, 0.158108, -0.056981), (-0.681722, 0.460829, -0.568163), (0.0, 0.0)),
-    ((-0.071221, 0.128237, -0.078539), (-0.568651, 0.239845, -0.786798), (0.0, 0.0)),
-    ((0.036894, 0.320431, 0.049175), (-0.689566, 0.714499, 0.118107), (0.0, 0.0)),
-    ((-0.118609, 0.047245, 0.018517), (-0.642903, -0.177984, 0.744957), (0.0, 0.0)),
-    ((-0.095857, 0.026957, 0.021061), (-0.374065, -0.567431, 0.733512), (0.0, 0.0)),
-    ((-0.098744, 0.047892, 0.026182), (-0.428816, -0.168706, 0.887478), (0.0, 0.0)),
-    ((-0.065249, 0.149554, -0.073553), (-0.493454, 0.362651, -0.790551), (0.0, 0.0)),
-    ((-0.060440, 0.165671, -0.067602), (-0.461592, 0.537767, -0.705466), (0.0, 0.0)),
-    ((0.115893, 0.002717, -0.077440), (0.149266, -0.684927, -0.713126), (0.0, 0.0)),
-    ((0.115642, 0.243725, 0.016389), (-0.113712, 0.277749, 0.953887), (0.0, 0.0)),
-    ((0.094461, 0.245956, 0.015463), (-0.551775, -0.504532, 0.664052), (0.0, 0.0)),
-    ((-0.072663, 0.098663, -0.080325), (-0.631001, -0.031373, -0.775140), (0.0, 0.0)),
-    ((-0.086660, 0.098195, -0.066640), (-0.763207, -0.020569, -0.645802), (0.0, 0.0)),
-    ((-0.101880, 0.129275, -0.025819), (-0.960479, 0.224952, -0.163823), (0.0, 0.0)),
-    ((-0.105688, 0.098971, -0.025712), (-0.904324, 0.334025, -0.265694), (0.0, 0.0)),
-    ((-0.038979, 0.073011, -0.099189), (-0.439558, -0.155126, -0.884701), (0.0, 0.0)),
-    ((0.016439, 0.315987, 0.069501), (-0.599475, 0.468184, 0.649129), (0.0, 0.0)),
-    ((0.049703, 0.051840, 0.073951), (0.692373, -0.153142, 0.705069), (0.0, 0.0)),
-    ((0.057700, 0.021116, 0.074443), (0.293771, 0.242775, 0.924497), (0.0, 0.0)),
-    ((0.059499, 0.050386, 0.052984), (0.922239, -0.096774, 0.374248), (0.0, 0.0)),
-    ((0.083305, 0.157145, 0.054549), (0.096896, 0.325083, 0.940672), (0.0, 0.0)),
-    ((0.189003, 0.242402, -0.030248), (0.896481, 0.442915, 0.010956), (0.0, 0.0)),
-    ((0.113693, 0.267603, -0.003724), (0.267037, 0.943602, -0.195532), (0.0, 0.0)),
-    ((-0.085900, 0.073674, -0.066002), (-0.708518, -0.120304, -0.695303), (0.0, 0.0)),
-    ((0.144480, 0.261433, 0.077704), (0.717490, -0.605976, 0.343425), (0.0, 0.0)),
-    ((0.126468, 0.270320, 0.073423), (-0.868374, -0.028840, -0.495010), (0.0, 0.0)),
-    ((0.140745, 0.248309, 0.072460), (0.413129, -0.824030, 0.387585), (0.0, 0.0)),
-    ((-0.096876, 0.099128, -0.051152), (-0.887112, 0.060030, -0.457595), (0.0, 0.0)),
-    ((-0.095364, 0.126226, -0.049276), (-0.900815, 0.189764, -0.390454), (0.0, 0.0)),
-    ((-0.106413, 0.081961, -0.033903), (-0.671865, 0.411908, -0.615528), (0.0, 0.0)),
-    ((0.139054, 0.129004, 0.048474), (0.386425, 0.148106, 0.910337), (0.0, 0.0)),
-    ((0.156170, 0.125238, 0.041668), (0.568865, 0.079989, 0.818506), (0.0, 0.0)),
-    ((0.164521, 0.153031, 0.025192), (0.696188, 0.160680, 0.699606), (0.0, 0.0)),
-    ((-0.047813, 0.021254, 0.054841), (-0.564806, -0.581164, 0.585833), (0.0, 0.0)),
-    ((-0.055310, 0.016265, 0.030643), (-0.527879, -0.702139, 0.477767), (0.0, 0.0)),
-    ((0.025101, 0.320756, 0.066280), (0.171239, 0.833522, 0.525254), (0.0, 0.0)),
-    ((-0.100357, 0.016979, 0.001079), (-0.452345, -0.858608, 0.241157), (0.0, 0.0)),
-    ((0.190135, 0.216821, -0.025761), (0.995666, 0.079440, 0.048250), (0.0, 0.0)),
-    ((0.112673, 0.213724, 0.021527), (-0.409925, 0.354503, 0.840388), (0.0, 0.0)),
-    ((-0.065413, 0.181717, -0.050101), (-0.588183, 0.638112, -0.496811), (0.0, 0.0)),
-    ((0.074487, 0.290469, 0.038173), (0.562304, 0.282540, 0.777123), (0.0, 0.0)),
-    ((0.054625, 0.010646, 0.004130), (-0.019257, -0.998962, 0.040773), (0.0, 0.0)),
-    ((0.157700, 0.267410, 0.046747), (0.992828, 0.098178, -0.067812), (0.0, 0.0)),
-    ((0.157805, 0.277411, 0.074300), (0.881222, -0.300790, 0.364605), (0.0, 0.0)),
-    ((0.082259, 0.124426, 0.060812), (0.215094, 0.020325, 0.976348), (0.0, 0.0)),
-    ((0.069776, 0.105299, 0.061269), (0.471572, -0.508255, 0.720573), (0.0, 0.0)),
-    ((0.139882, 0.267032, 0.102116), (0.648000, -0.430250, 0.628407), (0.0, 0.0)),
-    ((0.085673, 0.049887, 0.037469), (0.311533, -0.099246, 0.945006), (0.0, 0.0)),
-    ((0.067958, 0.051902, 0.037855), (0.503708, -0.138585, 0.852657), (0.0, 0.0)),
-    ((0.185576, 0.235020, -0.051662), (0.892178, 0.379315, -0.245125), (0.0, 0.0)),
-    ((0.183841, 0.224184, -0.073827), (0.832118, 0.410749, -0.372539), (0.0, 0.0)),
-    ((0.185571, 0.203588, -0.080897), (0.863155, 0.064455, -0.500778), (0.0, 0.0)),
-    ((-0.062667, 0.031528, 0.049052), (-0.639393, -0.482437, 0.598651), (0.0, 0.0)),
-    ((0.170707, 0.132481, -0.054793), (0.812922, -0.025910, -0.581744), (0.0, 0.0)),
-    ((0.166792, 0.103092, -0.053484), (0.787286, -0.334910, -0.517655), (0.0, 0.0)),
-    ((0.085457, 0.003200, -0.075974), (0.010041, -0.767632, -0.640767), (0.0, 0.0)),
-    ((0.083603, 0.001823, -0.050735), (-0.090304, -0.994903, -0.044710), (0.0, 0.0)),
-    ((0.188490, 0.187346, -0.002963), (0.897122, -0.049257, 0.438978), (0.0, 0.0)),
-    ((-0.019163, 0.157802, 0.056752), (-0.146092, 0.517380, 0.843165), (0.0, 0.0)),
-    ((0.006206, 0.157648, 0.059619), (-0.039979, 0.579302, 0.814112), (0.0, 0.0)),
-    ((0.006026, 0.180036, 0.045945), (0.021241, 0.593616, 0.804437), (0.0, 0.0)),
-    ((0.143107, 0.244185, 0.049499), (0.227363, -0.910977, 0.344096), (0.0, 0.0)),
-    ((0.127859, 0.251682, 0.042949), (-0.938139, -0.323313, -0.123875), (0.0, 0.0)),
-    ((0.193783, 0.184376, -0.027272), (0.992584, -0.118900, 0.025147), (0.0, 0.0)),
-    ((-0.041528, 0.006253, 0.048582), (-0.490310, -0.775536, 0.397626), (0.0, 0.0)),
-    ((0.169524, 0.158417, -0.054965), (0.768822, -0.339091, -0.542070), (0.0, 0.0)),
-    ((0.180375, 0.131745, 0.005805), (0.933287, 0.051271, 0.355358), (0.0, 0.0)),
-    ((-0.088718, 0.118624, 0.042119), (-0.743645, 0.228797, 0.628163), (0.0, 0.0)),
-    ((-0.074815, 0.098121, 0.053870), (-0.647603, -0.036225, 0.761071), (0.0, 0.0)),
-    ((0.180541, 0.172813, 0.002764), (0.577013, -0.444136, -0.685385), (0.0, 0.0)),
-    ((-0.017921, 0.013479, 0.060830), (-0.169286, -0.700674, 0.693075), (0.0, 0.0)),
-    ((-0.048222, 0.004932, -0.053688), (-0.331462, -0.925993, -0.180670), (0.0, 0.0)),
-    ((0.126326, 0.264332, 0.055186), (-0.949980, 0.173284, 0.259743), (0.0, 0.0)),
-    ((0.131245, 0.272080, 0.046733), (-0.815149, 0.563341, -0.134709), (0.0, 0.0)),
-    ((-0.070154, 0.049770, 0.050991), (-0.734642, -0.257759, 0.627552), (0.0, 0.0)),
-    ((-0.053574, 0.040318, 0.064154), (-0.731254, -0.202460, 0.651326), (0.0, 0.0)),
-    ((-0.088287, 0.104886, 0.042910), (-0.759972, -0.215491, 0.613147), (0.0, 0.0)),
-    ((-0.080041, 0.045509, 0.033606), (-0.561052, -0.409284, 0.719474), (0.0, 0.0)),
-    ((-0.018690, 0.003746, 0.052312), (0.365825, -0.908872, 0.200201), (0.0, 0.0)),
-    ((0.031817, 0.010964, 0.000734), (-0.017518, -0.999146, 0.036653), (0.0, 0.0)),
-    ((0.141546, 0.268327, 0.025189), (-0.469558, 0.795770, -0.382366), (0.0, 0.0)),
-    ((0.061242, 0.003259, 0.074862), (0.029176, -0.691031, 0.722221), (0.0, 0.0)),
-    ((0.083266, 0.006342, 0.070525), (0.666036, -0.358348, 0.654164), (0.0, 0.0)),
-    ((0.075965, 0.019598, 0.070343), (0.384747, 0.641682, 0.663442), (0.0, 0.0)),
-    ((0.136322, 0.274285, 0.125166), (0.876614, -0.429914, 0.216102), (0.0, 0.0)),
-    ((0.126773, 0.290329, 0.123555), (-0.998535, 0.031587, 0.043825), (0.0, 0.0)),
-    ((0.134723, 0.298148, 0.131908), (-0.250649, 0.187475, 0.949736), (0.0, 0.0)),
-    ((0.138377, 0.262567, -0.002045), (-0.073397, 0.980346, 0.183020), (0.0, 0.0)),
-    ((0.157256, 0.263537, 0.023208), (0.694418, 0.714866, -0.081790), (0.0, 0.0)),
-    ((0.163331, 0.259811, -0.003527), (0.529984, 0.831935, 0.164281), (0.0, 0.0)),
-    ((0.169230, 0.260381, -0.027667), (0.459212, 0.888302, -0.001099), (0.0, 0.0)),
-    ((0.088241, 0.202995, 0.018271), (-0.253578, 0.840571, 0.478652), (0.0, 0.0)),
-    ((0.059175, 0.192172, 0.025033), (-0.090121, 0.875179, 0.475265), (0.0, 0.0)),
-    ((0.157744, 0.298338, 0.098461), (0.950926, 0.279183, -0.133183), (0.0, 0.0)),
-    ((0.157002, 0.312186, 0.111292), (0.618671, 0.718497, -0.317759), (0.0, 0.0)),
-    ((-0.072094, 0.183288, -0.029006), (-0.694266, 0.706229, -0.138585), (0.0, 0.0)),
-    ((0.032954, 0.191855, 0.028036), (0.145299, 0.849818, 0.506607), (0.0, 0.0)),
-    ((-0.072090, 0.183477, -0.000886), (-0.684408, 0.721122, 0.107425), (0.0, 0.0)),
-    ((0.148212, 0.312618, 0.111964), (-0.283853, 0.785607, -0.549699), (0.0, 0.0)),
-    ((0.142314, 0.315114, 0.126595), (-0.276864, 0.796319, 0.537767), (0.0, 0.0)),
-    ((0.131986, 0.017062, -0.051962), (0.862270, 0.485427, 0.144200), (0.0, 0.0)),
-    ((0.131679, 0.012904, -0.071656), (0.789483, 0.308390, -0.530625), (0.0, 0.0)),
-    ((-0.068123, 0.180326, 0.019932), (-0.621052, 0.658681, 0.424696), (0.0, 0.0)),
-    ((0.048188, 0.099146, -0.097339), (0.592181, -0.501785, -0.630451), (0.0, 0.0)),
-    ((0.048056, 0.118081, -0.096132), (0.590442, 0.174871, -0.787866), (0.0, 0.0)),
-    ((0.156947, 0.308093, 0.118978), (0.845576, -0.006623, 0.533769), (0.0, 0.0)),
-    ((0.043673, 0.068695, -0.084137), (0.671407, -0.218696, -0.708029), (0.0, 0.0)),
-    ((0.114105, 0.068541, -0.060680), (0.399853, -0.524216, -0.751854), (0.0, 0.0)),
-    ((0.098382, 0.069702, -0.067577), (0.406537, 0.753929, -0.516007), (0.0, 0.0)),
-    ((-0.047469, 0.190299, 0.026322), (-0.431715, 0.715873, 0.548753), (0.0, 0.0)),
-    ((0.136245, 0.002737, 0.022704), (0.628986, -0.549181, 0.550188), (0.0, 0.0)),
-    ((0.134680, 0.016650, 0.022209), (0.672201, 0.461928, 0.578570), (0.0, 0.0)),
-    ((0.096649, 0.048977, -0.064885), (0.472671, -0.685446, -0.553819), (0.0, 0.0)),
-    ((0.157469, 0.123920, -0.070295), (0.602130, 0.135105, -0.786859), (0.0, 0.0)),
-    ((0.175936, 0.215886, -0.090123), (0.614795, 0.281503, -0.736717), (0.0, 0.0)),
-    ((0.172608, 0.176227, -0.089215), (0.461348, -0.548814, -0.697073), (0.0, 0.0)),
-    ((0.062707, 0.025227, 0.049498), (0.830073, 0.143437, -0.538865), (0.0, 0.0)),
-    ((0.133336, 0.004252, -0.021193), (0.814875, -0.484664, -0.317820), (0.0, 0.0)),
-    ((0.139575, 0.018566, -0.000845), (0.867244, 0.469192, -0.166478), (0.0, 0.0)),
-    ((0.138814, 0.001743, 0.001044), (0.719230, -0.692618, -0.054140), (0.0, 0.0)),
-    ((0.132720, 0.003363, -0.049639), (0.801233, -0.582659, 0.135960), (0.0, 0.0)),
-    ((0.131757, 0.003426, -0.069745), (0.683493, -0.617084, -0.389874), (0.0, 0.0)),
-    ((0.129913, 0.017444, -0.017015), (0.661519, 0.581256, -0.473769), (0.0, 0.0)),
-    ((0.057593, 0.296843, 0.045671), (0.409467, -0.038575, 0.911496), (0.0, 0.0)),
-    ((0.175881, 0.248349, -0.059872), (0.690512, 0.672506, -0.266213), (0.0, 0.0)),
-    ((0.121198, 0.029936, -0.000130), (0.725913, 0.572893, -0.380505), (0.0, 0.0)),
-    ((0.107010, 0.278551, 0.003879), (0.632313, 0.696371, -0.339427), (0.0, 0.0)),
-    ((0.056605, 0.313207, 0.041151), (0.759423, 0.530076, 0.377148), (0.0, 0.0)),
-    ((0.031894, 0.010912, -0.050097), (-0.050356, -0.984313, -0.168981), (0.0, 0.0)),
-    ((0.060004, 0.100516, 0.079747), (0.810297, -0.009003, 0.585925), (0.0, 0.0)),
-    ((0.115207, 0.046180, -0.002831), (0.943510, -0.323679, -0.070559), (0.0, 0.0)),
-    ((0.031635, 0.178501, 0.045489), (0.077334, 0.652943, 0.753441), (0.0, 0.0)),
-    ((0.031650, 0.157961, 0.058644), (0.091494, 0.589587, 0.802484), (0.0, 0.0)),
-    ((0.056617, 0.178484, 0.043171), (0.001099, 0.685171, 0.728355), (0.0, 0.0)),
-    ((0.005819, 0.204326, 0.017334), (0.100406, 0.919004, 0.381176), (0.0, 0.0)),
-    ((-0.020122, 0.204270, 0.018022), (-0.103214, 0.907712, 0.406659), (0.0, 0.0)),
-    ((0.009767, 0.194178, 0.032449), (0.094516, 0.777886, 0.621204), (0.0, 0.0)),
-    ((0.145147, 0.252244, 0.093942), (0.991394, 0.127018, 0.031129), (0.0, 0.0)),
-    ((0.055929, 0.074543, 0.075350), (0.821375, -0.207129, 0.531388), (0.0, 0.0)),
-    ((-0.022853, 0.005371, 0.027693), (0.319590, -0.941649, 0.105411), (0.0, 0.0)),
-    ((-0.008868, 0.009541, 0.011603), (0.335154, -0.915159, 0.223884), (0.0, 0.0)),
-    ((-0.015902, 0.011504, 0.029068), (0.373394, -0.917753, 0.135197), (0.0, 0.0)),
-    ((0.164100, 0.100673, 0.026288), (0.764519, -0.313517, 0.563189), (0.0, 0.0)),
-    ((0.170718, 0.128528, 0.024119), (0.835261, -0.004639, 0.549791), (0.0, 0.0)),
-    ((0.139978, 0.101518, 0.047844), (0.459212, -0.257210, 0.850246), (0.0, 0.0)),
-    ((0.151686, 0.096197, 0.038818), (0.655293, 0.212073, 0.724967), (0.0, 0.0)),
-    ((-0.039264, 0.200076, 0.016985), (-0.359630, 0.849178, 0.386700), (0.0, 0.0)),
-    ((-0.018103, 0.195017, 0.032773), (-0.086398, 0.778741, 0.621357), (0.0, 0.0)),
-    ((0.177514, 0.157968, -0.034975), (0.672048, 0.695914, -0.252968), (0.0, 0.0)),
-    ((0.031575, 0.003255, 0.069495), (-0.084140, -0.821314, 0.564196), (0.0, 0.0)),
-    ((-0.041322, 0.177862, 0.042831), (-0.463973, 0.510727, 0.723777), (0.0, 0.0)),
-    ((-0.022402, 0.183505, 0.044387), (-0.118656, 0.600085, 0.791040), (0.0, 0.0)),
-    ((-0.050249, 0.129123, 0.063223), (-0.433027, 0.397900, 0.808771), (0.0, 0.0)),
-    ((-0.042927, 0.122914, 0.070132), (-0.410382, 0.289773, 0.864620), (0.0, 0.0)),
-    ((-0.044727, 0.157217, 0.051847), (-0.343791, 0.470595, 0.812586), (0.0, 0.0)),
-    ((0.141317, 0.076609, 0.026901), (0.516861, -0.666066, 0.537736), (0.0, 0.0)),
-    ((0.159834, 0.086413, 0.021502), (0.696829, -0.518052, 0.495987), (0.0, 0.0)),
-    ((0.167929, 0.182605, 0.019207), (0.630757, 0.017487, 0.775780), (0.0, 0.0)),
-    ((0.149086, 0.165733, 0.031983), (0.469771, 0.245308, 0.847987), (0.0, 0.0)),
-    ((0.107948, 0.267535, 0.016469), (0.692251, 0.213935, 0.689169), (0.0, 0.0)),
-    ((0.150542, 0.069290, 0.001003), (0.587268, -0.787683, 0.186132), (0.0, 0.0)),
-    ((-0.018066, 0.130270, 0.077086), (-0.276406, 0.422834, 0.863002), (0.0, 0.0)),
-    ((0.006961, 0.130026, 0.082917), (-0.132267, 0.509720, 0.850093), (0.0, 0.0)),
-    ((-0.073373, 0.129411, 0.050441), (-0.552049, 0.286721, 0.782922), (0.0, 0.0)),
-    ((-0.117425, 0.065847, 0.016704), (-0.608936, 0.345744, 0.713858), (0.0, 0.0)),
-    ((0.025397, 0.199994, 0.016679), (0.299203, 0.889676, 0.344859), (0.0, 0.0)),
-    ((-0.068131, 0.155373, 0.042454), (-0.468795, 0.534501, 0.703177), (0.0, 0.0)),
-    ((0.051786, 0.002956, -0.095853), (0.337504, -0.673666, -0.657430), (0.0, 0.0)),
-    ((0.059594, 0.002300, -0.079424), (0.406446, -0.769280, -0.492904), (0.0, 0.0)),
-    ((-0.021759, 0.139863, 0.064959), (-0.783410, 0.348704, 0.514420), (0.0, 0.0)),
-    ((0.024447, 0.118896, 0.094110), (-0.156407, 0.166631, 0.973510), (0.0, 0.0)),
-    ((0.032976, 0.133498, 0.078247), (0.252022, 0.579608, 0.774926), (0.0, 0.0)),
-    ((0.034958, 0.140047, 0.065358), (0.919340, 0.275124, 0.281167), (0.0, 0.0)),
-    ((0.034895, 0.104030, 0.095162), (0.104953, 0.246925, 0.963317), (0.0, 0.0)),
-    ((0.111584, 0.158001, 0.051726), (0.198553, 0.307962, 0.930418), (0.0, 0.0)),
-    ((0.136366, 0.154520, 0.043799), (0.419935, 0.259682, 0.869594), (0.0, 0.0)),
-    ((0.136308, 0.181109, 0.032734), (0.289438, 0.258126, 0.921720), (0.0, 0.0)),
-    ((0.112174, 0.181770, 0.040263), (0.167119, 0.521439, 0.836726), (0.0, 0.0)),
-    ((-0.041482, 0.102632, 0.075352), (-0.447737, 0.117008, 0.886441), (0.0, 0.0)),
-    ((-0.060248, 0.106889, 0.065336), (-0.507004, 0.060244, 0.859798), (0.0, 0.0)),
-    ((0.030618, 0.022545, 0.074366), (0.018769, -0.241890, 0.970092), (0.0, 0.0)),
-    ((0.004525, 0.022042, 0.072312), (-0.168798, -0.483596, 0.858821), (0.0, 0.0)),
-    ((0.082121, 0.194006, 0.031946), (-0.114689, 0.754173, 0.646535), (0.0, 0.0)),
-    ((0.112172, 0.192519, 0.031715), (-0.007355, 0.525468, 0.850765), (0.0, 0.0)),
-    ((0.081486, 0.181172, 0.042867), (-0.016297, 0.586200, 0.809961), (0.0, 0.0)),
-    ((0.046934, 0.105043, 0.092301), (0.407575, 0.615314, 0.674703), (0.0, 0.0)),
-    ((0.115949, 0.069146, 0.033521), (0.293222, -0.546098, 0.784692), (0.0, 0.0)),
-    ((0.112964, 0.084676, 0.047398), (0.138890, -0.509140, 0.849361), (0.0, 0.0)),
-    ((0.110681, 0.103180, 0.055878), (0.145116, -0.246590, 0.958159), (0.0, 0.0)),
-    ((0.130613, 0.082401, 0.042545), (0.368633, -0.563555, 0.739219), (0.0, 0.0)),
-    ((0.112490, 0.127165, 0.057744), (0.204566, 0.063112, 0.976806), (0.0, 0.0)),
-    ((-0.037464, 0.031240, 0.067501), (-0.300180, -0.460799, 0.835170), (0.0, 0.0)),
-    ((0.037950, 0.074431, 0.091492), (0.237373, -0.277627, 0.930876), (0.0, 0.0)),
-    ((-0.020093, 0.028291, 0.070178), (-0.205451, -0.451155, 0.868465), (0.0, 0.0)),
-    ((-0.019639, 0.048937, 0.078640), (-0.225959, -0.263497, 0.937803), (0.0, 0.0)),
-    ((-0.018810, 0.075073, 0.083666), (-0.267586, -0.129093, 0.954833), (0.0, 0.0)),
-    ((-0.018874, 0.099695, 0.085506), (-0.317972, 0.099490, 0.942839), (0.0, 0.0)),
-    ((0.049712, 0.009447, -0.024444), (-0.117924, -0.992920, -0.012909), (0.0, 0.0)),
-    ((0.008211, 0.003764, 0.057229), (-0.105869, -0.988891, 0.104190), (0.0, 0.0)),
-    ((0.006558, 0.047125, 0.083405), (-0.085757, -0.291971, 0.952544), (0.0, 0.0)),
-    ((0.008089, 0.075909, 0.090156), (-0.158086, -0.196997, 0.967559), (0.0, 0.0)),
-    ((0.000641, 0.102775, 0.091500), (-0.252266, 0.136692, 0.957945), (0.0, 0.0)),
-    ((0.013879, 0.100570, 0.094246), (-0.140782, 0.106357, 0.984283), (0.0, 0.0)),
-    ((0.034383, 0.047173, 0.081747), (0.226020, -0.313944, 0.922117), (0.0, 0.0)),
-    ((0.031169, 0.081507, 0.095003), (0.060518, -0.228401, 0.971648), (0.0, 0.0)),
-    ((0.032223, 0.005085, 0.055756), (0.012207, -0.931730, -0.362896), (0.0, 0.0)),
-    ((-0.009096, 0.009110, -0.034418), (0.281198, -0.916379, -0.284829), (0.0, 0.0)),
-    ((0.006050, 0.010349, -0.024460), (0.148381, -0.986297, -0.071718), (0.0, 0.0)),
-    ((-0.018174, 0.002633, -0.024967), (0.213050, -0.960814, -0.177282), (0.0, 0.0)),
-    ((0.162091, 0.164494, -0.078223), (0.321299, -0.804895, -0.498886), (0.0, 0.0)),
-    ((-0.019192, 0.003081, -0.076842), (-0.148839, -0.656026, -0.739891), (0.0, 0.0)),
-    ((-0.039439, 0.004188, -0.075087), (-0.364269, -0.741417, -0.563524), (0.0, 0.0)),
-    ((-0.047355, 0.004087, 0.002966), (-0.056124, -0.998138, 0.022706), (0.0, 0.0)),
-    ((-0.068313, 0.005292, -0.026641), (-0.227577, -0.964538, -0.133610), (0.0, 0.0)),
-    ((-0.048217, 0.003848, -0.028585), (-0.060091, -0.997803, -0.026826), (0.0, 0.0)),
-    ((-0.018478, 0.010837, -0.050191), (0.345134, -0.934507, -0.086642), (0.0, 0.0)),
-    ((0.006196, 0.011559, -0.050769), (0.026032, -0.991974, -0.123508), (0.0, 0.0)),
-    ((0.032823, 0.003122, -0.095569), (-0.548082, -0.614521, -0.567370), (0.0, 0.0)),
-    ((0.032269, 0.002348, -0.084979), (-0.282662, -0.859218, -0.426405), (0.0, 0.0)),
-    ((-0.028212, 0.004269, -0.050097), (0.038118, -0.907193, 0.418928), (0.0, 0.0)),
-    ((-0.004603, 0.005297, -0.008560), (0.360210, -0.923734, 0.130070), (0.0, 0.0)),
-    ((-0.005811, 0.003941, -0.015149), (0.312815, -0.949522, -0.022401), (0.0, 0.0)),
-    ((0.005941, 0.011021, 0.000920), (0.149022, -0.983978, 0.097568), (0.0, 0.0)),
-    ((0.006139, 0.012645, 0.026355), (0.014252, -0.999817, -0.010407), (0.0, 0.0)),
-    ((-0.017659, 0.003494, 0.001710), (0.216346, -0.965758, 0.143132), (0.0, 0.0)),
-    ((0.031676, 0.010385, -0.024686), (-0.028016, -0.999573, -0.002869), (0.0, 0.0)),
-    ((0.007451, 0.002840, -0.083950), (-0.255440, -0.294290, -0.920927), (0.0, 0.0)),
-    ((-0.078533, 0.009054, -0.031549), (-0.308603, -0.892544, -0.328745), (0.0, 0.0)),
-    ((0.006313, 0.011105, 0.044874), (-0.005097, -0.954558, -0.297891), (0.0, 0.0)),
-    ((0.150411, 0.069360, -0.026199), (0.586657, -0.792596, -0.166082), (0.0, 0.0)),
-    ((0.159780, 0.084429, -0.044648), (0.674825, -0.603168, -0.425153), (0.0, 0.0)),
-    ((0.135482, 0.060018, 0.000416), (0.504898, -0.842189, 0.189001), (0.0, 0.0)),
-    ((0.134381, 0.058999, -0.024781), (0.495010, -0.859462, -0.127476), (0.0, 0.0)),
-    ((0.141399, 0.075392, -0.053164), (0.503830, -0.682150, -0.529893), (0.0, 0.0)),
-    ((0.111759, 0.000864, 0.001198), (0.028871, -0.999573, 0.000763), (0.0, 0.0)),
-    ((0.130074, 0.081848, -0.068914), (0.288034, -0.664693, -0.689322), (0.0, 0.0)),
-    ((0.114553, 0.000667, -0.023951), (0.189276, -0.981811, -0.012787), (0.0, 0.0)),
-    ((0.112628, 0.001581, -0.050363), (0.062319, -0.997803, -0.021760), (0.0, 0.0)),
-    ((0.085949, 0.002292, 0.025922), (-0.311197, -0.920591, 0.235847), (0.0, 0.0)),
-    ((0.084725, 0.002355, 0.001034), (0.002258, -0.999512, -0.030976), (0.0, 0.0)),
-    ((0.061304, 0.006303, 0.052355), (-0.059511, -0.662862, -0.746330), (0.0, 0.0)),
-    ((0.067017, 0.001126, -0.001682), (0.084170, -0.991394, -0.100162), (0.0, 0.0)),
-];
-pub const INDICES_0: &[usize] = &[
-    0, 1, 2,
-    3, 4, 5,
-    6, 7, 8,
-    9, 10, 11,
-    12, 13, 14,
-    12, 15, 1,
-    16, 13, 17,
-    18, 19, 20,
-    21, 22, 23,
-    24, 25, 26,
-    1, 20, 27,
-    28, 12, 14,
-    16, 14, 13,
-    29, 30, 31,
-    32, 33, 34,
-    35, 16, 22,
-    36, 37, 38,
-    39, 40, 3,
-    41, 42, 43,
-    44, 28, 35,
-    45, 46, 47,
-    48, 45, 47,
-    47, 49, 50,
-    49, 51, 50,
-    37, 52, 38,
-    53, 12, 28,
-    22, 16, 17,
-    54, 55, 56,
-    57, 58, 59,
-    12, 1, 0,
-    60, 61, 57,
-    62, 15, 12,
-    63, 64, 65,
-    66, 32, 67,
-    68, 69, 70,
-    71, 60, 57,
-    52, 72, 38,
-    73, 74, 75,
-    76, 77, 78,
-    76, 79, 77,
-    76, 80, 79,
-    81, 82, 83,
-    81, 84, 82,
-    85, 76, 78,
-    62, 12, 53,
-    86, 57, 59,
-    87, 88, 89,
-    62, 90, 15,
-    58, 91, 17,
-    82, 84, 92,
-    83, 82, 92,
-    93, 94, 95,
-    72, 96, 38,
-    13, 0, 2,
-    97, 98, 99,
-    42, 100, 43,
-    101, 102, 103,
-    104, 57, 71,
-    91, 58, 105,
-    12, 0, 13,
-    106, 63, 65,
-    107, 64, 63,
-    108, 109, 110,
-    111, 85, 112,
-    111, 113, 76,
-    85, 111, 76,
-    114, 80, 76,
-    113, 114, 76,
-    114, 115, 80,
-    114, 116, 117,
-    115, 114, 117,
-    74, 118, 75,
-    106, 107, 63,
-    44, 53, 28,
-    112, 119, 111,
-    114, 120, 116,
-    93, 95, 121,
-    122, 123, 124,
-    125, 107, 106,
-    126, 125, 106,
-    125, 119, 107,
-    127, 111, 119,
-    128, 127, 119,
-    127, 129, 113,
-    111, 127, 113,
-    129, 114, 113,
-    129, 130, 114,
-    114, 2, 120,
-    120, 2, 116,
-    131, 88, 132,
-    128, 119, 125,
-    133, 134, 135,
-    136, 2, 114,
-    130, 136, 114,
-    123, 137, 124,
-    35, 28, 16,
-    28, 14, 16,
-    88, 131, 89,
-    59, 58, 17,
-    138, 58, 57,
-    130, 2, 136,
-    139, 125, 126,
-    140, 141, 66,
-    142, 143, 144,
-    33, 145, 34,
-    64, 146, 147,
-    148, 125, 139,
-    149, 148, 139,
-    150, 130, 129,
-    151, 150, 129,
-    13, 2, 130,
-    150, 13, 130,
-    152, 60, 153,
-    35, 21, 154,
-    149, 155, 148,
-    156, 157, 158,
-    22, 17, 91,
-    44, 35, 154,
-    95, 159, 160,
-    161, 162, 163,
-    164, 165, 166,
-    167, 161, 163,
-    21, 35, 22,
-    168, 13, 150,
-    61, 138, 57,
-    84, 87, 89,
-    169, 6, 170,
-    92, 84, 89,
-    155, 171, 148,
-    172, 159, 95,
-    149, 83, 155,
-    83, 92, 171,
-    155, 83, 171,
-    92, 89, 173,
-    171, 92, 173,
-    89, 131, 174,
-    173, 89, 174,
-    132, 175, 174,
-    131, 132, 174,
-    86, 59, 150,
-    59, 168, 150,
-    17, 13, 168,
-    59, 17, 168,
-    176, 177, 178,
-    179, 180, 73,
-    88, 71, 132,
-    181, 182, 183,
-    118, 181, 183,
-    184, 185, 186,
-    81, 180, 187,
-    188, 189, 190,
-    191, 169, 192,
-    193, 194, 137,
-    187, 195, 84,
-    142, 134, 196,
-    197, 198, 199,
-    66, 200, 32,
-    153, 60, 88,
-    201, 45, 79,
-    49, 46, 45,
-    167, 181, 199,
-    49, 45, 201,
-    202, 203, 187,
-    142, 196, 204,
-    205, 137, 206,
-    140, 66, 67,
-    207, 201, 79,
-    207, 49, 201,
-    179, 75, 208,
-    209, 207, 49,
-    137, 123, 122,
-    210, 211, 212,
-    115, 207, 79,
-    18, 49, 209,
-    34, 51, 49,
-    18, 34, 49,
-    213, 193, 194,
-    213, 193, 137,
-    214, 215, 216,
-    115, 117, 207,
-    138, 217, 218,
-    106, 65, 219,
-    205, 206, 160,
-    207, 117, 209,
-    117, 18, 209,
-    220, 221, 141,
-    208, 187, 180,
-    152, 61, 60,
-    187, 84, 81,
-    205, 213, 137,
-    198, 161, 167,
-    212, 61, 152,
-    199, 198, 167,
-    211, 105, 218,
-    212, 152, 153,
-    212, 217, 138,
-    222, 212, 153,
-    223, 210, 222,
-    117, 19, 18,
-    196, 224, 225,
-    185, 142, 204,
-    210, 212, 222,
-    226, 153, 88,
-    196, 134, 133,
-    212, 211, 217,
-    160, 137, 95,
-    87, 226, 88,
-    116, 19, 117,
-    227, 56, 55,
-    228, 229, 230,
-    84, 195, 226,
-    10, 231, 11,
-    208, 202, 187,
-    232, 30, 29,
-    133, 98, 224,
-    163, 182, 181,
-    167, 163, 181,
-    205, 56, 213,
-    200, 233, 32,
-    234, 223, 202,
-    235, 236, 237,
-    73, 75, 179,
-    238, 239, 210,
-    223, 238, 210,
-    226, 87, 84,
-    225, 238, 240,
-    210, 239, 211,
-    238, 223, 234,
-    204, 196, 225,
-    134, 144, 135,
-    74, 181, 118,
-    195, 187, 226,
-    115, 117, 79,
-    183, 234, 75,
-    239, 97, 211,
-    183, 238, 234,
-    118, 183, 75,
-    56, 227, 213,
-    226, 203, 153,
-    241, 242, 243,
-    244, 245, 190,
-    223, 222, 202,
-    211, 218, 217,
-    164, 165, 96,
-    206, 137, 160,
-    213, 227, 246,
-    240, 238, 183,
-    138, 105, 58,
-    185, 204, 186,
-    247, 189, 215,
-    60, 71, 88,
-    248, 232, 29,
-    218, 105, 138,
-    179, 208, 180,
-    249, 141, 140,
-    222, 153, 203,
-    137, 122, 200,
-    160, 159, 205,
-    187, 203, 226,
-    234, 202, 208,
-    75, 234, 208,
-    212, 138, 61,
-    250, 105, 211,
-    95, 200, 121,
-    95, 137, 200,
-    214, 161, 198,
-    215, 214, 198,
-    94, 251, 172,
-    202, 222, 203,
-    79, 80, 115,
-    101, 252, 102,
-    72, 164, 96,
-    200, 221, 93,
-    253, 4, 254,
-    255, 256, 177,
-    257, 236, 235,
-    39, 3, 236,
-    258, 176, 259,
-    50, 260, 261,
-    39, 236, 257,
-    69, 262, 70,
-    263, 188, 245,
-    96, 165, 166,
-    264, 52, 265,
-    25, 266, 267,
-    248, 29, 268,
-    269, 270, 271,
-    43, 272, 273,
-    274, 248, 268,
-    20, 140, 67,
-    275, 244, 190,
-    276, 68, 164,
-    56, 205, 159,
-    277, 96, 166,
-    31, 100, 42,
-    278, 142, 185,
-    110, 184, 216,
-    184, 279, 185,
-    166, 165, 280,
-    281, 244, 275,
-    274, 68, 248,
-    142, 144, 134,
-    68, 70, 248,
-    282, 73, 180,
-    196, 133, 224,
-    70, 260, 248,
-    283, 284, 273,
-    285, 286, 108,
-    184, 110, 279,
-    272, 283, 273,
-    248, 260, 232,
-    257, 40, 39,
-    235, 284, 283,
-    165, 164, 268,
-    237, 236, 272,
-    164, 274, 268,
-    279, 287, 185,
-    287, 278, 185,
-    236, 235, 283,
-    236, 283, 272,
-    285, 110, 288,
-    281, 275, 289,
-    280, 165, 268,
-    63, 64, 147,
-    290, 252, 101,
-    5, 4, 253,
-    285, 288, 188,
-    93, 121, 200,
-    284, 291, 43,
-    273, 284, 43,
-    292, 293, 294,
-    263, 285, 188,
-    110, 216, 288,
-    224, 239, 238,
-    225, 224, 238,
-    224, 98, 239,
-    98, 97, 239,
-    32, 34, 18,
-    295, 296, 40,
-    244, 263, 245,
-    296, 297, 40,
-    8, 170, 6,
-    295, 297, 296,
-    20, 67, 32,
-    298, 299, 300,
-    33, 158, 145,
-    51, 34, 158,
-    233, 33, 32,
-    5, 291, 284,
-    163, 183, 182,
-    163, 240, 183,
-    163, 225, 240,
-    186, 163, 162,
-    301, 232, 157,
-    157, 232, 260,
-    204, 225, 163,
-    186, 204, 163,
-    145, 158, 34,
-    302, 140, 90,
-    140, 20, 90,
-    33, 156, 158,
-    20, 15, 90,
-    302, 90, 303,
-    20, 32, 18,
-    40, 304, 3,
-    15, 20, 1,
-    5, 284, 235,
-    3, 5, 235,
-    194, 124, 137,
-    300, 301, 156,
-    40, 305, 304,
-    27, 19, 116,
-    291, 5, 253,
-    51, 157, 50,
-    158, 157, 51,
-    99, 306, 211,
-    307, 90, 62,
-    98, 306, 99,
-    20, 19, 27,
-    300, 30, 301,
-    30, 232, 301,
-    133, 308, 98,
-    308, 306, 98,
-    309, 40, 310,
-    306, 250, 211,
-    2, 27, 116,
-    1, 27, 2,
-    135, 308, 133,
-    309, 305, 40,
-    310, 40, 297,
-    144, 308, 135,
-    300, 156, 298,
-    156, 301, 157,
-    311, 63, 147,
-    298, 156, 33,
-    21, 250, 306,
-    21, 105, 250,
-    57, 132, 71,
-    298, 33, 233,
-    291, 299, 298,
-    154, 21, 306,
-    308, 154, 306,
-    309, 310, 305,
-    21, 23, 105,
-    312, 65, 311,
-    154, 308, 313,
-    23, 91, 105,
-    86, 151, 132,
-    132, 151, 175,
-    23, 22, 91,
-    211, 97, 99,
-    314, 315, 127,
-    305, 310, 316,
-    221, 200, 66,
-    148, 173, 317,
-    318, 119, 319,
-    313, 320, 154,
-    94, 93, 124,
-    169, 321, 322,
-    175, 129, 315,
-    291, 253, 299,
-    148, 171, 173,
-    127, 174, 314,
-    314, 174, 175,
-    124, 251, 94,
-    125, 148, 128,
-    148, 317, 128,
-    49, 47, 46,
-    323, 119, 318,
-    64, 323, 318,
-    77, 324, 78,
-    325, 47, 50,
-    326, 48, 69,
-    173, 174, 127,
-    48, 262, 69,
-    175, 151, 129,
-    64, 107, 323,
-    78, 324, 265,
-    315, 129, 127,
-    259, 230, 327,
-    48, 47, 325,
-    262, 48, 325,
-    314, 175, 315,
-    327, 258, 259,
-    328, 329, 330,
-    312, 311, 331,
-    52, 264, 72,
-    332, 312, 331,
-    317, 173, 127,
-    94, 172, 95,
-    86, 132, 57,
-    325, 50, 261,
-    333, 329, 328,
-    324, 77, 264,
-    251, 334, 172,
-    335, 157, 260,
-    128, 317, 127,
-    333, 336, 329,
-    323, 107, 119,
-    264, 77, 276,
-    200, 337, 233,
-    333, 147, 336,
-    338, 213, 334,
-    339, 155, 24,
-    50, 335, 260,
-    9, 340, 256,
-    220, 249, 43,
-    147, 333, 328,
-    147, 341, 336,
-    325, 261, 262,
-    265, 324, 264,
-    337, 200, 122,
-    147, 146, 341,
-    297, 316, 310,
-    40, 304, 297,
-    297, 304, 316,
-    316, 304, 305,
-    295, 40, 297,
-    342, 343, 344,
-    295, 257, 40,
-    177, 256, 178,
-    257, 304, 40,
-    345, 346, 347,
-    235, 257, 348,
-    257, 3, 304,
-    235, 254, 257,
-    257, 254, 3,
-    254, 4, 304,
-    3, 254, 304,
-    338, 194, 213,
-    6, 293, 7,
-    6, 294, 293,
-    230, 178, 349,
-    228, 230, 349,
-    178, 256, 340,
-    349, 178, 340,
-    345, 347, 307,
-    272, 253, 235,
-    235, 253, 254,
-    262, 261, 260,
-    350, 351, 352,
-    327, 6, 258,
-    70, 262, 260,
-    341, 329, 336,
-    255, 231, 10,
-    9, 255, 10,
-    271, 270, 342,
-    6, 327, 230,
-    353, 354, 242,
-    355, 353, 242,
-    354, 243, 242,
-    356, 353, 355,
-    43, 100, 253,
-    272, 43, 253,
-    100, 299, 253,
-    124, 334, 137,
-    124, 194, 334,
-    357, 320, 358,
-    346, 357, 358,
-    164, 276, 359,
-    251, 124, 334,
-    360, 292, 229,
-    256, 255, 9,
-    320, 361, 362,
-    358, 320, 362,
-    363, 364, 365,
-    3, 235, 236,
-    366, 367, 353,
-    368, 369, 243,
-    370, 124, 93,
-    327, 230, 229,
-    336, 169, 322,
-    336, 341, 169,
-    341, 192, 169,
-    371, 36, 367,
-    366, 371, 367,
-    36, 38, 353,
-    367, 36, 353,
-    38, 96, 354,
-    353, 38, 354,
-    96, 277, 354,
-    277, 166, 354,
-    166, 243, 354,
-    166, 368, 243,
-    166, 268, 368,
-    268, 29, 368,
-    29, 42, 368,
-    29, 31, 42,
-    31, 30, 100,
-    30, 300, 100,
-    300, 299, 100,
-    233, 337, 370,
-    337, 122, 124,
-    370, 337, 124,
-    311, 65, 63,
-    176, 178, 230,
-    259, 176, 230,
-    166, 280, 268,
-    72, 264, 276,
-    249, 220, 141,
-    270, 372, 373,
-    374, 278, 279,
-    375, 162, 214,
-    329, 336, 322,
-    376, 345, 307,
-    270, 269, 372,
-    320, 365, 361,
-    143, 142, 374,
-    134, 144, 374,
-    377, 144, 374,
-    378, 139, 379,
-    144, 377, 365,
-    229, 294, 6,
-    154, 320, 357,
-    346, 358, 347,
-    144, 320, 313,
-    365, 377, 374,
-    269, 380, 372,
-    44, 154, 346,
-    144, 143, 374,
-    298, 43, 291,
-    90, 307, 303,
-    298, 220, 43,
-    320, 144, 365,
-    220, 298, 233,
-    373, 381, 382,
-    370, 220, 233,
-    382, 383, 343,
-    381, 384, 382,
-    307, 347, 342,
-    385, 386, 387,
-    388, 385, 387,
-    344, 343, 11,
-    205, 54, 56,
-    364, 374, 389,
-    361, 365, 362,
-    362, 365, 269,
-    53, 346, 345,
-    307, 342, 344,
-    303, 307, 344,
-    374, 390, 389,
-    62, 376, 307,
-    391, 392, 360,
-    270, 343, 342,
-    347, 271, 342,
-    292, 294, 229,
-    357, 346, 154,
-    142, 278, 374,
-    347, 358, 271,
-    327, 229, 6,
-    302, 393, 394,
-    249, 302, 394,
-    395, 231, 388,
-    231, 385, 388,
-    53, 345, 376,
-    382, 9, 11,
-    375, 216, 184,
-    62, 53, 376,
-    302, 249, 140,
-    395, 388, 394,
-    393, 395, 394,
-    343, 382, 11,
-    334, 194, 338,
-    162, 161, 214,
-    308, 144, 313,
-    53, 44, 346,
-    374, 363, 365,
-    3, 304, 4,
-    169, 170, 321,
-    358, 362, 271,
-    365, 364, 372,
-    269, 365, 372,
-    270, 373, 343,
-    375, 184, 186,
-    162, 375, 186,
-    214, 216, 375,
-    26, 25, 267,
-    373, 382, 343,
-    303, 393, 302,
-    393, 303, 395,
-    303, 344, 395,
-    11, 231, 395,
-    344, 11, 395,
-    396, 9, 382,
-    397, 398, 176,
-    340, 256, 177,
-    215, 188, 216,
-    188, 288, 216,
-    177, 399, 255,
-    370, 93, 221,
-    400, 398, 397,
-    366, 400, 397,
-    400, 355, 398,
-    366, 397, 192,
-    401, 385, 231,
-    369, 368, 387,
-    176, 399, 177,
-    364, 363, 374,
-    374, 279, 390,
-    215, 198, 247,
-    190, 245, 188,
-    244, 286, 285,
-    263, 244, 285,
-    389, 279, 110,
-    109, 389, 110,
-    279, 389, 390,
-    191, 258, 6,
-    241, 242, 386,
-    366, 355, 400,
-    42, 41, 387,
-    386, 243, 369,
-    321, 329, 322,
-    244, 286, 108,
-    244, 402, 286,
-    286, 402, 108,
-    220, 370, 221,
-    368, 42, 387,
-    278, 287, 279,
-    394, 388, 387,
-    403, 228, 349,
-    281, 404, 402,
-    244, 281, 402,
-    402, 404, 108,
-    404, 405, 108,
-    405, 406, 109,
-    108, 405, 109,
-    407, 389, 109,
-    406, 407, 109,
-    407, 372, 389,
-    372, 364, 389,
-    397, 176, 258,
-    192, 397, 258,
-    241, 243, 386,
-    386, 369, 387,
-    242, 241, 355,
-    41, 43, 249,
-    150, 151, 86,
-    290, 408, 252,
-    192, 258, 191,
-    176, 398, 399,
-    41, 249, 394,
-    409, 392, 281,
-    289, 409, 281,
-    392, 404, 281,
-    404, 392, 405,
-    392, 410, 405,
-    410, 411, 406,
-    405, 410, 406,
-    411, 412, 407,
-    406, 411, 407,
-    373, 372, 407,
-    412, 373, 407,
-    362, 269, 271,
-    387, 41, 394,
-    79, 276, 77,
-    411, 413, 412,
-    413, 373, 412,
-    386, 385, 401,
-    241, 386, 401,
-    276, 164, 72,
-    384, 381, 373,
-    413, 384, 373,
-    246, 227, 55,
-    54, 246, 55,
-    391, 414, 410,
-    392, 391, 410,
-    410, 414, 411,
-    414, 403, 411,
-    403, 415, 411,
-    415, 384, 413,
-    411, 415, 413,
-    355, 401, 399,
-    399, 401, 255,
-    355, 241, 401,
-    330, 329, 321,
-    355, 399, 398,
-    379, 266, 25,
-    213, 246, 54,
-    205, 213, 54,
-    255, 401, 231,
-    409, 416, 360,
-    191, 6, 169,
-    331, 311, 147,
-    353, 355, 366,
-    331, 147, 328,
-    360, 229, 391,
-    228, 414, 391,
-    228, 403, 414,
-    403, 349, 414,
-    396, 384, 415,
-    403, 396, 415,
-    334, 205, 159,
-    205, 334, 213,
-    382, 384, 396,
-    247, 198, 197,
-    159, 172, 334,
-    229, 228, 391,
-    108, 110, 285,
-    340, 396, 403,
-    349, 340, 403,
-    340, 384, 396,
-    340, 9, 396,
-    149, 139, 378,
-    417, 418, 419,
-    276, 79, 420,
-    180, 81, 421,
-    422, 180, 421,
-    423, 424, 425,
-    417, 426, 418,
-    426, 427, 418,
-    428, 149, 378,
-    189, 424, 423,
-    26, 408, 24,
-    428, 378, 429,
-    189, 423, 190,
-    282, 430, 426,
-    164, 68, 274,
-    190, 350, 275,
-    335, 50, 157,
-    431, 432, 418,
-    433, 290, 434,
-    190, 435, 350,
-    424, 282, 425,
-    352, 433, 434,
-    424, 73, 282,
-    282, 430, 419,
-    429, 378, 379,
-    282, 180, 422,
-    419, 426, 417,
-    339, 24, 436,
-    425, 282, 419,
-    437, 83, 149,
-    197, 73, 438,
-    149, 428, 429,
-    352, 434, 439,
-    289, 350, 281,
-    276, 420, 68,
-    68, 420, 69,
-    281, 352, 439,
-    197, 438, 424,
-    141, 221, 66,
-    439, 101, 103,
-    215, 189, 188,
-    433, 436, 290,
-    69, 420, 326,
-    74, 73, 197,
-    103, 102, 327,
-    275, 350, 289,
-    409, 289, 439,
-    427, 83, 155,
-    416, 409, 103,
-    348, 237, 235,
-    379, 126, 106,
-    371, 440, 36,
-    36, 440, 37,
-    37, 441, 52,
-    52, 441, 265,
-    427, 436, 418,
-    420, 48, 326,
-    371, 442, 440,
-    442, 443, 440,
-    423, 435, 190,
-    366, 442, 371,
-    444, 265, 441,
-    440, 441, 37,
-    435, 431, 433,
-    440, 444, 441,
-    201, 420, 79,
-    420, 201, 48,
-    74, 199, 181,
-    83, 437, 421,
-    350, 435, 351,
-    192, 341, 366,
-    366, 341, 442,
-    434, 290, 101,
-    282, 422, 421,
-    444, 78, 265,
-    436, 408, 290,
-    409, 439, 103,
-    430, 426, 419,
-    443, 444, 440,
-    379, 106, 266,
-    341, 146, 442,
-    442, 146, 443,
-    445, 330, 321,
-    170, 445, 321,
-    444, 446, 78,
-    199, 74, 197,
-    445, 328, 330,
-    64, 443, 146,
-    64, 444, 443,
-    447, 328, 445,
-    438, 73, 424,
-    64, 318, 444,
-    352, 351, 433,
-    237, 272, 235,
-    360, 392, 409,
-    7, 170, 8,
-    106, 219, 266,
-    318, 112, 444,
-    444, 112, 446,
-    446, 112, 78,
-    432, 419, 418,
-    448, 331, 328,
-    447, 448, 328,
-    435, 419, 432,
-    112, 85, 78,
-    431, 435, 432,
-    449, 170, 7,
-    433, 431, 418,
-    450, 445, 170,
-    449, 450, 170,
-    83, 421, 81,
-    379, 139, 126,
-    282, 421, 430,
-    281, 350, 352,
-    292, 7, 293,
-    448, 332, 331,
-    434, 101, 439,
-    436, 24, 408,
-    219, 332, 448,
-    247, 424, 189,
-    427, 155, 339,
-    437, 149, 429,
-    451, 7, 292,
-    332, 219, 312,
-    191, 449, 7,
-    451, 191, 7,
-    219, 65, 312,
-    451, 327, 7,
-    327, 191, 451,
-    191, 102, 449,
-    201, 45, 48,
-    319, 112, 318,
-    119, 112, 319,
-    102, 191, 327,
-    197, 424, 247,
-    289, 281, 439,
-    267, 219, 448,
-    267, 266, 219,
-    418, 436, 433,
-    351, 435, 433,
-    416, 451, 292,
-    360, 416, 292,
-    103, 327, 451,
-    416, 103, 451,
-    427, 339, 436,
-    26, 450, 452,
-];
-pub const COUNT_0: usize = 908;
@@ -0,0 +1,380 @@
+use {
+    cgmath::{Vector3, vec3},
+    crate::aabb::AaBb,
+    crate::ray::Ray
+};
+
+const BIN_COUNT: usize = 16;
+const MAX_LEAF_SIZE: usize = 4;
+const MAX_DEPTH: usize = 60;
+const TRAVERSAL_COST: f32 = 1.0;
+const INTERSECTION_COST: f32 = 1.0;
+
+#[derive(Clone, Copy)]
+struct BvhNode {
+    aabb: AaBb,
+    // for leaves it is an offset into primitive ids, for inner nodes it is an index of the
+    // second child (the first child is always stored right after its parent)
+    offset: usize,
+    // zero for inner nodes
+    count: usize,
+    axis: usize
+}
+
+#[derive(Clone, Copy)]
+struct BuildPrimitive {
+    id: usize,
+    aabb: AaBb,
+    centroid: Vector3<f32>
+}
+
+#[derive(Clone, Copy)]
+struct Bin {
+    aabb: Option<AaBb>,
+    count: usize
+}
+
+/// Bounding volume hierarchy built with binned surface area heuristic.
+/// Nodes are stored flattened in depth-first order
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    primitive_ids: Vec<usize>
+}
+
+fn union(a: Option<AaBb>, b: &AaBb) -> Option<AaBb> {
+    Some(a.map_or(*b, |a| a.union(b)))
+}
+
+impl Bvh {
+    pub fn build(primitives: &[(usize, AaBb)]) -> Self {
+        let mut build_primitives = primitives
+            .iter()
+            .map(|(id, aabb)| BuildPrimitive { id: *id, aabb: *aabb, centroid: aabb.centroid() })
+            .collect::<Vec<_>>();
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(primitives.len() * 2),
+            primitive_ids: Vec::with_capacity(primitives.len())
+        };
+        if !build_primitives.is_empty() {
+            bvh.build_recursive(&mut build_primitives, 0);
+        }
+        bvh
+    }
+
+    pub fn bounds(&self) -> Option<AaBb> {
+        self.nodes.first().map(|node| node.aabb)
+    }
+
+    fn push_leaf(&mut self, aabb: AaBb, primitives: &[BuildPrimitive]) {
+        self.nodes.push(BvhNode {
+            aabb,
+            offset: self.primitive_ids.len(),
+            count: primitives.len(),
+            axis: 0
+        });
+        self.primitive_ids.extend(primitives.iter().map(|p| p.id));
+    }
+
+    fn build_recursive(&mut self, primitives: &mut [BuildPrimitive], depth: usize) {
+        let aabb = primitives[1..]
+            .iter()
+            .fold(primitives[0].aabb, |acc, p| acc.union(&p.aabb));
+
+        // traversal stack is fixed-size, so degenerate inputs end up in bigger leaves
+        if primitives.len() == 1 || depth >= MAX_DEPTH {
+            self.push_leaf(aabb, primitives);
+            return;
+        }
+
+        let (centroid_min, centroid_max) = primitives[1..].iter().fold(
+            (primitives[0].centroid, primitives[0].centroid),
+            |(min, max), p| (
+                vec3(min.x.min(p.centroid.x), min.y.min(p.centroid.y), min.z.min(p.centroid.z)),
+                vec3(max.x.max(p.centroid.x), max.y.max(p.centroid.y), max.z.max(p.centroid.z))
+            )
+        );
+        let extent = centroid_max - centroid_min;
+        let axis = if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
+        } else {
+            2
+        };
+
+        let split = if extent[axis] <= 0.0 {
+            // all centroids coincide, so there is nothing to gain from SAH
+            if primitives.len() <= MAX_LEAF_SIZE {
+                self.push_leaf(aabb, primitives);
+                return;
+            }
+            primitives.len() / 2
+        } else {
+            let bin_of = |p: &BuildPrimitive| {
+                let relative = (p.centroid[axis] - centroid_min[axis]) / extent[axis];
+                ((relative * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1)
+            };
+
+            let mut bins = [Bin { aabb: None, count: 0 }; BIN_COUNT];
+            for p in primitives.iter() {
+                let bin = &mut bins[bin_of(p)];
+                bin.aabb = union(bin.aabb, &p.aabb);
+                bin.count += 1;
+            }
+
+            // sweep from the right to the left to know the cost of everything past each split plane
+            let mut right_areas = [0.0; BIN_COUNT];
+            let mut right_counts = [0; BIN_COUNT];
+            let (mut right_aabb, mut right_count) = (None, 0);
+            for i in (1..BIN_COUNT).rev() {
+                if let Some(bin_aabb) = bins[i].aabb {
+                    right_aabb = union(right_aabb, &bin_aabb);
+                }
+                right_count += bins[i].count;
+                right_areas[i] = right_aabb.map_or(0.0, |b| b.surface_area());
+                right_counts[i] = right_count;
+            }
+
+            let (mut left_aabb, mut left_count) = (None, 0);
+            let (mut best_cost, mut best_bin) = (f32::MAX, 0);
+            for i in 0..BIN_COUNT - 1 {
+                if let Some(bin_aabb) = bins[i].aabb {
+                    left_aabb = union(left_aabb, &bin_aabb);
+                }
+                left_count += bins[i].count;
+                if left_count == 0 || right_counts[i + 1] == 0 {
+                    continue;
+                }
+                let cost =
+                    left_aabb.map_or(0.0, |b| b.surface_area()) * left_count as f32 +
+                    right_areas[i + 1] * right_counts[i + 1] as f32;
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_bin = i;
+                }
+            }
+
+            let leaf_cost = INTERSECTION_COST * primitives.len() as f32;
+            let split_cost = TRAVERSAL_COST + INTERSECTION_COST * best_cost / aabb.surface_area().max(f32::MIN_POSITIVE);
+            if primitives.len() <= MAX_LEAF_SIZE && leaf_cost <= split_cost {
+                self.push_leaf(aabb, primitives);
+                return;
+            }
+
+            let mut left = 0;
+            for i in 0..primitives.len() {
+                if bin_of(&primitives[i]) <= best_bin {
+                    primitives.swap(i, left);
+                    left += 1;
+                }
+            }
+            if left == 0 || left == primitives.len() {
+                primitives.len() / 2
+            } else {
+                left
+            }
+        };
+
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode { aabb, offset: 0, count: 0, axis });
+        let (left, right) = primitives.split_at_mut(split);
+        self.build_recursive(left, depth + 1);
+        self.nodes[node_index].offset = self.nodes.len();
+        self.build_recursive(right, depth + 1);
+    }
+
+    /// Visits primitives whose boxes are hit by the ray in front-to-back order.
+    /// The callback receives a primitive id together with the current closest distance and
+    /// returns a distance of a closer hit if it has found one, which is used to prune the rest
+    pub fn traverse<F>(&self, ray: &Ray, t_min: f32, t_max: f32, mut f: F)
+        where F: FnMut(usize, f32) -> Option<f32>
+    {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let inv_dir = vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+        let mut t_max = t_max;
+        let mut stack: [(usize, f32); MAX_DEPTH + 4] = [(0, 0.0); MAX_DEPTH + 4];
+        let mut stack_size = 0;
+
+        if let Some(t) = self.nodes[0].aabb.hit_distance(ray, &inv_dir, t_min, t_max) {
+            stack[0] = (0, t);
+            stack_size = 1;
+        }
+
+        while stack_size > 0 {
+            stack_size -= 1;
+            let (node_index, t_enter) = stack[stack_size];
+            if t_enter > t_max {
+                continue;
+            }
+            let node = &self.nodes[node_index];
+            if node.count > 0 {
+                for &id in &self.primitive_ids[node.offset..node.offset + node.count] {
+                    if let Some(t) = f(id, t_max) {
+                        t_max = t_max.min(t);
+                    }
+                }
+                continue;
+            }
+
+            let (near, far) = if ray.direction[node.axis] < 0.0 {
+                (node.offset, node_index + 1)
+            } else {
+                (node_index + 1, node.offset)
+            };
+            let t_near = self.nodes[near].aabb.hit_distance(ray, &inv_dir, t_min, t_max);
+            let t_far = self.nodes[far].aabb.hit_distance(ray, &inv_dir, t_min, t_max);
+            // far child goes to the stack first so the near one is popped first
+            if let Some(t) = t_far {
+                stack[stack_size] = (far, t);
+                stack_size += 1;
+            }
+            if let Some(t) = t_near {
+                stack[stack_size] = (near, t);
+                stack_size += 1;
+            }
+        }
+    }
+
+    /// Visits primitives whose boxes are hit by the ray until the callback reports a hit.
+    /// Returns whether any primitive has been hit
+    pub fn any<F>(&self, ray: &Ray, t_min: f32, t_max: f32, mut f: F) -> bool
+        where F: FnMut(usize) -> bool
+    {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let inv_dir = vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+        let mut stack: [usize; MAX_DEPTH + 4] = [0; MAX_DEPTH + 4];
+        let mut stack_size = 1;
+
+        while stack_size > 0 {
+            stack_size -= 1;
+            let node_index = stack[stack_size];
+            let node = &self.nodes[node_index];
+            if node.aabb.hit_distance(ray, &inv_dir, t_min, t_max).is_none() {
+                continue;
+            }
+            if node.count > 0 {
+                if self.primitive_ids[node.offset..node.offset + node.count].iter().any(|&id| f(id)) {
+                    return true;
+                }
+                continue;
+            }
+            stack[stack_size] = node.offset;
+            stack[stack_size + 1] = node_index + 1;
+            stack_size += 2;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::{InnerSpace, vec2},
+        rand::{Rng, SeedableRng, rngs::StdRng},
+        crate::{
+            material::{Albedo, Material, MaterialDetails, Relief},
+            mesh_data::{MeshData, VertexDescription},
+            ray::HitTestable,
+            shape::{Shape, test_ray_triangle_intersection},
+            transform::Transform,
+            world::World
+        }
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn random_point(rng: &mut StdRng, size: f32) -> Vector3<f32> {
+        vec3(rng.gen_range(-size, size), rng.gen_range(-size, size), rng.gen_range(-size, size))
+    }
+
+    #[test]
+    fn finds_same_hits_as_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(26);
+        let mut mesh = MeshData::default();
+        for _ in 0..300 {
+            let center = random_point(&mut rng, 5.0);
+            for _ in 0..3 {
+                mesh.vertices.push(VertexDescription {
+                    position: center + random_point(&mut rng, 1.0),
+                    normal: vec3(0.0, 0.0, 0.0),
+                    uv: vec2(0.0, 0.0)
+                });
+            }
+        }
+        mesh.indices = (0..mesh.vertices.len()).collect();
+        mesh.generate_normals();
+        let description = mesh.description();
+        let bvh = description.build_bvh();
+        let shape = Shape::TriangleMesh { transform: Transform::identity(), mesh: description, material: MATERIAL, bvh: Some(&bvh) };
+
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let ray = Ray { origin: random_point(&mut rng, 8.0), direction: random_point(&mut rng, 1.0).normalize() };
+            let t_max = rng.gen_range(1.0, 20.0);
+            let linear = (0..description.triangle_count)
+                .filter_map(|face_id| test_ray_triangle_intersection(
+                    &description.get_triangle(face_id).unwrap(), &ray, 0.0, t_max, &MATERIAL, description.shading, None
+                ))
+                .map(|hit_info| hit_info.t)
+                .fold(None, |nearest: Option<f32>, t| Some(nearest.map_or(t, |nearest| nearest.min(t))));
+
+            match (shape.hit_test(&ray, 0.0, t_max), linear) {
+                (Some(hit_info), Some(t)) => {
+                    assert_eq!(hit_info.t, t);
+                    hits += 1;
+                },
+                (None, None) => {},
+                (hit_info, t) => panic!("bvh hits at {:?}, linear scan at {:?}", hit_info.map(|h| h.t), t)
+            }
+            assert_eq!(shape.occluded(&ray, t_max), linear.is_some());
+        }
+        assert!(hits > 100);
+    }
+
+    #[test]
+    fn world_finds_same_hits_as_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(26);
+        let mut shapes: Vec<_> = (0..200)
+            .map(|_| Shape::Sphere {
+                radius: rng.gen_range(0.1, 1.0),
+                transform: Transform::translation(random_point(&mut rng, 5.0)),
+                material: MATERIAL
+            })
+            .collect();
+        shapes.push(Shape::Plane { two_sided: true, transform: Transform::translation(vec3(0.0, 0.0, -6.0)), material: MATERIAL });
+        shapes.push(Shape::Plane { two_sided: false, transform: Transform::translation(vec3(0.0, 0.0, 6.0)), material: MATERIAL });
+        let world = World::construct(&shapes);
+        assert!(!world.is_bounded());
+
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let ray = Ray { origin: random_point(&mut rng, 8.0), direction: random_point(&mut rng, 1.0).normalize() };
+            let t_max = rng.gen_range(1.0, 20.0);
+            let linear = shapes.iter()
+                .filter_map(|shape| shape.hit_test(&ray, 0.0, t_max))
+                .map(|hit_info| hit_info.t)
+                .fold(None, |nearest: Option<f32>, t| Some(nearest.map_or(t, |nearest| nearest.min(t))));
+
+            match (world.hit_test(&ray, 0.0, t_max), linear) {
+                (Some(hit_info), Some(t)) => {
+                    assert_eq!(hit_info.t, t);
+                    hits += 1;
+                },
+                (None, None) => {},
+                (hit_info, t) => panic!("world hits at {:?}, linear scan at {:?}", hit_info.map(|h| h.t), t)
+            }
+            assert_eq!(world.occluded(&ray, t_max), linear.is_some());
+        }
+        assert!(hits > 100);
+    }
+}
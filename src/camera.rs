@@ -25,16 +25,28 @@ impl Camera {
         }
     }
 
-    pub fn orient_at(&mut self, target: Vector3<f32>) {
-        self.direction = (target - self.origin).normalize();
-    }
-
     pub fn get_basis_vectors(&self, aspect: f32) -> (Vector3<f32>, Vector3<f32>, Vector3<f32>) {
         let forward = self.direction.normalize();
-        let right = self.up.cross(forward);
-        let up = forward.cross(right);
+        // right handed like the world, so +x is on the right when looking down -z
+        let right = forward.cross(self.up).normalize();
+        let up = right.cross(forward);
         (right * aspect, up, forward * aspect / (self.fov / 2.0).tan())
     }
 
 }
 
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::vec3
+    };
+
+    #[test]
+    fn basis_is_right_handed() {
+        let camera = Camera::new(Origin(vec3(0.0, 0.0, 0.0)), Up(Vector3::unit_y()), Fov(1.0), Target(vec3(0.0, 0.0, -1.0)));
+        let (right, up, _) = camera.get_basis_vectors(1.0);
+        assert!((right - Vector3::unit_x()).magnitude() < 1e-6);
+        assert!((up - Vector3::unit_y()).magnitude() < 1e-6);
+    }
+}
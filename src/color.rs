@@ -1,4 +1,7 @@
-use cgmath::Vector3;
+use {
+    cgmath::Vector3,
+    std::fmt
+};
 
 #[derive(Clone, Copy)]
 pub struct Color{
@@ -7,9 +10,9 @@ pub struct Color{
     pub b: u8
 }
 
-impl Color {
-    pub fn to_string(&self) -> String {
-        format!("{}\t{}\t{}\t", self.r, self.g, self.b)
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}\t{}\t", self.r, self.g, self.b)
     }
 }
 
@@ -0,0 +1,173 @@
+use {
+    cgmath::InnerSpace,
+    crate::{
+        ray::{HitTestable, HitInfo, Ray},
+        shape::Shape
+    }
+};
+
+/// Every surface a ray could cross inside of a single solid, it is a guard against shapes
+/// which keep reporting the same surface due to rounding
+const MAX_CROSSINGS: usize = 64;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    /// the right solid is cut out of the left one
+    Difference
+}
+
+impl CsgOperation {
+    fn contains(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right
+        }
+    }
+}
+
+/// Surface of a solid crossed by a ray, the ray enters the solid when it goes against
+/// the outward normal
+#[derive(Copy, Clone)]
+struct Crossing<'a> {
+    hit_info: HitInfo<'a>,
+    entering: bool
+}
+
+/// Crossings of a closed shape in order of distance. They are kept in a fixed-size buffer
+/// like the bvh traversal stack, so nothing is allocated per ray
+struct Crossings<'a> {
+    items: [Option<Crossing<'a>>; MAX_CROSSINGS],
+    len: usize
+}
+
+impl<'a> Crossings<'a> {
+    fn get(&self, index: usize) -> Option<&Crossing<'a>> {
+        if index < self.len { self.items[index].as_ref() } else { None }
+    }
+}
+
+/// Crossings of a closed shape within the interval, along with whether the ray is inside of
+/// the shape at t_min. Shapes only report the nearest hit, so they are asked again from every
+/// crossing found, and the first one tells what is before it
+fn crossings<'a>(shape: &'a Shape<'a>, ray: &Ray, t_min: f32, t_max: f32) -> (bool, Crossings<'a>) {
+    let mut crossings = Crossings { items: [None; MAX_CROSSINGS], len: 0 };
+    let mut t = t_min;
+    while crossings.len < MAX_CROSSINGS {
+        let hit_info = match shape.hit_test(ray, t, t_max) {
+            Some(hit_info) => hit_info,
+            None => break
+        };
+        t = hit_info.t;
+        let entering = hit_info.ng.dot(ray.direction) < 0.0;
+        // a ray through an edge of a mesh could hit both faces sharing it
+        let last = crossings.len.checked_sub(1).and_then(|last| crossings.get(last));
+        if last.is_none_or(|last| last.entering != entering) {
+            crossings.items[crossings.len] = Some(Crossing { hit_info, entering });
+            crossings.len += 1;
+        }
+    }
+    let starts_inside = match crossings.get(0) {
+        Some(crossing) => !crossing.entering,
+        // with nothing crossed inside of the interval, the ray is in the shape if it leaves it later
+        None => shape.hit_test(ray, t_max, f32::INFINITY).is_some_and(|hit_info| hit_info.ng.dot(ray.direction) > 0.0)
+    };
+    (starts_inside, crossings)
+}
+
+/// Nearest point where the ray crosses the boundary of the combined solid. Crossings of both
+/// shapes are merged by distance, and the one changing whether the ray is inside of the result
+/// is its surface. Normals are flipped where the surface comes from inside of a shape
+/// (i.e. from the cut out one)
+pub fn test_ray_csg_intersection<'a>(
+    operation: CsgOperation,
+    left: &'a Shape<'a>,
+    right: &'a Shape<'a>,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32
+) -> Option<HitInfo<'a>>
+{
+    let (mut in_left, lefts) = crossings(left, ray, t_min, t_max);
+    let (mut in_right, rights) = crossings(right, ray, t_min, t_max);
+    let mut inside = operation.contains(in_left, in_right);
+
+    let (mut left_index, mut right_index) = (0, 0);
+    loop {
+        let take_left = match (lefts.get(left_index), rights.get(right_index)) {
+            (Some(l), Some(r)) => l.hit_info.t <= r.hit_info.t,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => return None
+        };
+        let crossing = if take_left {
+            let crossing = *lefts.get(left_index)?;
+            left_index += 1;
+            in_left = crossing.entering;
+            crossing
+        } else {
+            let crossing = *rights.get(right_index)?;
+            right_index += 1;
+            in_right = crossing.entering;
+            crossing
+        };
+        let now_inside = operation.contains(in_left, in_right);
+        if now_inside != inside {
+            let hit_info = crossing.hit_info;
+            // ray entering the result goes against its outward normal
+            let flip = (hit_info.ng.dot(ray.direction) < 0.0) != now_inside;
+            return Some(if flip {
+                HitInfo { n: -hit_info.n, ng: -hit_info.ng, dpdv: -hit_info.dpdv, ..hit_info }
+            } else {
+                hit_info
+            });
+        }
+        inside = now_inside;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::vec3,
+        crate::{
+            material::{Albedo, Material, MaterialDetails, Relief},
+            transform::Transform
+        }
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    #[test]
+    fn cut_out_surface_faces_out_of_difference() {
+        // unit sphere with a bite of radius 0.5 taken out of its -x side
+        let left = Shape::Sphere { radius: 1.0, transform: Transform::identity(), material: MATERIAL };
+        let right = Shape::Sphere { radius: 0.5, transform: Transform::translation(vec3(-1.0, 0.0, 0.0)), material: MATERIAL };
+        let test = |origin, direction| {
+            test_ray_csg_intersection(CsgOperation::Difference, &left, &right, &Ray { origin, direction }, 0.0, 100.0)
+        };
+
+        let hit_info = test(vec3(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.t - 4.5).abs() < 1e-4);
+        assert!((hit_info.n - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+        assert!((hit_info.ng - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+
+        // leaving the result through the cut out surface
+        let hit_info = test(vec3(0.0, 0.0, 0.0), vec3(-1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.t - 0.5).abs() < 1e-4);
+        assert!((hit_info.n - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+
+        // the other side is the surface of the left sphere as it is
+        let hit_info = test(vec3(5.0, 0.0, 0.0), vec3(-1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.t - 4.0).abs() < 1e-4);
+        assert!((hit_info.n - vec3(1.0, 0.0, 0.0)).magnitude() < 1e-4);
+    }
+}
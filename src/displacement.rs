@@ -0,0 +1,194 @@
+use {
+    cgmath::{Vector3, InnerSpace, vec3},
+    std::collections::HashMap,
+    crate::{
+        material::Height,
+        mesh_data::{MeshData, VertexDescription, position_key}
+    }
+};
+
+/// Tessellation stops after this many passes even if some edges are still too long
+const MAX_TESSELLATION_PASSES: usize = 16;
+
+/// Mesh with its surface pushed along normals by height times scale, which unlike bump
+/// mapping changes silhouettes too. Source is tessellated until its edges are no longer
+/// than max_edge_length, so the height is sampled finely enough. The result is an ordinary
+/// mesh to be placed by Shape::TriangleMesh with its own bvh
+pub fn displaced_mesh(source: &MeshData, height: Height, scale: f32, max_edge_length: f32) -> MeshData {
+    let mut mesh = MeshData {
+        vertices: source.vertices.clone(),
+        indices: source.indices.clone(),
+        tangents: Vec::new(),
+        colors: source.colors.clone(),
+        shading: source.shading
+    };
+    mesh.generate_normals();
+    tessellate(&mut mesh, max_edge_length);
+    displace(&mut mesh, height, scale);
+    if !source.tangents.is_empty() {
+        mesh.generate_tangents();
+    }
+    mesh
+}
+
+/// Splits edges longer than max_edge_length in halves until there are none left. Midpoints
+/// are shared by both faces of an edge, so the mesh stays free of cracks
+pub fn tessellate(mesh: &mut MeshData, max_edge_length: f32) {
+    let max_length2 = max_edge_length * max_edge_length;
+    for _ in 0..MAX_TESSELLATION_PASSES {
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        for face_id in 0..mesh.indices.len() / 3 {
+            let face = [mesh.indices[face_id * 3], mesh.indices[face_id * 3 + 1], mesh.indices[face_id * 3 + 2]];
+            for (a, b) in [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])] {
+                let key = (a.min(b), a.max(b));
+                let (pa, pb) = (mesh.vertices[a].position, mesh.vertices[b].position);
+                if (pb - pa).magnitude2() > max_length2 && !midpoints.contains_key(&key) {
+                    midpoints.insert(key, split_edge(mesh, a, b));
+                }
+            }
+        }
+        if midpoints.is_empty() {
+            return;
+        }
+
+        let midpoint = |a: usize, b: usize| midpoints.get(&(a.min(b), a.max(b))).copied();
+        let mut indices = Vec::with_capacity(mesh.indices.len() * 2);
+        for face in mesh.indices.chunks_exact(3) {
+            // face is rotated so that split edges start from its first corners
+            let rotation = (0..3).find(|&i| midpoint(face[i], face[(i + 1) % 3]).is_some() &&
+                midpoint(face[(i + 2) % 3], face[i]).is_none());
+            let [a, b, c] = match rotation {
+                Some(i) => [face[i], face[(i + 1) % 3], face[(i + 2) % 3]],
+                None => [face[0], face[1], face[2]]
+            };
+            match (midpoint(a, b), midpoint(b, c), midpoint(c, a)) {
+                (Some(ab), Some(bc), Some(ca)) => indices.extend_from_slice(&[a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]),
+                (Some(ab), Some(bc), None) => indices.extend_from_slice(&[ab, b, bc, a, ab, bc, a, bc, c]),
+                (Some(ab), None, None) => indices.extend_from_slice(&[a, ab, c, ab, b, c]),
+                _ => indices.extend_from_slice(&[a, b, c])
+            }
+        }
+        mesh.indices = indices;
+    }
+}
+
+fn split_edge(mesh: &mut MeshData, a: usize, b: usize) -> usize {
+    let (va, vb) = (mesh.vertices[a], mesh.vertices[b]);
+    let normal = va.normal + vb.normal;
+    mesh.vertices.push(VertexDescription {
+        position: (va.position + vb.position) * 0.5,
+        normal: if normal.magnitude2() > 0.0 { normal.normalize() } else { va.normal },
+        uv: (va.uv + vb.uv) * 0.5
+    });
+    if !mesh.colors.is_empty() {
+        let color = (mesh.colors[a] + mesh.colors[b]) * 0.5;
+        mesh.colors.push(color);
+    }
+    mesh.vertices.len() - 1
+}
+
+/// Moves vertices along their normals and generates new normals for the moved surface.
+/// Vertices at the same position (i.e. at uv seams) are moved together, otherwise
+/// the surface would crack along seams
+pub fn displace(mesh: &mut MeshData, height: Height, scale: f32) {
+    let mut offsets: HashMap<[u32; 3], (Vector3<f32>, usize)> = HashMap::new();
+    for vertex in &mesh.vertices {
+        let offset = offsets.entry(position_key(vertex.position)).or_insert((vec3(0.0, 0.0, 0.0), 0));
+        offset.0 += vertex.normal * (height.get(vertex.uv) * scale);
+        offset.1 += 1;
+    }
+    for vertex in mesh.vertices.iter_mut() {
+        let (offset, count) = offsets[&position_key(vertex.position)];
+        vertex.position += offset / count as f32;
+        vertex.normal = vec3(0.0, 0.0, 0.0);
+    }
+    mesh.generate_normals();
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::{Vector2, vec2}
+    };
+
+    fn vertex(x: f32, y: f32, u: f32) -> VertexDescription {
+        VertexDescription { position: vec3(x, y, 0.0), normal: vec3(0.0, 0.0, 1.0), uv: vec2(u, 0.0) }
+    }
+
+    fn face_normals(mesh: &MeshData) -> Vec<Vector3<f32>> {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|f| {
+                let p = |i: usize| mesh.vertices[f[i]].position;
+                (p(1) - p(0)).cross(p(2) - p(0))
+            })
+            .collect()
+    }
+
+    /// Lengths of edges used by a single face, which are the outline of a flat mesh without cracks
+    fn outline(mesh: &MeshData) -> f32 {
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for f in mesh.indices.chunks_exact(3) {
+            for (a, b) in [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])] {
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        edges
+            .iter()
+            .filter(|(_, &count)| count == 1)
+            .map(|(&(a, b), _)| (mesh.vertices[a].position - mesh.vertices[b].position).magnitude())
+            .sum()
+    }
+
+    #[test]
+    fn tessellation_keeps_area_winding_and_outline() {
+        // one, two and three edges longer than the limit, starting from every corner
+        let triangles = [
+            [vertex(0.0, 0.0, 0.0), vertex(2.0, 0.0, 0.0), vertex(1.0, 0.1, 0.0)],
+            [vertex(0.0, 0.0, 0.0), vertex(2.0, 0.0, 0.0), vertex(2.0, 0.5, 0.0)],
+            [vertex(0.0, 0.0, 0.0), vertex(4.0, 0.0, 0.0), vertex(0.0, 1.0, 0.0)]
+        ];
+        for triangle in triangles.iter() {
+            for rotation in 0..3 {
+                // a second face shares the long first edge, so its midpoints have to be shared
+                let apex = vertex(1.0, -1.0, 0.0);
+                let mut mesh = MeshData {
+                    vertices: vec![triangle[0], triangle[1], triangle[2], apex],
+                    indices: vec![rotation, (rotation + 1) % 3, (rotation + 2) % 3, 1, 0, 3],
+                    ..Default::default()
+                };
+                let (area, length) = (face_normals(&mesh).iter().map(|n| n.magnitude()).sum::<f32>(), outline(&mesh));
+                tessellate(&mut mesh, 1.5);
+                let normals = face_normals(&mesh);
+                assert!(normals.len() > 2);
+                assert!(normals.iter().all(|n| n.z > 0.0), "faces are flipped");
+                assert!((normals.iter().map(|n| n.magnitude()).sum::<f32>() - area).abs() < 1e-4);
+                assert!((outline(&mesh) - length).abs() < 1e-4, "mesh has cracks");
+                for f in mesh.indices.chunks_exact(3) {
+                    for (a, b) in [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])] {
+                        assert!((mesh.vertices[a].position - mesh.vertices[b].position).magnitude() <= 1.5);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seam_vertices_stay_together() {
+        // the first and the last vertices are at the same position on both sides of a uv seam
+        let mut mesh = MeshData {
+            vertices: vec![vertex(0.0, 0.0, 0.0), vertex(1.0, 0.0, 0.5), vertex(0.0, 1.0, 0.5), vertex(0.0, 0.0, 1.0), vertex(0.0, -1.0, 0.5)],
+            indices: vec![0, 1, 2, 3, 4, 1],
+            ..Default::default()
+        };
+        fn u(uv: Vector2<f32>) -> f32 {
+            uv.x
+        }
+        displace(&mut mesh, Height::Procedural(u), 1.0);
+        let (a, b) = (mesh.vertices[0].position, mesh.vertices[3].position);
+        assert_eq!(position_key(a), position_key(b));
+        // they are moved by the average of their heights
+        assert!((a.z - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,57 @@
+use cgmath::{Vector3, InnerSpace, vec3};
+
+const MACHINE_EPSILON: f32 = f32::EPSILON * 0.5;
+
+/// Bound of a relative error accumulated after n consecutive floating point operations
+pub fn gamma(n: u32) -> f32 {
+    let n = n as f32;
+    n * MACHINE_EPSILON / (1.0 - n * MACHINE_EPSILON)
+}
+
+pub fn abs(v: Vector3<f32>) -> Vector3<f32> {
+    vec3(v.x.abs(), v.y.abs(), v.z.abs())
+}
+
+pub fn next_float_up(v: f32) -> f32 {
+    if v.is_infinite() && v > 0.0 {
+        return v;
+    }
+    // -0.0 and 0.0 share the same next value
+    let v = if v == -0.0 { 0.0 } else { v };
+    let bits = v.to_bits();
+    let bits = if v >= 0.0 { bits + 1 } else { bits - 1 };
+    f32::from_bits(bits)
+}
+
+pub fn next_float_down(v: f32) -> f32 {
+    if v.is_infinite() && v < 0.0 {
+        return v;
+    }
+    let v = if v == 0.0 { -0.0 } else { v };
+    let bits = v.to_bits();
+    let bits = if v > 0.0 { bits - 1 } else { bits + 1 };
+    f32::from_bits(bits)
+}
+
+/// Moves a point with a known error bound along the geometric normal just far enough,
+/// so a ray leaving it in the direction w can't hit the surface the point came from
+pub fn offset_ray_origin(
+    p: Vector3<f32>,
+    p_error: Vector3<f32>,
+    n: Vector3<f32>,
+    w: Vector3<f32>
+) -> Vector3<f32>
+{
+    let d = abs(n).dot(p_error);
+    let offset = if w.dot(n) < 0.0 { -n * d } else { n * d };
+    let mut po = p + offset;
+    // rounding of the sum above could bring the point back into the error box
+    for i in 0..3 {
+        if offset[i] > 0.0 {
+            po[i] = next_float_up(po[i]);
+        } else if offset[i] < 0.0 {
+            po[i] = next_float_down(po[i]);
+        }
+    }
+    po
+}
@@ -0,0 +1,1061 @@
+use {
+    cgmath::{
+        Matrix4,
+        SquareMatrix,
+        Quaternion,
+        InnerSpace,
+        Vector3,
+        vec2,
+        vec3,
+        vec4
+    },
+    serde_json::Value,
+    std::{
+        f32::consts::PI,
+        fmt,
+        fs,
+        io,
+        path::{Component, Path}
+    },
+    crate::{
+        aabb::AaBb,
+        bvh::Bvh,
+        camera::Camera,
+        image_loader::{ImgData, load_png},
+        material::{Albedo, Material, MaterialDetails, Relief},
+        mesh_data::{MeshData, VertexDescription},
+        shape::Shape,
+        transform::Transform
+    }
+};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+const MODE_TRIANGLES: u64 = 4;
+const MODE_TRIANGLE_STRIP: u64 = 5;
+const MODE_TRIANGLE_FAN: u64 = 6;
+/// Accessors without a buffer view are all zeros, so nothing in the file bounds their size
+const MAX_ZERO_ACCESSOR_COUNT: usize = 1 << 24;
+
+// punctual lights have no size, so they become small spheres and far away disks
+const POINT_LIGHT_RADIUS: f32 = 0.05;
+const SUN_ANGULAR_RADIUS: f32 = 0.05;
+const SUN_DISTANCE_IN_SCENE_SIZES: f32 = 10.0;
+
+#[derive(Debug)]
+pub enum GltfError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+    Unsupported(String)
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GltfError::Io(e) => write!(f, "{}", e),
+            GltfError::Json(e) => write!(f, "bad json: {}", e),
+            GltfError::Invalid(message) => write!(f, "invalid gltf: {}", message),
+            GltfError::Unsupported(message) => write!(f, "unsupported gltf: {}", message)
+        }
+    }
+}
+
+impl From<io::Error> for GltfError {
+    fn from(e: io::Error) -> Self {
+        GltfError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for GltfError {
+    fn from(e: serde_json::Error) -> Self {
+        GltfError::Json(e)
+    }
+}
+
+fn invalid(message: impl Into<String>) -> GltfError {
+    GltfError::Invalid(message.into())
+}
+
+/// Metallic-roughness material. Tracer materials have a single roughness and can't
+/// modulate a texture by a color, so only the base color and normals are textured
+pub struct GltfMaterial {
+    pub base_color: Vector3<f32>,
+    pub alpha: f32,
+    pub base_color_texture: Option<usize>,
+    pub normal_texture: Option<usize>,
+    pub normal_scale: f32,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Vector3<f32>,
+    pub transmission: f32,
+    pub ior: f32
+}
+
+impl GltfMaterial {
+    pub fn to_material<'a>(&self, images: &'a [ImgData]) -> Material<'a> {
+        let albedo = match self.base_color_texture.map(|i| &images[i]) {
+            Some(ImgData { width, height, colors }) => Albedo::Texture(*width, *height, colors),
+            None => Albedo::Constant(self.base_color)
+        };
+        let relief = match self.normal_texture.map(|i| &images[i]) {
+            Some(ImgData { width, height, colors }) => Relief::NormalMap {
+                width: *width,
+                height: *height,
+                normals: colors,
+                strength: self.normal_scale
+            },
+            None => Relief::None
+        };
+        if self.emissive.magnitude2() > 0.0 {
+            Material {
+                albedo: Albedo::Constant(self.emissive),
+                details: MaterialDetails::Lambertian,
+                emittance: 1.0,
+                relief: Relief::None
+            }
+        } else if self.transmission > 0.0 || self.alpha < 1.0 {
+            Material {
+                albedo,
+                details: MaterialDetails::Dielectric { ref_idx: self.ior, roughness: self.roughness },
+                emittance: 0.0,
+                relief
+            }
+        } else if self.metallic >= 0.5 {
+            Material {
+                albedo,
+                details: MaterialDetails::Metallic { roughness: self.roughness },
+                emittance: 0.0,
+                relief
+            }
+        } else {
+            Material { albedo, details: MaterialDetails::Lambertian, emittance: 0.0, relief }
+        }
+    }
+}
+
+/// Triangles of a single primitive of a gltf mesh
+pub struct GltfPrimitive {
+    pub mesh: MeshData,
+    pub material: Option<usize>
+}
+
+/// Placement of a primitive by a node of the scene
+pub struct GltfInstance {
+    pub primitive: usize,
+    pub transform: Transform
+}
+
+pub struct GltfCamera {
+    pub transform: Transform,
+    /// vertical field of view in radians
+    pub yfov: f32
+}
+
+#[derive(Clone, Copy)]
+pub enum GltfLightKind {
+    Point,
+    Spot,
+    Directional
+}
+
+pub struct GltfLight {
+    pub kind: GltfLightKind,
+    pub color: Vector3<f32>,
+    pub intensity: f32,
+    pub transform: Transform
+}
+
+/// Default scene of a gltf file with every node transform baked into instances,
+/// cameras and lights
+pub struct GltfScene {
+    pub primitives: Vec<GltfPrimitive>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<ImgData>,
+    pub instances: Vec<GltfInstance>,
+    pub cameras: Vec<GltfCamera>,
+    pub lights: Vec<GltfLight>
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> &'v [Value] {
+    value.get(key).and_then(Value::as_array).map_or(&[], |a| a.as_slice())
+}
+
+fn index(value: &Value, key: &str) -> Option<usize> {
+    value.get(key).and_then(Value::as_u64).map(|i| i as usize)
+}
+
+fn number(value: &Value, key: &str) -> Option<f32> {
+    value.get(key).and_then(Value::as_f64).map(|n| n as f32)
+}
+
+fn numbers(value: &Value, key: &str) -> Option<Vec<f32>> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|a| a.iter().filter_map(Value::as_f64).map(|n| n as f32).collect())
+}
+
+fn element<'v>(document: &'v Value, collection: &str, id: usize) -> Result<&'v Value, GltfError> {
+    array(document, collection)
+        .get(id)
+        .ok_or_else(|| invalid(format!("{} {} doesn't exist", collection, id)))
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, GltfError> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    for c in text.bytes().filter(|&c| c != b'=' && !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return Err(invalid("bad base64 data")),
+        };
+        accumulator = (accumulator << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Uris are percent encoded, so %20 in a file name stands for a space
+fn decode_percent(uri: &str) -> Result<String, GltfError> {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = bytes.get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid(format!("bad percent encoding in uri {}", uri)))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid(format!("uri {} isn't utf-8", uri)))
+}
+
+/// Files are only looked for next to the gltf one or below it
+fn load_uri(uri: &str, base_dir: &Path) -> Result<Vec<u8>, GltfError> {
+    if uri.starts_with("data:") {
+        let (_, data) = uri
+            .split_once(";base64,")
+            .ok_or_else(|| GltfError::Unsupported("data uri which is not base64".to_string()))?;
+        decode_base64(data)
+    } else {
+        let path = decode_percent(uri)?;
+        let path = Path::new(&path);
+        if !path.components().all(|part| matches!(part, Component::Normal(_) | Component::CurDir)) {
+            return Err(invalid(format!("uri {} leaves the directory of the file", uri)));
+        }
+        Ok(fs::read(base_dir.join(path))?)
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, GltfError> {
+    let b = bytes.get(offset..offset + 4).ok_or_else(|| invalid("glb is truncated"))?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Splits glb container to its json and binary chunks
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), GltfError> {
+    if read_u32(bytes, 0)? != GLB_MAGIC {
+        return Err(invalid("glb doesn't start with glTF magic"));
+    }
+    let version = read_u32(bytes, 4)?;
+    if version != 2 {
+        return Err(GltfError::Unsupported(format!("glb version {}", version)));
+    }
+    let (mut json, mut bin) = (None, None);
+    let mut offset = 12;
+    while offset < bytes.len() {
+        let length = read_u32(bytes, offset)? as usize;
+        let kind = read_u32(bytes, offset + 4)?;
+        let data = bytes
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| invalid("glb is truncated"))?;
+        match kind {
+            GLB_JSON_CHUNK if json.is_none() => json = Some(data),
+            GLB_BIN_CHUNK if bin.is_none() => bin = Some(data),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    Ok((json.ok_or_else(|| invalid("glb has no json chunk"))?, bin))
+}
+
+struct Accessor {
+    components: usize,
+    values: Vec<f64>
+}
+
+impl Accessor {
+    fn get(&self, i: usize) -> &[f64] {
+        &self.values[i * self.components..(i + 1) * self.components]
+    }
+
+    fn count(&self) -> usize {
+        self.values.len() / self.components
+    }
+}
+
+struct Document<'a> {
+    json: &'a Value,
+    buffers: Vec<Vec<u8>>
+}
+
+impl Document<'_> {
+    fn buffer_view(&self, id: usize) -> Result<(&[u8], Option<usize>), GltfError> {
+        let view = element(self.json, "bufferViews", id)?;
+        let buffer = index(view, "buffer").ok_or_else(|| invalid(format!("buffer view {} has no buffer", id)))?;
+        let buffer = self.buffers.get(buffer).ok_or_else(|| invalid(format!("buffer {} doesn't exist", buffer)))?;
+        let offset = index(view, "byteOffset").unwrap_or(0);
+        let length = index(view, "byteLength").ok_or_else(|| invalid(format!("buffer view {} has no length", id)))?;
+        let data = offset
+            .checked_add(length)
+            .and_then(|end| buffer.get(offset..end))
+            .ok_or_else(|| invalid(format!("buffer view {} is out of its buffer", id)))?;
+        Ok((data, index(view, "byteStride")))
+    }
+
+    /// Reads every element of an accessor converting components to f64, which keeps
+    /// integers exact. Normalized integers are mapped to [0, 1] or [-1, 1]
+    fn accessor(&self, id: usize) -> Result<Accessor, GltfError> {
+        let accessor = element(self.json, "accessors", id)?;
+        if accessor.get("sparse").is_some() {
+            return Err(GltfError::Unsupported("sparse accessors".to_string()));
+        }
+        let components = match accessor.get("type").and_then(Value::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            other => return Err(GltfError::Unsupported(format!("accessor type {:?}", other)))
+        };
+        let component_type = accessor.get("componentType").and_then(Value::as_u64).unwrap_or(0);
+        let (size, max): (usize, f64) = match component_type {
+            5120 => (1, i8::MAX as f64),
+            5121 => (1, u8::MAX as f64),
+            5122 => (2, i16::MAX as f64),
+            5123 => (2, u16::MAX as f64),
+            5125 => (4, u32::MAX as f64),
+            5126 => (4, 1.0),
+            _ => return Err(invalid(format!("accessor {} has bad component type {}", id, component_type)))
+        };
+        let normalized = accessor.get("normalized").and_then(Value::as_bool).unwrap_or(false);
+        let count = index(accessor, "count").ok_or_else(|| invalid(format!("accessor {} has no count", id)))?;
+        let view = match index(accessor, "bufferView") {
+            Some(view) => view,
+            // accessor without a buffer view is all zeros
+            None if count <= MAX_ZERO_ACCESSOR_COUNT => return Ok(Accessor { components, values: vec![0.0; count * components] }),
+            None => return Err(invalid(format!("accessor {} without buffer view is too large", id)))
+        };
+        let (data, stride) = self.buffer_view(view)?;
+        let offset = index(accessor, "byteOffset").unwrap_or(0);
+        let stride = stride.unwrap_or(size * components);
+        // elements can't overlap, so their count is bounded by the size of the view
+        if stride < size * components {
+            return Err(invalid(format!("accessor {} has stride {} shorter than its elements", id, stride)));
+        }
+        let end = match count.checked_sub(1) {
+            Some(last) => stride
+                .checked_mul(last)
+                .and_then(|start| start.checked_add(offset))
+                .and_then(|start| start.checked_add(size * components)),
+            None => Some(0)
+        };
+        if end.is_none_or(|end| end > data.len()) {
+            return Err(invalid(format!("accessor {} is out of its buffer view", id)));
+        }
+        let mut values = Vec::with_capacity(count * components);
+        for i in 0..count {
+            for c in 0..components {
+                let b = &data[offset + i * stride + c * size..];
+                let value = match component_type {
+                    5120 => b[0] as i8 as f64,
+                    5121 => b[0] as f64,
+                    5122 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    5123 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64
+                };
+                values.push(if normalized { (value / max).max(-1.0) } else { value });
+            }
+        }
+        Ok(Accessor { components, values })
+    }
+
+    fn image(&self, id: usize, base_dir: &Path) -> Result<ImgData, GltfError> {
+        let image = element(self.json, "images", id)?;
+        let mime_type = image.get("mimeType").and_then(Value::as_str);
+        let bytes = match (image.get("uri").and_then(Value::as_str), index(image, "bufferView")) {
+            (Some(uri), _) => load_uri(uri, base_dir)?,
+            (None, Some(view)) => self.buffer_view(view)?.0.to_vec(),
+            _ => return Err(invalid(format!("image {} has neither uri nor buffer view", id)))
+        };
+        let is_png = bytes.starts_with(b"\x89PNG");
+        if !is_png {
+            return Err(GltfError::Unsupported(format!("image {} of type {}, only png is supported", id, mime_type.unwrap_or("unknown"))));
+        }
+        load_png(bytes.as_slice()).map_err(|e| invalid(format!("image {} is broken: {}", id, e)))
+    }
+
+    fn material(&self, material: &Value, texture_images: &mut Vec<usize>) -> Result<GltfMaterial, GltfError> {
+        let pbr = material.get("pbrMetallicRoughness").unwrap_or(&Value::Null);
+        let extensions = material.get("extensions").unwrap_or(&Value::Null);
+        let base_color = numbers(pbr, "baseColorFactor").unwrap_or_else(|| vec![1.0; 4]);
+        let (base_color, alpha) = match base_color.as_slice() {
+            [r, g, b, a] => (vec3(*r, *g, *b), *a),
+            _ => return Err(invalid("base color should have 4 components"))
+        };
+        let alpha = if material.get("alphaMode").and_then(Value::as_str) == Some("BLEND") { alpha } else { 1.0 };
+        let mut texture = |info: Option<&Value>| -> Result<Option<usize>, GltfError> {
+            let texture = match info.and_then(|t| index(t, "index")) {
+                Some(texture) => texture,
+                None => return Ok(None)
+            };
+            let source = index(element(self.json, "textures", texture)?, "source")
+                .ok_or_else(|| invalid(format!("texture {} has no source", texture)))?;
+            // images are loaded once no matter how many textures use them
+            Ok(Some(texture_images.iter().position(|&image| image == source).unwrap_or_else(|| {
+                texture_images.push(source);
+                texture_images.len() - 1
+            })))
+        };
+        let base_color_texture = texture(pbr.get("baseColorTexture"))?;
+        let normal_texture = texture(material.get("normalTexture"))?;
+        let emissive = numbers(material, "emissiveFactor").unwrap_or_else(|| vec![0.0; 3]);
+        let emissive_strength = extensions
+            .get("KHR_materials_emissive_strength")
+            .and_then(|e| number(e, "emissiveStrength"))
+            .unwrap_or(1.0);
+        Ok(GltfMaterial {
+            base_color,
+            alpha,
+            base_color_texture,
+            normal_texture,
+            normal_scale: material.get("normalTexture").and_then(|t| number(t, "scale")).unwrap_or(1.0),
+            metallic: number(pbr, "metallicFactor").unwrap_or(1.0),
+            roughness: number(pbr, "roughnessFactor").unwrap_or(1.0),
+            emissive: match emissive.as_slice() {
+                [r, g, b] => vec3(*r, *g, *b) * emissive_strength,
+                _ => return Err(invalid("emissive factor should have 3 components"))
+            },
+            transmission: extensions
+                .get("KHR_materials_transmission")
+                .and_then(|e| number(e, "transmissionFactor"))
+                .unwrap_or(0.0),
+            ior: extensions.get("KHR_materials_ior").and_then(|e| number(e, "ior")).unwrap_or(1.5)
+        })
+    }
+
+    fn primitive(&self, primitive: &Value) -> Result<GltfPrimitive, GltfError> {
+        let mode = primitive.get("mode").and_then(Value::as_u64).unwrap_or(MODE_TRIANGLES);
+        let attributes = primitive.get("attributes").unwrap_or(&Value::Null);
+        let attribute = |name: &str, components: usize| -> Result<Option<Accessor>, GltfError> {
+            let accessor = match index(attributes, name) {
+                Some(id) => self.accessor(id)?,
+                None => return Ok(None)
+            };
+            if accessor.components != components {
+                return Err(invalid(format!("{} should have {} components, not {}", name, components, accessor.components)));
+            }
+            Ok(Some(accessor))
+        };
+        let positions = attribute("POSITION", 3)?.ok_or_else(|| invalid("primitive has no positions"))?;
+        // every other attribute has an element per position
+        let vertex_attribute = |name: &str, components: usize| match attribute(name, components)? {
+            Some(accessor) if accessor.count() != positions.count() => Err(invalid(format!(
+                "{} has {} elements, but there are {} positions", name, accessor.count(), positions.count()
+            ))),
+            accessor => Ok(accessor)
+        };
+        let normals = vertex_attribute("NORMAL", 3)?;
+        let uvs = vertex_attribute("TEXCOORD_0", 2)?;
+        let tangents = vertex_attribute("TANGENT", 4)?;
+
+        let mut mesh = MeshData::default();
+        for i in 0..positions.count() {
+            let p = positions.get(i);
+            let n = normals.as_ref().map_or(&[0.0, 0.0, 0.0][..], |n| n.get(i));
+            let uv = uvs.as_ref().map_or(&[0.0, 0.0][..], |uv| uv.get(i));
+            mesh.vertices.push(VertexDescription {
+                position: vec3(p[0] as f32, p[1] as f32, p[2] as f32),
+                normal: vec3(n[0] as f32, n[1] as f32, n[2] as f32),
+                // gltf puts uv origin to the top left corner of an image
+                uv: vec2(uv[0] as f32, 1.0 - uv[1] as f32)
+            });
+            if let Some(tangents) = &tangents {
+                let t = tangents.get(i);
+                mesh.tangents.push(vec4(t[0] as f32, t[1] as f32, t[2] as f32, t[3] as f32));
+            }
+        }
+
+        let vertex_ids = match index(primitive, "indices") {
+            Some(id) => self.accessor(id)?.values.iter().map(|&i| i as usize).collect::<Vec<_>>(),
+            None => (0..positions.count()).collect()
+        };
+        if let Some(i) = vertex_ids.iter().find(|&&i| i >= positions.count()) {
+            return Err(invalid(format!("primitive refers to missing vertex {}", i)));
+        }
+        match mode {
+            MODE_TRIANGLES => mesh.indices = vertex_ids[..vertex_ids.len() / 3 * 3].to_vec(),
+            MODE_TRIANGLE_STRIP => for i in 2..vertex_ids.len() {
+                // every other triangle of a strip has its winding flipped
+                let (a, b) = if i % 2 == 0 { (i - 2, i - 1) } else { (i - 1, i - 2) };
+                mesh.indices.extend_from_slice(&[vertex_ids[a], vertex_ids[b], vertex_ids[i]]);
+            },
+            MODE_TRIANGLE_FAN => for i in 2..vertex_ids.len() {
+                mesh.indices.extend_from_slice(&[vertex_ids[0], vertex_ids[i - 1], vertex_ids[i]]);
+            },
+            _ => return Err(GltfError::Unsupported(format!("primitive mode {}, only triangles could be rendered", mode)))
+        }
+        if normals.is_none() {
+            mesh.generate_normals();
+        }
+        Ok(GltfPrimitive { mesh, material: index(primitive, "material") })
+    }
+}
+
+fn node_matrix(node: &Value) -> Result<Matrix4<f32>, GltfError> {
+    if let Some(m) = numbers(node, "matrix") {
+        if m.len() != 16 {
+            return Err(invalid("node matrix should have 16 components"));
+        }
+        // gltf matrices are column major just like cgmath ones
+        return Ok(Matrix4::new(
+            m[0], m[1], m[2], m[3],
+            m[4], m[5], m[6], m[7],
+            m[8], m[9], m[10], m[11],
+            m[12], m[13], m[14], m[15]
+        ));
+    }
+    let t = numbers(node, "translation").unwrap_or_else(|| vec![0.0; 3]);
+    let r = numbers(node, "rotation").unwrap_or_else(|| vec![0.0, 0.0, 0.0, 1.0]);
+    let s = numbers(node, "scale").unwrap_or_else(|| vec![1.0; 3]);
+    if t.len() != 3 || r.len() != 4 || s.len() != 3 {
+        return Err(invalid("node has malformed translation, rotation or scale"));
+    }
+    Ok(
+        Matrix4::from_translation(vec3(t[0], t[1], t[2])) *
+        Matrix4::from(Quaternion::new(r[3], r[0], r[1], r[2]).normalize()) *
+        Matrix4::from_nonuniform_scale(s[0], s[1], s[2])
+    )
+}
+
+struct SceneBuilder<'a> {
+    document: &'a Document<'a>,
+    mesh_primitives: Vec<Vec<usize>>,
+    instances: Vec<GltfInstance>,
+    cameras: Vec<GltfCamera>,
+    lights: Vec<GltfLight>
+}
+
+impl SceneBuilder<'_> {
+    fn visit(&mut self, node_id: usize, parent: &Matrix4<f32>, depth: usize) -> Result<(), GltfError> {
+        let json = self.document.json;
+        if depth > array(json, "nodes").len() {
+            return Err(invalid("node hierarchy has a cycle"));
+        }
+        let node = element(json, "nodes", node_id)?;
+        let matrix = parent * node_matrix(node)?;
+        // nodes scaled down to nothing can't be seen, as well as everything below them
+        let transform = match Transform::from_matrix(matrix) {
+            Some(transform) => transform,
+            None => return Ok(())
+        };
+        if let Some(mesh) = index(node, "mesh") {
+            let primitives = self
+                .mesh_primitives
+                .get(mesh)
+                .ok_or_else(|| invalid(format!("meshes {} doesn't exist", mesh)))?;
+            for &primitive in primitives {
+                self.instances.push(GltfInstance { primitive, transform });
+            }
+        }
+        if let Some(camera) = index(node, "camera") {
+            let camera = element(json, "cameras", camera)?;
+            // orthographic cameras can't be expressed with the tracer camera, so they are skipped
+            if let Some(perspective) = camera.get("perspective") {
+                self.cameras.push(GltfCamera {
+                    transform,
+                    yfov: number(perspective, "yfov").ok_or_else(|| invalid("perspective camera has no yfov"))?
+                });
+            }
+        }
+        let light = node
+            .get("extensions")
+            .and_then(|e| e.get("KHR_lights_punctual"))
+            .and_then(|e| index(e, "light"));
+        if let Some(light) = light {
+            let lights = json
+                .get("extensions")
+                .and_then(|e| e.get("KHR_lights_punctual"))
+                .map_or(&[][..], |e| array(e, "lights"));
+            let light = lights.get(light).ok_or_else(|| invalid(format!("light {} doesn't exist", light)))?;
+            let color = numbers(light, "color").unwrap_or_else(|| vec![1.0; 3]);
+            if color.len() != 3 {
+                return Err(invalid("light color should have 3 components"));
+            }
+            self.lights.push(GltfLight {
+                kind: match light.get("type").and_then(Value::as_str) {
+                    Some("point") => GltfLightKind::Point,
+                    Some("spot") => GltfLightKind::Spot,
+                    Some("directional") => GltfLightKind::Directional,
+                    other => return Err(GltfError::Unsupported(format!("light type {:?}", other)))
+                },
+                color: vec3(color[0], color[1], color[2]),
+                intensity: number(light, "intensity").unwrap_or(1.0),
+                transform
+            });
+        }
+        for child in array(node, "children") {
+            let child = child.as_u64().ok_or_else(|| invalid("node child should be an index"))?;
+            self.visit(child as usize, &matrix, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl GltfScene {
+    /// Loads either json gltf with its external buffers and images or binary glb
+    pub fn load(path: &Path) -> Result<Self, GltfError> {
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(b"glTF") {
+            let (json, bin) = split_glb(&bytes)?;
+            GltfScene::parse(json, bin, base_dir)
+        } else {
+            GltfScene::parse(&bytes, None, base_dir)
+        }
+    }
+
+    /// Parses gltf json. Binary chunk of glb is used for the buffer which has no uri,
+    /// external files are looked up in base_dir
+    pub fn parse(json: &[u8], bin: Option<&[u8]>, base_dir: &Path) -> Result<Self, GltfError> {
+        let json: Value = serde_json::from_slice(json)?;
+        let version = json.get("asset").and_then(|a| a.get("version")).and_then(Value::as_str);
+        if !version.is_some_and(|v| v.starts_with("2.")) {
+            return Err(GltfError::Unsupported(format!("version {:?}, only 2.x is supported", version)));
+        }
+
+        let buffers = array(&json, "buffers")
+            .iter()
+            .map(|buffer| match buffer.get("uri").and_then(Value::as_str) {
+                Some(uri) => load_uri(uri, base_dir),
+                None => bin.map(<[u8]>::to_vec).ok_or_else(|| invalid("buffer has no uri and there is no glb chunk"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let document = Document { json: &json, buffers };
+
+        let mut texture_images = Vec::new();
+        let materials = array(&json, "materials")
+            .iter()
+            .map(|m| document.material(m, &mut texture_images))
+            .collect::<Result<Vec<_>, _>>()?;
+        let images = texture_images
+            .iter()
+            .map(|&image| document.image(image, base_dir))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut primitives = Vec::new();
+        let mut mesh_primitives = Vec::new();
+        for mesh in array(&json, "meshes") {
+            let mut ids = Vec::new();
+            for primitive in array(mesh, "primitives") {
+                let mut primitive = document.primitive(primitive)?;
+                if let Some(material) = primitive.material {
+                    let material = materials
+                        .get(material)
+                        .ok_or_else(|| invalid(format!("material {} doesn't exist", material)))?;
+                    // normal maps need tangents, files are allowed to leave them out
+                    if material.normal_texture.is_some() && primitive.mesh.tangents.is_empty() {
+                        primitive.mesh.generate_tangents();
+                    }
+                }
+                ids.push(primitives.len());
+                primitives.push(primitive);
+            }
+            mesh_primitives.push(ids);
+        }
+
+        let mut builder = SceneBuilder {
+            document: &document,
+            mesh_primitives,
+            instances: Vec::new(),
+            cameras: Vec::new(),
+            lights: Vec::new()
+        };
+        let scene = match index(&json, "scene").or(if array(&json, "scenes").is_empty() { None } else { Some(0) }) {
+            Some(scene) => element(&json, "scenes", scene)?,
+            None => return Err(invalid("file has no scenes"))
+        };
+        for root in array(scene, "nodes") {
+            let root = root.as_u64().ok_or_else(|| invalid("scene node should be an index"))?;
+            builder.visit(root as usize, &Matrix4::identity(), 0)?;
+        }
+
+        Ok(GltfScene {
+            primitives,
+            materials,
+            images,
+            instances: builder.instances,
+            cameras: builder.cameras,
+            lights: builder.lights
+        })
+    }
+
+    /// Bounds of every placed primitive. Lights and cameras are not included
+    pub fn bounds(&self) -> Option<AaBb> {
+        self.instances
+            .iter()
+            .filter_map(|instance| {
+                let mesh = &self.primitives[instance.primitive].mesh;
+                AaBb::from_points(
+                    &mesh.vertices.iter().map(|v| instance.transform.point(v.position)).collect::<Vec<_>>()
+                )
+            })
+            .fold(None, |acc, b| Some(acc.map_or(b, |a: AaBb| a.union(&b))))
+    }
+
+    /// Builds a bvh per primitive, which is shared by all the instances of the primitive
+    pub fn build_bvhs(&self) -> Vec<Bvh> {
+        self.primitives.iter().map(|p| p.mesh.description().build_bvh()).collect()
+    }
+
+    /// Makes shapes for every instance and light of the scene placed with given transform.
+    /// Primitives without a material get the default one
+    pub fn shapes<'a>(
+        &'a self,
+        bvhs: &'a [Bvh],
+        transform: Transform,
+        default_material: Material<'a>
+    ) -> Vec<Shape<'a>>
+    {
+        let mut shapes = self.instances
+            .iter()
+            .map(|instance| {
+                let primitive = &self.primitives[instance.primitive];
+                Shape::TriangleMesh {
+                    transform: transform * instance.transform,
+                    mesh: primitive.mesh.description(),
+                    material: primitive.material.map_or(default_material, |m| self.materials[m].to_material(&self.images)),
+                    bvh: Some(&bvhs[instance.primitive])
+                }
+            })
+            .collect::<Vec<_>>();
+        let scene_size = self.bounds().map_or(1.0, |b| (b.max - b.min).magnitude());
+        let scene_center = self.bounds().map_or(vec3(0.0, 0.0, 0.0), |b| b.centroid());
+        shapes.extend(self.lights.iter().map(|light| light.to_shape(transform, scene_center, scene_size)));
+        shapes
+    }
+
+    /// First camera of the scene for the image with given aspect ratio
+    pub fn camera(&self, transform: &Transform, aspect: f32) -> Option<Camera> {
+        self.cameras.first().map(|camera| camera.to_camera(transform, aspect))
+    }
+}
+
+impl GltfLight {
+    /// Direction light travels in, which is -z of the light node
+    fn direction(&self) -> Vector3<f32> {
+        self.transform.vector(-Vector3::unit_z()).normalize()
+    }
+
+    /// Point and spot lights become spheres with intensity given in candela, spot cones
+    /// are ignored. Directional lights become disks far away from the scene, whose
+    /// radiance gives the illuminance in lux
+    fn to_shape<'a>(&self, transform: Transform, scene_center: Vector3<f32>, scene_size: f32) -> Shape<'a> {
+        match self.kind {
+            GltfLightKind::Point | GltfLightKind::Spot => {
+                let radiance = self.intensity / (PI * POINT_LIGHT_RADIUS * POINT_LIGHT_RADIUS);
+                let position = self.transform.point(vec3(0.0, 0.0, 0.0));
+                Shape::Sphere {
+                    radius: POINT_LIGHT_RADIUS,
+                    transform: transform * Transform::translation(position),
+                    material: light_material(self.color * radiance)
+                }
+            },
+            GltfLightKind::Directional => {
+                let direction = self.direction();
+                let distance = scene_size * SUN_DISTANCE_IN_SCENE_SIZES;
+                let solid_angle = PI * SUN_ANGULAR_RADIUS * SUN_ANGULAR_RADIUS;
+                // disk faces +y, which should look along the light direction
+                let rotation = Quaternion::from_arc(Vector3::unit_y(), direction, Some(Vector3::unit_x()));
+                Shape::Disk {
+                    radius: distance * SUN_ANGULAR_RADIUS.tan(),
+                    transform: transform * Transform::rigid(scene_center - direction * distance, rotation),
+                    material: light_material(self.color * (self.intensity / solid_angle))
+                }
+            }
+        }
+    }
+}
+
+fn light_material<'a>(color: Vector3<f32>) -> Material<'a> {
+    Material {
+        albedo: Albedo::Constant(color),
+        details: MaterialDetails::Lambertian,
+        emittance: 1.0,
+        relief: Relief::None
+    }
+}
+
+impl GltfCamera {
+    /// Tracer camera takes horizontal field of view, so the vertical one is converted using
+    /// the aspect ratio of the rendered image. The aspect ratio of the camera is ignored, since
+    /// converting with it would lose the vertical field of view of an image of another shape
+    pub fn to_camera(&self, transform: &Transform, aspect: f32) -> Camera {
+        let transform = *transform * self.transform;
+        Camera {
+            origin: transform.point(vec3(0.0, 0.0, 0.0)),
+            fov: ((self.yfov / 2.0).tan() * aspect).atan() * 2.0,
+            up: transform.vector(Vector3::unit_y()).normalize(),
+            direction: transform.vector(-Vector3::unit_z()).normalize()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Triangle whose accessors all read a zeroed buffer of 64 bytes
+    fn parse(accessors: &str, attributes: &str) -> Result<GltfScene, GltfError> {
+        let json = format!(r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": 64 }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": 64 }}],
+            "accessors": [{}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ {} }} }}] }}],
+            "nodes": [{{ "mesh": 0 }}],
+            "scenes": [{{ "nodes": [0] }}]
+        }}"#, accessors, attributes);
+        GltfScene::parse(json.as_bytes(), Some(&[0; 64]), Path::new(""))
+    }
+
+    const POSITIONS: &str = r#"{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }"#;
+
+    fn is_invalid(result: Result<GltfScene, GltfError>) -> bool {
+        matches!(result, Err(GltfError::Invalid(_)))
+    }
+
+    #[test]
+    fn reads_triangle() {
+        let scene = parse(POSITIONS, r#""POSITION": 0"#).unwrap();
+        assert_eq!(scene.primitives[0].mesh.vertices.len(), 3);
+        assert_eq!(scene.instances.len(), 1);
+    }
+
+    #[test]
+    fn rejects_attributes_of_wrong_size() {
+        let short_normals = r#"{ "bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3" }"#;
+        assert!(is_invalid(parse(&format!("{}, {}", POSITIONS, short_normals), r#""POSITION": 0, "NORMAL": 1"#)));
+        let flat_positions = r#"{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC2" }"#;
+        assert!(is_invalid(parse(flat_positions, r#""POSITION": 0"#)));
+        let vec3_tangents = POSITIONS;
+        assert!(is_invalid(parse(&format!("{}, {}", POSITIONS, vec3_tangents), r#""POSITION": 0, "TANGENT": 1"#)));
+    }
+
+    #[test]
+    fn rejects_accessors_out_of_buffer() {
+        let huge = format!(r#"{{ "bufferView": 0, "componentType": 5126, "count": {}, "type": "VEC3" }}"#, usize::MAX / 4);
+        assert!(is_invalid(parse(&huge, r#""POSITION": 0"#)));
+        let past_end = r#"{ "bufferView": 0, "byteOffset": 4, "componentType": 5126, "count": 1000, "type": "VEC3" }"#;
+        assert!(is_invalid(parse(past_end, r#""POSITION": 0"#)));
+        let zeros = format!(r#"{{ "componentType": 5126, "count": {}, "type": "VEC3" }}"#, usize::MAX / 2);
+        assert!(is_invalid(parse(&zeros, r#""POSITION": 0"#)));
+    }
+
+    /// File with the given nodes and root extensions, whose only mesh is a triangle
+    /// with positions in a base64 data uri
+    fn scene(nodes: &str, roots: &str, extra: &str) -> Result<GltfScene, GltfError> {
+        let json = format!(r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": 36, "uri": "data:application/octet-stream;base64,{}" }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+            "accessors": [{}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }} }}] }}],
+            "nodes": [{}],
+            "scenes": [{{ "nodes": [{}] }}]
+            {}
+        }}"#, TRIANGLE_BASE64, POSITIONS, nodes, roots, extra);
+        GltfScene::parse(json.as_bytes(), None, Path::new(""))
+    }
+
+    // (0, 0, 0), (1, 0, 0) and (0, 1, 0) as little endian floats
+    const TRIANGLE_BASE64: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA";
+
+    fn close(a: Vector3<f32>, b: Vector3<f32>) -> bool {
+        (a - b).magnitude() < 1e-5
+    }
+
+    #[test]
+    fn decodes_base64_data_uris() {
+        assert_eq!(decode_base64("TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        // url safe alphabet is accepted as well
+        assert_eq!(decode_base64("-_8=").unwrap(), decode_base64("+/8=").unwrap());
+        assert!(matches!(decode_base64("TW*u"), Err(GltfError::Invalid(_))));
+        let scene = scene(r#"{ "mesh": 0 }"#, "0", "").unwrap();
+        let positions = scene.primitives[0].mesh.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert!(close(positions[1], vec3(1.0, 0.0, 0.0)) && close(positions[2], vec3(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn loads_percent_encoded_file_uris() {
+        assert_eq!(decode_percent("my%20triangle.bin").unwrap(), "my triangle.bin");
+        assert!(matches!(decode_percent("bad%2"), Err(GltfError::Invalid(_))));
+
+        let dir = std::env::temp_dir().join(format!("rust-tracer-gltf-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("my triangle.bin"), decode_base64(TRIANGLE_BASE64).unwrap()).unwrap();
+        let json = |uri: &str| format!(r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": 36, "uri": "{}" }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+            "accessors": [{}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }} }}] }}],
+            "nodes": [{{ "mesh": 0 }}],
+            "scenes": [{{ "nodes": [0] }}]
+        }}"#, uri, POSITIONS);
+        let scene = GltfScene::parse(json("my%20triangle.bin").as_bytes(), None, &dir);
+        let outside = GltfScene::parse(json("../my%20triangle.bin").as_bytes(), None, &dir.join("models"));
+        let absolute = GltfScene::parse(json(&dir.join("my triangle.bin").to_string_lossy()).as_bytes(), None, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let positions = scene.unwrap().primitives[0].mesh.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert!(close(positions[1], vec3(1.0, 0.0, 0.0)) && close(positions[2], vec3(0.0, 1.0, 0.0)));
+        assert!(matches!(outside, Err(GltfError::Invalid(_))));
+        assert!(matches!(absolute, Err(GltfError::Invalid(_))));
+    }
+
+    #[test]
+    fn children_are_placed_by_their_parents() {
+        // the parent turns a quarter around z and doubles the size, the child moves along x
+        let nodes = r#"
+            { "children": [1], "translation": [0, 0, 1], "rotation": [0, 0, 0.70710677, 0.70710677], "scale": [2, 2, 2] },
+            { "mesh": 0, "translation": [1, 0, 0] }"#;
+        let scene = scene(nodes, "0", "").unwrap();
+        assert_eq!(scene.instances.len(), 1);
+        let transform = scene.instances[0].transform;
+        assert!(close(transform.point(vec3(0.0, 0.0, 0.0)), vec3(0.0, 2.0, 1.0)));
+        assert!(close(transform.point(vec3(1.0, 0.0, 0.0)), vec3(0.0, 4.0, 1.0)));
+    }
+
+    #[test]
+    fn imports_cameras_and_lights() {
+        let nodes = r#"
+            { "children": [1, 2, 3], "translation": [0, 0, 5] },
+            { "camera": 0 },
+            { "extensions": { "KHR_lights_punctual": { "light": 0 } }, "translation": [1, 0, 0] },
+            { "extensions": { "KHR_lights_punctual": { "light": 1 } }, "rotation": [-0.70710677, 0, 0, 0.70710677] }"#;
+        let extra = r#",
+            "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.8, "znear": 0.1 } }],
+            "extensions": { "KHR_lights_punctual": { "lights": [
+                { "type": "point", "color": [1, 0.5, 0], "intensity": 20 },
+                { "type": "directional" }
+            ] } }"#;
+        let scene = scene(nodes, "0", extra).unwrap();
+        let camera = scene.camera(&Transform::identity(), 1.0).unwrap();
+        assert!(close(camera.origin, vec3(0.0, 0.0, 5.0)));
+        assert!(close(camera.direction, vec3(0.0, 0.0, -1.0)));
+        assert!((camera.fov - 0.8).abs() < 1e-5);
+
+        assert_eq!(scene.lights.len(), 2);
+        let point = &scene.lights[0];
+        assert!(matches!(point.kind, GltfLightKind::Point));
+        assert!(close(point.color, vec3(1.0, 0.5, 0.0)));
+        assert_eq!(point.intensity, 20.0);
+        assert!(close(point.transform.point(vec3(0.0, 0.0, 0.0)), vec3(1.0, 0.0, 5.0)));
+        // directional light looks down -z of its node, which is turned to look down
+        let sun = &scene.lights[1];
+        assert!(matches!(sun.kind, GltfLightKind::Directional));
+        assert!(close(sun.direction(), vec3(0.0, -1.0, 0.0)));
+        assert_eq!(sun.intensity, 1.0);
+    }
+
+    #[test]
+    fn maps_metallic_roughness_materials() {
+        let extra = r#",
+            "materials": [
+                { "pbrMetallicRoughness": { "baseColorFactor": [1, 0, 0, 1], "metallicFactor": 0 } },
+                { "pbrMetallicRoughness": { "metallicFactor": 1, "roughnessFactor": 0.25 } },
+                { "extensions": { "KHR_materials_transmission": { "transmissionFactor": 1 }, "KHR_materials_ior": { "ior": 1.33 } } },
+                { "alphaMode": "BLEND", "pbrMetallicRoughness": { "baseColorFactor": [1, 1, 1, 0.5] } },
+                { "emissiveFactor": [1, 1, 0], "extensions": { "KHR_materials_emissive_strength": { "emissiveStrength": 4 } } }
+            ]"#;
+        let scene = scene(r#"{ "mesh": 0 }"#, "0", extra).unwrap();
+        let materials = scene.materials.iter().map(|m| m.to_material(&scene.images)).collect::<Vec<_>>();
+        assert!(matches!(materials[0].details, MaterialDetails::Lambertian));
+        assert!(matches!(materials[0].albedo, Albedo::Constant(c) if close(c, vec3(1.0, 0.0, 0.0))));
+        assert!(matches!(materials[1].details, MaterialDetails::Metallic { roughness } if roughness == 0.25));
+        assert!(matches!(materials[2].details, MaterialDetails::Dielectric { ref_idx, .. } if ref_idx == 1.33));
+        assert!(matches!(materials[3].details, MaterialDetails::Dielectric { ref_idx, .. } if ref_idx == 1.5));
+        assert_eq!(materials[4].emittance, 1.0);
+        assert!(matches!(materials[4].albedo, Albedo::Constant(c) if close(c, vec3(4.0, 4.0, 0.0))));
+    }
+
+    fn glb(chunks: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (kind, data) in chunks {
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&kind.to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+        let mut header = Vec::new();
+        header.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        header.extend_from_slice(&2u32.to_le_bytes());
+        header.extend_from_slice(&(12 + bytes.len() as u32).to_le_bytes());
+        header.extend_from_slice(&bytes);
+        header
+    }
+
+    #[test]
+    fn splits_glb_chunks() {
+        let json = r#"{
+            "asset": { "version": "2.0" },
+            "buffers": [{ "byteLength": 36 }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+            "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "nodes": [{ "mesh": 0 }],
+            "scenes": [{ "nodes": [0] }]
+        }"#;
+        let bin = decode_base64(TRIANGLE_BASE64).unwrap();
+        let bytes = glb(&[(GLB_JSON_CHUNK, json.as_bytes()), (GLB_BIN_CHUNK, &bin)]);
+        let (json_chunk, bin_chunk) = split_glb(&bytes).unwrap();
+        assert_eq!(json_chunk, json.as_bytes());
+        assert_eq!(bin_chunk, Some(bin.as_slice()));
+        let scene = GltfScene::parse(json_chunk, bin_chunk, Path::new("")).unwrap();
+        assert!(close(scene.primitives[0].mesh.vertices[1].position, vec3(1.0, 0.0, 0.0)));
+
+        assert!(matches!(split_glb(&bytes[..bytes.len() - 1]), Err(GltfError::Invalid(_))));
+        assert!(matches!(split_glb(&glb(&[(GLB_BIN_CHUNK, &bin)])), Err(GltfError::Invalid(_))));
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'x';
+        assert!(matches!(split_glb(&bad_magic), Err(GltfError::Invalid(_))));
+    }
+
+    #[test]
+    fn camera_keeps_vertical_fov_for_any_image() {
+        let camera = GltfCamera { transform: Transform::identity(), yfov: 1.0 };
+        for &aspect in [0.5f32, 1.0, 2.0].iter() {
+            let (_, up, forward) = camera.to_camera(&Transform::identity(), aspect).get_basis_vectors(aspect);
+            assert!((up.magnitude() / forward.magnitude() - 0.5f32.tan()).abs() < 1e-5, "{}", aspect);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use {
+    std::fmt,
+    cgmath::{Vector2, Vector3, InnerSpace, vec2, vec3},
+    crate::{
+        aabb::AaBb,
+        material::Material,
+        ray::{HitInfo, Ray},
+        mesh_data::{Shading, VertexDescription},
+        shape::test_ray_triangle_intersection
+    }
+};
+
+#[derive(Debug)]
+pub enum HeightfieldError {
+    TooSmall { width: usize, depth: usize },
+    PixelCount { expected: usize, found: usize }
+}
+
+impl fmt::Display for HeightfieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeightfieldError::TooSmall { width, depth } =>
+                write!(f, "heightfield needs at least 2x2 samples, got {}x{}", width, depth),
+            HeightfieldError::PixelCount { expected, found } =>
+                write!(f, "heightfield image should have {} pixels, got {}", expected, found)
+        }
+    }
+}
+
+/// Terrain of samples on a regular grid with every cell split into two triangles. Columns of
+/// the image go along x and rows along z, the grid spans extent centered at the origin and
+/// rises from zero up to height_scale
+pub struct Heightfield {
+    pub width: usize,
+    pub depth: usize,
+    pub extent: Vector2<f32>,
+    heights: Vec<f32>,
+    normals: Vec<Vector3<f32>>,
+    /// Lowest and highest sample of every cell for the finest level, and of every 2x2
+    /// block of the previous level for the next ones, up to a single block on top
+    levels: Vec<MinMaxLevel>
+}
+
+struct MinMaxLevel {
+    width: usize,
+    depth: usize,
+    bounds: Vec<(f32, f32)>
+}
+
+impl MinMaxLevel {
+    fn get(&self, x: usize, z: usize) -> (f32, f32) {
+        self.bounds[x + z * self.width]
+    }
+}
+
+impl Heightfield {
+    /// Greyscale image gives heights, colored ones are converted by their luminance
+    pub fn from_image(
+        width: usize,
+        depth: usize,
+        pixels: &[Vector3<f32>],
+        extent: Vector2<f32>,
+        height_scale: f32
+    ) -> Result<Self, HeightfieldError> {
+        if width < 2 || depth < 2 {
+            return Err(HeightfieldError::TooSmall { width, depth });
+        }
+        if pixels.len() != width * depth {
+            return Err(HeightfieldError::PixelCount { expected: width * depth, found: pixels.len() });
+        }
+        let heights: Vec<f32> = pixels
+            .iter()
+            .map(|color| color.dot(vec3(0.2126, 0.7152, 0.0722)) * height_scale)
+            .collect();
+        let mut field = Heightfield { width, depth, extent, heights, normals: Vec::new(), levels: Vec::new() };
+        field.normals = field.smooth_normals();
+        field.levels = field.min_max_levels();
+        Ok(field)
+    }
+
+    fn height(&self, x: usize, z: usize) -> f32 {
+        self.heights[x + z * self.width]
+    }
+
+    fn spacing(&self) -> Vector2<f32> {
+        vec2(self.extent.x / (self.width - 1) as f32, self.extent.y / (self.depth - 1) as f32)
+    }
+
+    fn position(&self, x: usize, z: usize) -> Vector3<f32> {
+        let spacing = self.spacing();
+        vec3(x as f32 * spacing.x - self.extent.x * 0.5, self.height(x, z), z as f32 * spacing.y - self.extent.y * 0.5)
+    }
+
+    /// Normals from central differences of heights, which turn into one sided ones on borders
+    fn smooth_normals(&self) -> Vec<Vector3<f32>> {
+        let spacing = self.spacing();
+        let mut normals = Vec::with_capacity(self.heights.len());
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                let (x0, x1) = (x.saturating_sub(1), (x + 1).min(self.width - 1));
+                let (z0, z1) = (z.saturating_sub(1), (z + 1).min(self.depth - 1));
+                let dhdx = (self.height(x1, z) - self.height(x0, z)) / ((x1 - x0) as f32 * spacing.x);
+                let dhdz = (self.height(x, z1) - self.height(x, z0)) / ((z1 - z0) as f32 * spacing.y);
+                normals.push(vec3(-dhdx, 1.0, -dhdz).normalize());
+            }
+        }
+        normals
+    }
+
+    fn min_max_levels(&self) -> Vec<MinMaxLevel> {
+        let (width, depth) = (self.width - 1, self.depth - 1);
+        let mut bounds = Vec::with_capacity(width * depth);
+        for z in 0..depth {
+            for x in 0..width {
+                let corners = [self.height(x, z), self.height(x + 1, z), self.height(x, z + 1), self.height(x + 1, z + 1)];
+                bounds.push(corners.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| (lo.min(h), hi.max(h))));
+            }
+        }
+        let mut levels = vec![MinMaxLevel { width, depth, bounds }];
+        while let Some(last) = levels.last().filter(|level| level.width > 1 || level.depth > 1) {
+            let (width, depth) = (last.width.div_ceil(2), last.depth.div_ceil(2));
+            let mut bounds = Vec::with_capacity(width * depth);
+            for z in 0..depth {
+                for x in 0..width {
+                    let mut block = (f32::INFINITY, f32::NEG_INFINITY);
+                    for (cx, cz) in [(2 * x, 2 * z), (2 * x + 1, 2 * z), (2 * x, 2 * z + 1), (2 * x + 1, 2 * z + 1)] {
+                        if cx < last.width && cz < last.depth {
+                            let (lo, hi) = last.get(cx, cz);
+                            block = (block.0.min(lo), block.1.max(hi));
+                        }
+                    }
+                    bounds.push(block);
+                }
+            }
+            levels.push(MinMaxLevel { width, depth, bounds });
+        }
+        levels
+    }
+
+    pub fn bounds(&self) -> AaBb {
+        let (lo, hi) = self.levels.last().unwrap().get(0, 0);
+        AaBb {
+            min: vec3(-self.extent.x * 0.5, lo, -self.extent.y * 0.5),
+            max: vec3(self.extent.x * 0.5, hi, self.extent.y * 0.5)
+        }
+    }
+
+    /// Box of a block of cells at a level, cells past the border are cut off
+    fn block_bounds(&self, level: usize, x: usize, z: usize) -> AaBb {
+        let spacing = self.spacing();
+        let (lo, hi) = self.levels[level].get(x, z);
+        let (x0, z0) = (x << level, z << level);
+        let (x1, z1) = (((x + 1) << level).min(self.width - 1), ((z + 1) << level).min(self.depth - 1));
+        AaBb {
+            min: vec3(x0 as f32 * spacing.x - self.extent.x * 0.5, lo, z0 as f32 * spacing.y - self.extent.y * 0.5),
+            max: vec3(x1 as f32 * spacing.x - self.extent.x * 0.5, hi, z1 as f32 * spacing.y - self.extent.y * 0.5)
+        }
+    }
+
+    /// Uvs follow the grid like they would for the image on a quad, so the image
+    /// (or one of the same aspect) could be its albedo
+    fn vertex(&self, x: usize, z: usize) -> VertexDescription {
+        VertexDescription {
+            position: self.position(x, z),
+            normal: self.normals[x + z * self.width],
+            uv: vec2(x as f32 / (self.width - 1) as f32, 1.0 - z as f32 / (self.depth - 1) as f32)
+        }
+    }
+}
+
+/// Every level halves the grid, so there are at most as many of them as bits of its size.
+/// Descending a level replaces a block on the stack with at most 4 of its children
+const STACK_SIZE: usize = 3 * usize::BITS as usize + 1;
+
+/// Descends the min/max hierarchy nearest block first, blocks the ray misses or enters beyond
+/// the nearest hit found so far are skipped along with everything below them. Cells of
+/// the finest level are tested as two triangles each
+pub fn test_ray_heightfield_intersection<'a>(
+    field: &Heightfield,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    let top = field.levels.len() - 1;
+    let inv_dir = vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+    let enter = |bounds: AaBb, t_max: f32| bounds.hit_distance(ray, &inv_dir, t_min, t_max);
+    let mut t_max = t_max;
+    let mut nearest: Option<HitInfo> = None;
+    let mut stack = [(0, 0, 0, 0.0); STACK_SIZE];
+    stack[0] = (top, 0, 0, enter(field.block_bounds(top, 0, 0), t_max)?);
+    let mut stack_size = 1;
+    while stack_size > 0 {
+        stack_size -= 1;
+        let (level, x, z, t_enter) = stack[stack_size];
+        if t_enter >= t_max {
+            continue;
+        }
+        if level == 0 {
+            let corners = [field.vertex(x, z), field.vertex(x + 1, z), field.vertex(x, z + 1), field.vertex(x + 1, z + 1)];
+            for triangle in [[corners[0], corners[2], corners[1]], [corners[1], corners[2], corners[3]]] {
+                if let Some(hit_info) = test_ray_triangle_intersection(&triangle, ray, t_min, t_max, material, Shading::Smooth, None) {
+                    t_max = hit_info.t;
+                    nearest = Some(hit_info);
+                }
+            }
+            continue;
+        }
+        let finer = &field.levels[level - 1];
+        let mut children = [(0, 0, 0, 0.0); 4];
+        let mut count = 0;
+        for (cx, cz) in [(2 * x, 2 * z), (2 * x + 1, 2 * z), (2 * x, 2 * z + 1), (2 * x + 1, 2 * z + 1)] {
+            if cx < finer.width && cz < finer.depth {
+                if let Some(t) = enter(field.block_bounds(level - 1, cx, cz), t_max) {
+                    children[count] = (level - 1, cx, cz, t);
+                    count += 1;
+                }
+            }
+        }
+        // the nearest child is popped first
+        let children = &mut children[..count];
+        children.sort_by(|a, b| b.3.total_cmp(&a.3));
+        stack[stack_size..stack_size + count].copy_from_slice(children);
+        stack_size += count;
+    }
+    // terrain isn't a solid, so it faces the ray from below as well
+    nearest.map(|hit_info| if hit_info.ng.dot(ray.direction) > 0.0 {
+        HitInfo { n: -hit_info.n, ng: -hit_info.ng, dpdv: -hit_info.dpdv, ..hit_info }
+    } else {
+        hit_info
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        rand::{Rng, SeedableRng, rngs::StdRng},
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn grey(value: f32) -> Vector3<f32> {
+        vec3(value, value, value)
+    }
+
+    #[test]
+    fn ray_hits_slope_of_known_cell() {
+        // 3x3 samples over [-1, 1] rising along x from 0 to 1
+        let pixels: Vec<_> = (0..9).map(|i| grey((i % 3) as f32 * 0.5)).collect();
+        let field = Heightfield::from_image(3, 3, &pixels, vec2(2.0, 2.0), 1.0).unwrap();
+        let slope_normal = vec3(-0.5, 1.0, 0.0).normalize();
+
+        let ray = Ray { origin: vec3(0.5, 5.0, 0.3), direction: vec3(0.0, -1.0, 0.0) };
+        let hit_info = test_ray_heightfield_intersection(&field, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 4.25).abs() < 1e-4);
+        assert!((hit_info.n - slope_normal).magnitude() < 1e-4);
+        assert!((hit_info.uv.unwrap() - vec2(0.75, 0.35)).magnitude() < 1e-4);
+
+        let ray = Ray { origin: vec3(0.5, -5.0, 0.3), direction: vec3(0.0, 1.0, 0.0) };
+        let hit_info = test_ray_heightfield_intersection(&field, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 5.75).abs() < 1e-4);
+        assert!((hit_info.n + slope_normal).magnitude() < 1e-4);
+        assert!((hit_info.ng + slope_normal).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn hierarchy_finds_same_hits_as_every_triangle() {
+        // sizes which aren't powers of two leave partial blocks on borders of levels
+        let mut rng = StdRng::seed_from_u64(49);
+        let (width, depth) = (5, 3);
+        let pixels: Vec<_> = (0..width * depth).map(|_| grey(rng.gen())).collect();
+        let field = Heightfield::from_image(width, depth, &pixels, vec2(4.0, 2.0), 1.0).unwrap();
+        let brute_force = |ray: &Ray| {
+            let mut nearest: Option<f32> = None;
+            for z in 0..depth - 1 {
+                for x in 0..width - 1 {
+                    let corners = [field.vertex(x, z), field.vertex(x + 1, z), field.vertex(x, z + 1), field.vertex(x + 1, z + 1)];
+                    for triangle in [[corners[0], corners[2], corners[1]], [corners[1], corners[2], corners[3]]] {
+                        let t_max = nearest.unwrap_or(f32::INFINITY);
+                        if let Some(hit_info) = test_ray_triangle_intersection(&triangle, ray, 0.0, t_max, &MATERIAL, Shading::Smooth, None) {
+                            nearest = Some(hit_info.t);
+                        }
+                    }
+                }
+            }
+            nearest
+        };
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let origin = vec3(rng.gen_range(-4.0, 4.0), rng.gen_range(-2.0, 3.0), rng.gen_range(-3.0, 3.0));
+            let target = vec3(rng.gen_range(-2.0, 2.0), rng.gen_range(0.0, 1.0), rng.gen_range(-1.0, 1.0));
+            let ray = Ray { origin, direction: target - origin };
+            let expected = brute_force(&ray);
+            let found = test_ray_heightfield_intersection(&field, &ray, 0.0, f32::INFINITY, &MATERIAL).map(|h| h.t);
+            match (expected, found) {
+                (Some(expected), Some(found)) => assert!((expected - found).abs() < 1e-5),
+                (None, None) => {},
+                _ => panic!("hierarchy found {:?} instead of {:?}", found, expected)
+            }
+            hits += expected.is_some() as usize;
+        }
+        assert!(hits > 100);
+    }
+
+    #[test]
+    fn rejects_bad_image_dimensions() {
+        let pixels = vec![grey(0.5); 6];
+        assert!(matches!(
+            Heightfield::from_image(1, 6, &pixels, vec2(1.0, 1.0), 1.0),
+            Err(HeightfieldError::TooSmall { width: 1, depth: 6 })
+        ));
+        assert!(matches!(
+            Heightfield::from_image(2, 2, &pixels, vec2(1.0, 1.0), 1.0),
+            Err(HeightfieldError::PixelCount { expected: 4, found: 6 })
+        ));
+    }
+}
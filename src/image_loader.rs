@@ -1,6 +1,6 @@
 use std::io::Read;
-use png::{Decoder, ColorType};
-use cgmath::{Vector3, vec3};
+use png::{Decoder, ColorType, DecodingError};
+use cgmath::{Vector3, InnerSpace, vec3};
 
 pub struct ImgData {
     pub width: usize,
@@ -8,12 +8,12 @@ pub struct ImgData {
     pub colors: Vec<Vector3<f32>>
 }
 
-pub fn load_png<R: Read>(r: R) -> ImgData {
-    let mut decoder = Decoder::new(r);
-    let (info, mut reader) = decoder.read_info().unwrap();
+pub fn load_png<R: Read>(r: R) -> Result<ImgData, DecodingError> {
+    let decoder = Decoder::new(r);
+    let (info, mut reader) = decoder.read_info()?;
     let (w, h) = (info.width as usize, info.height as usize);
     let mut buf = vec![0; info.buffer_size()];
-    reader.next_frame(&mut buf).unwrap();
+    reader.next_frame(&mut buf)?;
 
     let bytes_per_pixel = match reader.output_color_type()
     {
@@ -21,20 +21,20 @@ pub fn load_png<R: Read>(r: R) -> ImgData {
         (ColorType::RGB, _) => 3,
         (ColorType::Grayscale, _) => 1,
         (ColorType::GrayscaleAlpha, _) => 2,
-        _ => panic!("unsupported color type")
+        _ => return Err(DecodingError::Format("unsupported color type".into()))
     };
     let mut vec = vec![Vector3::new(0.0, 0.0, 0.0); w*h];
     let mut offset = 0;
-    for i in 0..w*h {
+    for color in vec.iter_mut() {
         if bytes_per_pixel > 2 {
-            vec[i] = vec3(
+            *color = vec3(
                 buf[offset] as f32 / 255.0,
                 buf[offset + 1] as f32 / 255.0,
                 buf[offset + 2] as f32 / 255.0
             );
         } else {
             let luma = buf[offset] as f32 / 255.0;
-            vec[i] = vec3(
+            *color = vec3(
                 luma,
                 luma,
                 luma
@@ -42,5 +42,23 @@ pub fn load_png<R: Read>(r: R) -> ImgData {
         }
         offset += bytes_per_pixel;
     }
-    ImgData {width: w, height: h, colors: vec}
+    Ok(ImgData {width: w, height: h, colors: vec})
+}
+
+/// Turns luminance of an image into a tangent space normal map, bumps get steeper with scale.
+/// Rows go down the image while v goes up, so the v slope is taken upwards
+pub fn height_to_normal_map(image: &ImgData, scale: f32) -> ImgData {
+    let (w, h) = (image.width, image.height);
+    let height = |x: usize, y: usize| image.colors[x + y * w].dot(vec3(0.2126, 0.7152, 0.0722));
+    let mut colors = Vec::with_capacity(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            // maps of spheres wrap around horizontally
+            let du = height((x + 1) % w, y) - height((x + w - 1) % w, y);
+            let dv = height(x, y.saturating_sub(1)) - height(x, (y + 1).min(h - 1));
+            let n = vec3(-du * scale * 0.5, -dv * scale * 0.5, 1.0).normalize();
+            colors.push(n * 0.5 + vec3(0.5, 0.5, 0.5));
+        }
+    }
+    ImgData { width: w, height: h, colors }
 }
\ No newline at end of file
@@ -9,70 +9,121 @@ mod world;
 mod material;
 mod camera;
 mod vector_utils;
+mod sampling;
 mod scene;
 mod image_loader;
-mod bunny;
+mod assets;
 mod aabb;
+mod bvh;
+mod transform;
+mod scene_graph;
+mod float_utils;
+mod mesh_data;
+mod obj_loader;
+mod obj_parser;
+mod ply_loader;
+mod stl_loader;
+mod gltf_loader;
+mod model;
+mod displacement;
+mod quadrics;
+mod polynomial;
+mod torus;
+mod quartic;
+mod csg;
+mod sdf;
+mod voxel;
+mod vox_loader;
+mod heightfield;
+mod metaballs;
 
 use {
     cgmath::{
+        Vector2,
         Vector3,
         Quaternion,
         Euler,
         Rad,
-        vec2,
-        vec3,
-        Angle,
-        VectorSpace,
-        InnerSpace
+        Matrix4,
+        InnerSpace,
+        vec3
     },
-    png::{Decoder, ColorType},
-    std::env,
+    std::{env, f32::consts::PI, path::Path, process},
     shape::*,
     world::*,
     material::*,
     image_loader::*,
-    scene::*
+    scene::*,
+    camera::{Camera, Origin, Up, Fov, Target},
+    transform::Transform,
+    aabb::AaBb,
+    model::Model,
+    mesh_data::MeshData,
+    displacement::displaced_mesh,
+    heightfield::Heightfield,
+    metaballs::Metaball,
+    scene_graph::SceneNode
 };
-use crate::aabb::AaBb;
-use png::Compression::Fast;
 
 const NUM_SAMPLES: u16 = 400;
 const FOCUS_DISTANCE: f32 = 1.6;
 const APERTURE: f32 = 0.035;
 const MAX_T: f32 = 400.0;
+const WIDTH: usize = 640;
+const HEIGHT: usize = 400;
+
 
 const MOON_MAP_BYTES: &[u8] = include_bytes!("moonmap.png");
 const EARTH_MAP_BYTES: &[u8] = include_bytes!("earthmap.png");
+const MOON_RADIUS: f32 = 0.5;
+const MOON_DISPLACEMENT: f32 = 0.02;
+// about ten texels of the moon map, finer edges only add triangles the silhouette doesn't show
+const MOON_MAX_EDGE_LENGTH: f32 = 0.03;
+const GLOBE_RADIUS: f32 = 0.1;
+// craters of the moon globe are carved from its albedo, darker spots being lower
+const MOON_GLOBE_NORMAL_SCALE: f32 = 4.0;
+const EARTH_GLOBE_BUMP_STRENGTH: f32 = 0.005;
+const EARTH_EXTENT: Vector2<f32> = Vector2::new(8.0, 4.0);
+const EARTH_HEIGHT_SCALE: f32 = 0.05;
+const METABALLS_PERIOD: f32 = 100.0;
+const METABALLS_THRESHOLD: f32 = 0.5;
+const LAMP_SWING: f32 = 0.2;
+/// Horizontal field of view of the camera for gltf scenes without one, in degrees
+const FRAMING_FOV: f32 = 60.0;
 
 const LIGHT_GRAY_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(0.8, 0.8, 0.8)),
     details: MaterialDetails::Metallic {roughness: 0.2},
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const DARK_GRAY_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(0.1, 0.1, 0.1)),
     details: MaterialDetails::Metallic {roughness: 0.6},
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const BLUE_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(0.1, 0.1, 1.0)),
     details: MaterialDetails::Dielectric { ref_idx: 1.1, roughness: 0.1 },
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const LIGHT_GRAY_MAT_LAMBERT: Material = Material {
     albedo: Albedo::Constant(vec3(0.8, 0.8, 0.8)),
     details: MaterialDetails::Lambertian,
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const WHITE_BULB_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(3.0, 3.0, 3.0)),
     details: MaterialDetails::Lambertian,
-    emittance: 1.0
+    emittance: 1.0,
+    relief: Relief::None
 };
 
 const DIELECTRIC_MAT: Material = Material {
@@ -81,163 +132,308 @@ const DIELECTRIC_MAT: Material = Material {
         ref_idx: 1.5,
         roughness: 0.0
     },
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const RED_MIRROR_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(1.0, 0.0, 0.0)),
     details: MaterialDetails::Metallic {roughness: 0.0},
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
 const CHECKER_MAT_2: Material = Material {
     albedo: Albedo::Checker(2.0),
     details: MaterialDetails::Lambertian,
-    emittance: 0.0
+    emittance: 0.0,
+    relief: Relief::None
 };
 
+// dimples of an orange peel, u goes around the sphere and v along its axis
+fn orange_peel(uv: Vector2<f32>) -> f32 {
+    let (u, v) = (uv.x * 2.0 * PI * 24.0, uv.y * PI * 24.0);
+    (u.sin() * v.sin()).abs()
+}
+
 const ORANGE_MAT: Material = Material {
     albedo: Albedo::Constant(vec3(2.0, 0.8, 0.0)),
     details: MaterialDetails::Lambertian,
-    emittance: 0.9
+    emittance: 0.9,
+    relief: Relief::Bump { height: Height::Procedural(orange_peel), strength: 0.002 }
 };
 
+// blobs circle around a stretched one and climb it, they go around once per period of frames
+fn metaballs(t: u64) -> [Metaball; 4] {
+    let angle = t as f32 * 2.0 * PI / METABALLS_PERIOD;
+    let orbit = |phase: f32, height: f32| vec3((angle + phase).cos() * 0.06, height, (angle + phase).sin() * 0.06);
+    [
+        Metaball::Segment { a: vec3(0.0, 0.0, 0.0), b: vec3(0.0, 0.14, 0.0), radius: 0.08, weight: 1.0 },
+        Metaball::Point { center: orbit(0.0, 0.02 + 0.02 * angle.sin()), radius: 0.07, weight: 1.0 },
+        Metaball::Point { center: orbit(2.0 * PI / 3.0, 0.07 + 0.02 * angle.cos()), radius: 0.07, weight: 1.0 },
+        Metaball::Point { center: orbit(4.0 * PI / 3.0, 0.12 - 0.02 * angle.sin()), radius: 0.07, weight: 1.0 }
+    ]
+}
+
+// lamp made of a cube base, a sphere knob and a disk bulb, which is moved as a whole
+fn lamp(transform: Transform) -> SceneNode<'static> {
+    SceneNode::group(transform, vec![
+        SceneNode::Shape(Shape::Cube {
+            sizes: vec3(0.12, 0.03, 0.12),
+            transform: Transform::translation(vec3(0.0, 0.015, 0.0)),
+            material: DARK_GRAY_MAT
+        }),
+        SceneNode::Shape(Shape::Sphere {
+            radius: 0.025,
+            transform: Transform::translation(vec3(0.0, 0.055, 0.0)),
+            material: RED_MIRROR_MAT
+        }),
+        SceneNode::Shape(Shape::Disk {
+            radius: 0.04,
+            transform: Transform::translation(vec3(0.0, 0.08, 0.0)),
+            material: WHITE_BULB_MAT
+        })
+    ])
+}
+
 fn main() {
     let mut args = env::args();
     if args.len() < 2 {
-        eprintln!("Usage: rust-tracer N > some.ppm");
+        eprintln!("Usage: rust-tracer N [model.obj|ply|stl|gltf|glb|vox] > some.ppm");
         return;
     }
     let t: u64 = args.nth(1).unwrap().parse().unwrap();
 
+    let model = args.next().map(|path| Model::load(Path::new(&path)).unwrap_or_else(|e| {
+        eprintln!("Failed to load {}: {}", path, e);
+        process::exit(1)
+    }));
+    if let Some(Model::Mesh(mesh)) = &model {
+        for file_name in &mesh.missing_libraries {
+            eprintln!("Skipping missing materials {}", file_name);
+        }
+    }
+    let model_bvhs = model.as_ref().map_or(Vec::new(), |model| model.build_bvhs());
+
     let quat_identity: Quaternion<f32> = Quaternion::new(0.0, 0.0, 1.0, 0.0);
     let quat_flip180_z: Quaternion<f32> = Euler::new(Rad(0.0), Rad(0.0), Rad(180.0f32.to_radians())).into();
 
+    let moon_map = load_png(MOON_MAP_BYTES).unwrap();
+    let moon_normal_map = height_to_normal_map(&moon_map, MOON_GLOBE_NORMAL_SCALE);
     let ImgData{
         width: moon_map_width,
         height: moon_map_height,
         colors: moon_map_colors
-    } = load_png(MOON_MAP_BYTES);
+    } = moon_map;
 
     let moon_map_mat = Material {
         albedo: Albedo::Texture(moon_map_width, moon_map_height, &moon_map_colors),
         details: MaterialDetails::Lambertian,
-        emittance: 0.0
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    // craters are carved from the albedo, darker spots being lower, and they are displaced
+    // instead of bumped so they show up on the silhouette of the moon as well
+    let moon_mesh = displaced_mesh(
+        &MeshData::uv_sphere(MOON_RADIUS, 64, 32),
+        Height::Texture(moon_map_width, moon_map_height, &moon_map_colors),
+        MOON_DISPLACEMENT,
+        MOON_MAX_EDGE_LENGTH
+    );
+    let moon_bvh = moon_mesh.description().build_bvh();
+
+    // small copy of the moon shows a relief which doesn't change the geometry
+    let moon_globe_mat = Material {
+        relief: Relief::NormalMap {
+            width: moon_normal_map.width,
+            height: moon_normal_map.height,
+            normals: &moon_normal_map.colors,
+            strength: 1.0
+        },
+        ..moon_map_mat
     };
 
     let ImgData{
         width: earth_map_width,
         height: earth_map_height,
         colors: earth_map_colors
-    } = load_png(EARTH_MAP_BYTES);
+    } = load_png(EARTH_MAP_BYTES).unwrap();
 
     let earth_map_mat = Material {
         albedo: Albedo::Texture(earth_map_width, earth_map_height, &earth_map_colors),
         details: MaterialDetails::Lambertian,
-        emittance: 0.0
+        emittance: 0.0,
+        relief: Relief::None
+    };
+    // land is brighter than oceans, so it rises above them on both the small copy of
+    // the earth, which is only bumped, and the terrain
+    let earth_globe_mat = Material {
+        relief: Relief::Bump {
+            height: Height::Texture(earth_map_width, earth_map_height, &earth_map_colors),
+            strength: EARTH_GLOBE_BUMP_STRENGTH
+        },
+        ..earth_map_mat
     };
+    let earth_terrain = Heightfield::from_image(
+        earth_map_width,
+        earth_map_height,
+        &earth_map_colors,
+        EARTH_EXTENT,
+        EARTH_HEIGHT_SCALE
+    ).unwrap();
 
-    let bunny_vertices = bunny::VERTICES_0
-        .iter()
-        .map(|((vx, vy, vz), (nx, ny, nz), (u, v))| VertexDescription {
-            position: vec3(*vx, *vy, *vz),
-            normal: vec3(*nx, *ny, *nz),
-            uv: vec2(*u, *v)
-        })
-        .collect::<Vec<_>>();
+    let metaball_sources = metaballs(t);
 
-    let bunny_mesh = MeshDescription {
-        vertices: &bunny_vertices,
-        indices: bunny::INDICES_0,
-        triangle_count: bunny::COUNT_0
-    };
+    let bunny_mesh = assets::bunny::MESHES[0];
+
+    let bunny_bvh = bunny_mesh.build_bvh();
 
     let bunny_shape = Shape::TriangleMesh {
-        center: vec3(0.55, -0.5, 0.65),
+        transform: Transform::translation(vec3(0.55, -0.5, 0.65)),
         mesh: bunny_mesh,
         material: LIGHT_GRAY_MAT_LAMBERT,
-        face_oct_tree: None
+        bvh: Some(&bunny_bvh)
     };
-    let bunny_aabb: Option<AaBb> = bunny_shape.into();
-    let mut bunny_face_oct_tree = FaceOctTree::make(bunny_aabb.unwrap(), 2);
-    let bunny_shape = bunny_shape.extend_with_oct_tree(&mut bunny_face_oct_tree);
 
-    let shapes = &[
+    let mut shapes = vec![
         Shape::Disk{
-            center: vec3(-0.85, 0.49, 1.05),
             radius: 0.125,
-            rotation: quat_flip180_z,
+            transform: Transform::rigid(vec3(-0.85, 0.49, 1.05), quat_flip180_z),
             material: WHITE_BULB_MAT
         },
         Shape::Disk{
-            center: vec3(0.85, 0.49, 1.05),
             radius: 0.125 / 2.0,
-            rotation: quat_flip180_z,
+            transform: Transform::rigid(vec3(0.85, 0.49, 1.05), quat_flip180_z),
             material: WHITE_BULB_MAT
         },
         Shape::Disk{
-            center: vec3(0.0, 0.49, -1.05),
             radius: 0.125 / 4.0,
-            rotation: quat_flip180_z,
+            transform: Transform::rigid(vec3(0.0, 0.49, -1.05), quat_flip180_z),
             material: WHITE_BULB_MAT
         },
         Shape::Sphere{
-            center: vec3(-0.6, -0.3, 0.7),
             radius: 0.15,
-            rotation: quat_identity,
+            transform: Transform::rigid(vec3(-0.6, -0.3, 0.7), quat_identity),
             material: DIELECTRIC_MAT
         },
-        Shape::Sphere{
-            center: vec3(0.0, 0.0, 1.0),
-            radius: 0.5,
-            rotation: quat_identity,
-            material: moon_map_mat
+        Shape::TriangleMesh{
+            transform: Transform::rigid(vec3(0.0, 0.0, 1.0), quat_identity),
+            mesh: moon_mesh.description(),
+            material: moon_map_mat,
+            bvh: Some(&moon_bvh)
         },
         Shape::Sphere{
-            center: vec3(0.25, -0.4, 0.65),
             radius: 0.1,
-            rotation: quat_identity,
+            transform: Transform::rigid(vec3(0.25, -0.4, 0.65), quat_identity),
             material: ORANGE_MAT
         },
         Shape::Cube {
-            center: vec3(-0.25, -0.4, 0.35),
             sizes: vec3(0.2, 0.2, 0.2),
-            rotation: Quaternion::new(0.5, 0.0, 1.0, 0.0),
+            transform: Transform::rigid(vec3(-0.25, -0.4, 0.35), Quaternion::new(0.5, 0.0, 1.0, 0.0)),
             material: CHECKER_MAT_2
         },
         Shape::Sphere{
-            center: vec3(0.15, -0.45, 0.55),
             radius: 0.05,
-            rotation: quat_identity,
+            transform: Transform::rigid(vec3(0.15, -0.45, 0.55), quat_identity),
             material: DARK_GRAY_MAT
         },
         Shape::Sphere{
-            center: vec3(-0.75, -0.45, 0.75),
             radius: 0.05,
-            rotation: quat_identity,
+            transform: Transform::rigid(vec3(-0.75, -0.45, 0.75), quat_identity),
             material: DARK_GRAY_MAT
         },
-        Shape::Disk{
-            center: vec3(0.0, -0.5, 1.0),
-            radius: 2.0,
-            rotation: quat_identity,
-            material: earth_map_mat
+        Shape::Sphere{
+            radius: GLOBE_RADIUS,
+            transform: Transform::rigid(vec3(-0.95, -0.38, 1.3), quat_identity),
+            material: moon_globe_mat
         },
         Shape::Sphere{
-            center: vec3(0.0, 100.0, 1.0),
-            radius: 99.5,
-            rotation: quat_identity,
-            material: LIGHT_GRAY_MAT
+            radius: GLOBE_RADIUS,
+            transform: Transform::rigid(vec3(-0.95, -0.38, 0.8), quat_identity),
+            material: earth_globe_mat
+        },
+        Shape::Metaballs{
+            sources: &metaball_sources,
+            threshold: METABALLS_THRESHOLD,
+            transform: Transform::rigid(vec3(-0.55, -0.45, 1.4), quat_identity),
+            material: BLUE_MAT
         },
-        bunny_shape
+        Shape::Heightfield{
+            field: &earth_terrain,
+            transform: Transform::rigid(vec3(0.0, -0.5, 1.0), quat_identity),
+            material: earth_map_mat
+        },
+        Shape::Plane{
+            two_sided: false,
+            transform: Transform::rigid(vec3(0.0, 0.5, 1.0), quat_flip180_z),
+            material: LIGHT_GRAY_MAT
+        }
     ];
 
+    // the lamp rocks to and fro once per period of the metaballs
+    let lamp_angle = (t as f32 * 2.0 * PI / METABALLS_PERIOD).sin() * LAMP_SWING;
+    let lamp_rotation: Quaternion<f32> = Euler::new(Rad(0.0), Rad(0.0), Rad(lamp_angle)).into();
+    shapes.extend(lamp(Transform::rigid(vec3(-0.2, -0.5, 1.6), lamp_rotation)).flatten());
+
+    let mut camera = None;
+    match &model {
+        // gltf scenes come with their own lights and cameras, so they are rendered alone
+        Some(Model::Scene(scene)) => {
+            let aspect = WIDTH as f32 / HEIGHT as f32;
+            shapes = scene.shapes(&model_bvhs, Transform::identity(), LIGHT_GRAY_MAT_LAMBERT);
+            camera = scene
+                .camera(&Transform::identity(), aspect)
+                .or_else(|| scene.bounds().map(|bounds| framing_camera(&bounds, aspect)));
+        },
+        Some(model) => {
+            // loaded model takes place of the bunny
+            let bunny_aabb: Option<AaBb> = bunny_shape.into();
+            if let (Some(model_aabb), Some(bunny_aabb)) = (model.bounds(), bunny_aabb) {
+                let transform = fit_into(&model_aabb, &bunny_aabb);
+                shapes.extend(model.shapes(&model_bvhs, transform, LIGHT_GRAY_MAT_LAMBERT));
+            }
+        },
+        None => shapes.push(bunny_shape)
+    }
+
     let scene = Scene {
         focus_distance: FOCUS_DISTANCE,
-        aperture: APERTURE,
+        // imported cameras are pinholes, since the focus distance is unknown
+        aperture: if camera.is_some() { 0.0 } else { APERTURE },
         num_samples: NUM_SAMPLES,
         max_t: MAX_T,
-        world: World::construct(shapes)
+        world: World::construct(&shapes),
+        camera
     };
-    scene.render_as_ppm(t, 640, 400);
+    scene.render_as_ppm(t, WIDTH, HEIGHT);
+}
+
+/// Camera looking at the box from the front and a bit above, far enough to see all of it
+fn framing_camera(bounds: &AaBb, aspect: f32) -> Camera {
+    let fov = FRAMING_FOV.to_radians();
+    let vertical_half_fov = ((fov / 2.0).tan() / aspect).atan();
+    let radius = (bounds.max - bounds.min).magnitude() * 0.5;
+    let target = bounds.centroid();
+    Camera::new(
+        Origin(target + vec3(0.0, 0.5, 1.0).normalize() * (radius / vertical_half_fov.sin())),
+        Up(Vector3::unit_y()),
+        Fov(fov),
+        Target(target)
+    )
+}
+
+/// Uniformly scales and moves an object with bounds source, so it stands on the bottom
+/// of target in its center and fits inside it. It is only moved there when target is flat
+fn fit_into(source: &AaBb, target: &AaBb) -> Transform {
+    let (source_size, target_size) = (source.max - source.min, target.max - target.min);
+    let scale = (0..3)
+        .filter(|&a| source_size[a] > 0.0)
+        .map(|a| target_size[a] / source_size[a])
+        .fold(f32::MAX, f32::min);
+    let scale = if scale == f32::MAX { 1.0 } else { scale };
+    let source_base = vec3(source.centroid().x, source.min.y, source.centroid().z);
+    let target_base = vec3(target.centroid().x, target.min.y, target.centroid().z);
+    Transform::from_matrix(Matrix4::from_translation(target_base - source_base * scale) * Matrix4::from_scale(scale))
+        .unwrap_or_else(|| Transform::translation(target_base - source_base))
 }
@@ -1,7 +1,8 @@
 use {
     cgmath::{Vector2, Vector3, InnerSpace, VectorSpace, vec2, vec3},
     crate::ray::{HitInfo, Ray},
-    crate::vector_utils::*,
+    crate::sampling::get_random_in_unit_sphere,
+    crate::vector_utils::orthonormal_basis,
     rand:: {
         prelude::ThreadRng,
         Rng
@@ -19,39 +20,123 @@ pub enum Albedo<'a> {
     Texture(usize, usize, &'a[Vector3<f32>]),
 }
 
+/// Bilinearly filtered texel at uv, v goes up from the bottom row of the image. U wraps
+/// around, so filtering across the seam of a sphere blends both edges of the image,
+/// and v is clamped to the top and bottom rows
+fn sample_texture(w: usize, h: usize, pixels: &[Vector3<f32>], uv: Vector2<f32>) -> Vector3<f32> {
+    let (wf, hf) = (w as f32, h as f32);
+    let (u, v) = (uv.x.rem_euclid(1.0) * wf, (1.0 - uv.y).clamp(0.0, 1.0) * hf);
+    let (h_t, v_t) = (u.fract(), v.fract());
+
+    // rem_euclid of tiny negative numbers rounds up to 1
+    let u = (u as usize) % w;
+    let v = (v as usize).min(h - 1);
+    let next_u = (u + 1) % w;
+    let next_v = (v + 1).min(h - 1);
+
+    let px0_idx = u + v * w;
+    let px1_idx = next_u + v * w;
+    let px2_idx = u + next_v * w;
+    let px3_idx = next_u + next_v * w;
+    let p0 = pixels[px0_idx].lerp(pixels[px1_idx], h_t);
+    let p1 = pixels[px2_idx].lerp(pixels[px3_idx], h_t);
+    p0.lerp(p1, v_t)
+}
+
 impl AlbedoFn for Albedo<'_> {
     fn get_color(&self, uv: Vector2<f32>) -> Vector3<f32> {
         match self {
             Albedo::Constant(color) => *color,
             Albedo::Checker(scale) => {
+                // cells are floored, so they keep alternating at negative coordinates
                 let uv_scale = uv * *scale;
-                let x_even = uv_scale.x as u32 & 1 == 0;
-                let y_even = uv_scale.y as u32 & 1 == 0;
+                let x_even = uv_scale.x.floor() as i64 & 1 == 0;
+                let y_even = uv_scale.y.floor() as i64 & 1 == 0;
                 if (x_even && !y_even) || (y_even && !x_even) {
                     vec3(0.0, 0.0, 0.0)
                 } else {
                     vec3(1.0, 1.0, 1.0)
                 }
             },
-            Albedo::Texture(w, h, pixels) => {
-                let (w, h) = (*w, *h);
-                let (wf, hf) = (w as f32, h as f32);
-                let (u, v) = (uv.x * wf, hf - uv.y * hf);
-                let (h_t, v_t) = (u.fract(), v.fract());
-
-                let u = (u.trunc() as usize).min(w - 1);
-                let v = (v.trunc() as usize).min(h - 1);
-                let next_u = (u + 1).min(w - 1);
-                let next_v = (v + 1).min(h - 1);
-
-                let px0_idx = u + v * w;
-                let px1_idx = next_u + v * w;
-                let px2_idx = u + next_v * w;
-                let px3_idx = next_u + next_v * w;
-                let p0 = pixels[px0_idx].lerp(pixels[px1_idx], h_t);
-                let p1 = pixels[px2_idx].lerp(pixels[px3_idx], h_t);
-                p0.lerp(p1, v_t)
+            Albedo::Texture(w, h, pixels) => sample_texture(*w, *h, pixels, uv),
+        }
+    }
+}
+
+/// Height of a bumpy surface at uv
+#[derive(Copy, Clone)]
+pub enum Height<'a> {
+    /// greyscale image, colored ones are converted by their luminance
+    Texture(usize, usize, &'a [Vector3<f32>]),
+    Procedural(fn(Vector2<f32>) -> f32)
+}
+
+impl Height<'_> {
+    pub fn get(&self, uv: Vector2<f32>) -> f32 {
+        match *self {
+            Height::Texture(w, h, pixels) => sample_texture(w, h, pixels, uv).dot(vec3(0.2126, 0.7152, 0.0722)),
+            Height::Procedural(height) => height(uv)
+        }
+    }
+
+    /// Step of finite differences in uv, which is a texel for textures
+    fn delta(&self) -> Vector2<f32> {
+        match *self {
+            Height::Texture(w, h, _) => vec2(1.0 / w as f32, 1.0 / h as f32),
+            Height::Procedural(_) => vec2(PROCEDURAL_BUMP_DELTA, PROCEDURAL_BUMP_DELTA)
+        }
+    }
+}
+
+const PROCEDURAL_BUMP_DELTA: f32 = 1e-3;
+
+/// Perturbation of shading normals, which gives a surface relief without extra geometry
+#[derive(Copy, Clone)]
+pub enum Relief<'a> {
+    None,
+    /// Tangent space normal map, x goes along dpdu, y along dpdv and z along the normal.
+    /// Strength scales how much normals are tilted
+    NormalMap { width: usize, height: usize, normals: &'a [Vector3<f32>], strength: f32 },
+    /// Surface pushed along its normal by height times strength, so strength is
+    /// the displacement of the highest point in units of the shape
+    Bump { height: Height<'a>, strength: f32 }
+}
+
+/// Tangent and bitangent at the hit, made orthogonal to its normal
+fn tangent_frame(hit: &HitInfo) -> (Vector3<f32>, Vector3<f32>) {
+    let n = hit.n;
+    let t = hit.dpdu - n * n.dot(hit.dpdu);
+    if t.magnitude2() == 0.0 {
+        return orthonormal_basis(n);
+    }
+    let t = t.normalize();
+    // v may go either way around the tangent, mirrored uvs are common in meshes
+    let b = n.cross(t);
+    if b.dot(hit.dpdv) < 0.0 { (t, -b) } else { (t, b) }
+}
+
+impl Relief<'_> {
+    pub fn shading_normal(&self, hit: &HitInfo) -> Vector3<f32> {
+        match (*self, hit.uv) {
+            (Relief::NormalMap { width, height, normals, strength }, Some(uv)) => {
+                let (t, b) = tangent_frame(hit);
+                let m = sample_texture(width, height, normals, uv) * 2.0 - vec3(1.0, 1.0, 1.0);
+                let n = t * (m.x * strength) + b * (m.y * strength) + hit.n * m.z;
+                if n.magnitude2() > 0.0 { n.normalize() } else { hit.n }
+            },
+            (Relief::Bump { height, strength }, Some(uv)) => {
+                let delta = height.delta();
+                let dhdu = (height.get(uv + vec2(delta.x, 0.0)) - height.get(uv - vec2(delta.x, 0.0))) / (2.0 * delta.x);
+                let dhdv = (height.get(uv + vec2(0.0, delta.y)) - height.get(uv - vec2(0.0, delta.y))) / (2.0 * delta.y);
+                // derivatives of the displaced point, change of the normal itself is neglected
+                let dpdu = hit.dpdu + hit.n * (dhdu * strength);
+                let dpdv = hit.dpdv + hit.n * (dhdv * strength);
+                let n = dpdu.cross(dpdv);
+                // uv could be mirrored, so the normal is kept on the side of the original one
+                let n = if hit.dpdu.cross(hit.dpdv).dot(hit.n) < 0.0 { -n } else { n };
+                if n.magnitude2() > 0.0 { n.normalize() } else { hit.n }
             },
+            _ => hit.n
         }
     }
 }
@@ -67,7 +152,8 @@ pub enum MaterialDetails {
 pub struct Material<'a> {
     pub albedo: Albedo<'a>,
     pub emittance: f32,
-    pub details: MaterialDetails
+    pub details: MaterialDetails,
+    pub relief: Relief<'a>
 }
 
 fn reflect(v: &Vector3<f32>, n: &Vector3<f32>) -> Vector3<f32> {
@@ -89,20 +175,25 @@ impl Material<'_> {
     pub fn scatter(&self, ray_in:&Ray, rng: &mut ThreadRng, hit: &HitInfo)
         -> Option<(Vector3<f32>, Ray)>
     {
-        let &HitInfo{n: normal, p: point, uv, ..} = hit;
+        let &HitInfo{p: point, uv, ..} = hit;
+        let normal = self.relief.shading_normal(hit);
         let uv = uv.unwrap_or(vec2(0.0, 0.0));
         let albedo = self.albedo.get_color(uv);
+        // surfaces which aren't solids (like open meshes) are hit from behind as well, and
+        // only dielectrics care which side is the outer one, others scatter back to the ray
+        let facing = if ray_in.direction.dot(hit.n) > 0.0 { -1.0 } else { 1.0 };
         match self.details {
             MaterialDetails::Lambertian => {
-                let target = point + normal + get_random_in_unit_sphere(rng);
-                let ray_reflect = Ray{origin : point, direction: (target - point).normalize()};
+                let target = point + normal * facing + get_random_in_unit_sphere(rng);
+                let ray_reflect = hit.spawn_ray((target - point).normalize());
                 Some((albedo, ray_reflect))
             },
             MaterialDetails::Metallic { roughness } => {
                 let reflected_ray_dir = reflect(&ray_in.direction, &normal);
-                if reflected_ray_dir.dot(normal) > 0.0 {
+                // relief only tilts the reflection, rays going into the surface itself are absorbed
+                if reflected_ray_dir.dot(hit.n * facing) > 0.0 {
                     let target = point + reflected_ray_dir + get_random_in_unit_sphere(rng) * roughness;
-                    let ray_reflect = Ray{origin : point, direction: (target - point).normalize()};
+                    let ray_reflect = hit.spawn_ray((target - point).normalize());
                     Some((albedo, ray_reflect))
                 } else {
                     None
@@ -110,16 +201,18 @@ impl Material<'_> {
             }
             MaterialDetails::Dielectric { ref_idx, roughness } => {
                 let reflected_ray_dir = reflect(&ray_in.direction, &normal);
-                let cosine = ray_in.direction.dot(hit.n);
-                let (outward_n, ni_over_nt, cosine) = if ray_in.direction.dot(hit.n) > 0.0 {
-                    (-hit.n, ref_idx, cosine)
+                let cosine = ray_in.direction.dot(normal).abs();
+                // whether the ray leaves the solid is up to the surface itself, a steep relief
+                // could turn the perturbed normal away from the ray even when it enters
+                let (outward_n, ni_over_nt) = if ray_in.direction.dot(hit.n) > 0.0 {
+                    (-normal, ref_idx)
                 } else {
-                    (hit.n, 1.0 / ref_idx, -cosine)
+                    (normal, 1.0 / ref_idx)
                 };
                 match refract(&ray_in.direction, &outward_n, ni_over_nt) {
                     None => {
                         let target = point + reflected_ray_dir + get_random_in_unit_sphere(rng) * roughness;
-                        let ray_reflect = Ray{origin : point, direction: (target - point).normalize()};
+                        let ray_reflect = hit.spawn_ray((target - point).normalize());
                         Some((albedo, ray_reflect))
                     },
                     Some(refracted_ray_dir) => {
@@ -134,14 +227,65 @@ impl Material<'_> {
                             }
                         };
                         let target = point + scattered_dir + get_random_in_unit_sphere(rng) * roughness;
-                        let ray_scattered = Ray{
-                            origin : point,
-                            direction: (target - point).normalize()
-                        };
+                        let ray_scattered = hit.spawn_ray((target - point).normalize());
                         Some((albedo, ray_scattered))
                     },
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2x2 image, top row black and white, bottom row red and green
+    const PIXELS: [Vector3<f32>; 4] = [
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, 1.0, 1.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0)
+    ];
+
+    #[test]
+    fn texture_wraps_u_and_clamps_v() {
+        let sample = |u: f32, v: f32| sample_texture(2, 2, &PIXELS, vec2(u, v));
+        assert!((sample(-0.25, 0.0) - sample(0.75, 0.0)).magnitude() < 1e-6);
+        assert!((sample(1.25, 0.0) - sample(0.25, 0.0)).magnitude() < 1e-6);
+        assert!((sample(0.0, 1.5) - sample(0.0, 1.0)).magnitude() < 1e-6);
+        assert!((sample(0.0, -0.5) - PIXELS[2]).magnitude() < 1e-6);
+        // green of the last column blends with red of the first one
+        assert!((sample(0.75, 0.5) - vec3(0.5, 0.5, 0.0)).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn back_face_scatters_to_side_of_ray() {
+        let hit = HitInfo {
+            t: 1.0,
+            p: vec3(0.0, 0.0, 0.0),
+            n: vec3(0.0, 0.0, 1.0),
+            ng: vec3(0.0, 0.0, 1.0),
+            p_error: vec3(0.0, 0.0, 0.0),
+            material: Material {
+                albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+                details: MaterialDetails::Lambertian,
+                emittance: 0.0,
+                relief: Relief::None
+            },
+            uv: None,
+            dpdu: vec3(1.0, 0.0, 0.0),
+            dpdv: vec3(0.0, 1.0, 0.0)
+        };
+        // the ray comes from below, behind the surface
+        let ray = Ray { origin: vec3(0.1, 0.2, -1.0), direction: vec3(-0.1, -0.2, 1.0).normalize() };
+        let mut rng = rand::thread_rng();
+        for details in [MaterialDetails::Lambertian, MaterialDetails::Metallic { roughness: 0.0 }].iter() {
+            let material = Material { details: *details, ..hit.material };
+            for _ in 0..100 {
+                let (_, scattered) = material.scatter(&ray, &mut rng, &hit).unwrap();
+                assert!(scattered.direction.z < 0.0);
+            }
+        }
+    }
+}
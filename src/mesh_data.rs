@@ -0,0 +1,577 @@
+use {
+    cgmath::{Vector2, Vector3, Vector4, InnerSpace, vec2, vec3},
+    std::{collections::HashMap, f32::consts::PI},
+    crate::vector_utils::orthonormal_basis
+};
+
+// Meshes and their loaders only depend on cgmath, so build.rs shares them to embed assets
+
+#[derive(Copy, Clone)]
+pub struct VertexDescription {
+    pub position: Vector3<f32>,
+    pub normal: Vector3<f32>,
+    pub uv: Vector2<f32>
+}
+
+/// How shading normals of a mesh are found
+#[derive(Copy, Clone, PartialEq, Default)]
+pub enum Shading {
+    /// normals of vertices are interpolated across faces
+    #[default]
+    Smooth,
+    /// every face uses its geometric normal, so edges between faces stay sharp
+    Flat
+}
+
+#[derive(Copy, Clone)]
+pub struct MeshDescription<'a> {
+    pub vertices: &'a [VertexDescription],
+    pub indices: &'a [usize],
+    pub triangle_count: usize,
+    pub shading: Shading,
+    // tangents of vertices with handedness of bitangent in w, empty if the mesh has none
+    pub tangents: &'a [Vector4<f32>],
+    // colors of vertices which take place of the albedo of the material, empty if the mesh has none
+    pub colors: &'a [Vector3<f32>]
+}
+
+impl MeshDescription<'_> {
+    pub fn get_triangle(&self, face_id: usize) -> Option<[VertexDescription; 3]> {
+        let offset = face_id * 3;
+        if offset + 3 > self.indices.len() {
+            return None;
+        }
+        Some([
+            self.vertices[self.indices[offset]],
+            self.vertices[self.indices[offset + 1]],
+            self.vertices[self.indices[offset + 2]]
+        ])
+    }
+
+    pub fn get_tangents(&self, face_id: usize) -> Option<[Vector4<f32>; 3]> {
+        let offset = face_id * 3;
+        if self.tangents.is_empty() || offset + 3 > self.indices.len() {
+            return None;
+        }
+        Some([
+            self.tangents[self.indices[offset]],
+            self.tangents[self.indices[offset + 1]],
+            self.tangents[self.indices[offset + 2]]
+        ])
+    }
+
+    pub fn get_colors(&self, face_id: usize) -> Option<[Vector3<f32>; 3]> {
+        let offset = face_id * 3;
+        if self.colors.is_empty() || offset + 3 > self.indices.len() {
+            return None;
+        }
+        Some([
+            self.colors[self.indices[offset]],
+            self.colors[self.indices[offset + 1]],
+            self.colors[self.indices[offset + 2]]
+        ])
+    }
+}
+
+/// Tangent of a face as MikkTSpace sees it, before tangents of its corners are averaged
+struct FaceTangent {
+    // direction of increasing u, unit length unless uvs of the face are degenerate
+    tangent: Vector3<f32>,
+    // whether uvs keep the winding of the face
+    preserves_orientation: bool,
+    // faces without an area in uv space don't add to tangents of groups they join
+    uv_degenerate: bool,
+    // faces with corners at the same vertex are left out of groups
+    degenerate: bool
+}
+
+/// Crease angle which keeps edges of machined parts sharp and curved surfaces smooth
+pub const DEFAULT_CREASE_ANGLE: f32 = PI / 6.0;
+
+/// Owned counterpart of MeshDescription, which is what mesh loaders produce
+#[derive(Default)]
+pub struct MeshData {
+    pub vertices: Vec<VertexDescription>,
+    pub indices: Vec<usize>,
+    // tangents of vertices with handedness of bitangent in w, empty if the source doesn't have them
+    pub tangents: Vec<Vector4<f32>>,
+    // colors of vertices, empty if the source doesn't have them
+    pub colors: Vec<Vector3<f32>>,
+    pub shading: Shading
+}
+
+// vertices are grouped by position, so seams of uvs don't show up as seams of normals.
+// Adding zero turns -0.0 into 0.0, which is the same position with different bits
+pub fn position_key(p: Vector3<f32>) -> [u32; 3] {
+    [(p.x + 0.0).to_bits(), (p.y + 0.0).to_bits(), (p.z + 0.0).to_bits()]
+}
+
+impl MeshData {
+    /// Sphere of segments around y and rings along it, centered at the origin. Uvs are
+    /// the same Shape::Sphere has, so both could use the same textures
+    pub fn uv_sphere(radius: f32, segments: usize, rings: usize) -> Self {
+        let mut mesh = MeshData::default();
+        for ring in 0..=rings {
+            let phi = PI * ring as f32 / rings as f32;
+            let (y, rho) = (-phi.cos(), phi.sin());
+            // the first and the last columns of vertices overlap, so u could go from 0 to 1
+            for segment in 0..=segments {
+                let u = segment as f32 / segments as f32;
+                let angle = (u - 0.5) * 2.0 * PI;
+                let normal = vec3(rho * angle.cos(), y, rho * angle.sin());
+                mesh.vertices.push(VertexDescription {
+                    position: normal * radius,
+                    normal,
+                    uv: vec2(u, (y + 1.0) * 0.5)
+                });
+            }
+        }
+        let vertex = |ring: usize, segment: usize| ring * (segments + 1) + segment;
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let (a, b) = (vertex(ring, segment), vertex(ring, segment + 1));
+                let (c, d) = (vertex(ring + 1, segment), vertex(ring + 1, segment + 1));
+                // triangles touching poles by an edge are degenerate
+                if ring + 1 < rings {
+                    mesh.indices.extend_from_slice(&[a, c, d]);
+                }
+                if ring > 0 {
+                    mesh.indices.extend_from_slice(&[a, d, b]);
+                }
+            }
+        }
+        mesh
+    }
+
+    pub fn description(&self) -> MeshDescription<'_> {
+        MeshDescription {
+            vertices: &self.vertices,
+            indices: &self.indices,
+            triangle_count: self.indices.len() / 3,
+            shading: self.shading,
+            tangents: &self.tangents,
+            colors: &self.colors
+        }
+    }
+
+    /// Normal of every face corner, which averages normals of faces sharing the position
+    /// of the corner weighted by their angles at it. Faces meeting the face of the corner at
+    /// an angle larger than the crease one are left out
+    fn corner_normals(&self, crease_angle: f32) -> Vec<Vector3<f32>> {
+        let faces = self.indices.chunks_exact(3).collect::<Vec<_>>();
+        let face_normals = faces
+            .iter()
+            .map(|face| {
+                let p = |i: usize| self.vertices[face[i]].position;
+                let n = (p(1) - p(0)).cross(p(2) - p(0));
+                if n.magnitude2() > 0.0 { n.normalize() } else { n }
+            })
+            .collect::<Vec<_>>();
+        let corner_angle = |face: &[usize], corner: usize| {
+            let p = |i: usize| self.vertices[face[(corner + i) % 3]].position;
+            let (a, b) = (p(1) - p(0), p(2) - p(0));
+            if a.magnitude2() > 0.0 && b.magnitude2() > 0.0 {
+                a.angle(b).0
+            } else {
+                0.0
+            }
+        };
+
+        let mut corners_at: HashMap<[u32; 3], Vec<(usize, f32)>> = HashMap::new();
+        for (face_id, face) in faces.iter().enumerate() {
+            for corner in 0..3 {
+                corners_at
+                    .entry(position_key(self.vertices[face[corner]].position))
+                    .or_default()
+                    .push((face_id, corner_angle(face, corner)));
+            }
+        }
+
+        let cos_crease = crease_angle.cos();
+        let mut normals = Vec::with_capacity(self.indices.len());
+        for (face_id, face) in faces.iter().enumerate() {
+            let face_normal = face_normals[face_id];
+            for &vertex_id in face.iter() {
+                let n = corners_at[&position_key(self.vertices[vertex_id].position)]
+                    .iter()
+                    .filter(|(other, _)| face_normals[*other].dot(face_normal) >= cos_crease)
+                    .fold(vec3(0.0, 0.0, 0.0), |acc, (other, angle)| acc + face_normals[*other] * *angle);
+                normals.push(if n.magnitude2() > 0.0 { n.normalize() } else { face_normal });
+            }
+        }
+        normals
+    }
+
+    /// Fills normals of vertices which don't have one (i.e. zero normals) with an average of
+    /// normals of faces sharing the vertex, weighted by angles of faces at it
+    pub fn generate_normals(&mut self) {
+        if self.vertices.iter().all(|v| v.normal.magnitude2() > 0.0) {
+            return;
+        }
+        let normals = self.corner_normals(PI);
+        for (&vertex_id, normal) in self.indices.iter().zip(normals) {
+            let vertex = &mut self.vertices[vertex_id];
+            if vertex.normal.magnitude2() == 0.0 {
+                vertex.normal = normal;
+            }
+        }
+    }
+
+    /// Generates tangents the way MikkTSpace does, so normal maps baked against its tangents
+    /// come out right. Every face has a tangent going along u, which is projected to the plane
+    /// of the normal at each corner. Corners of a vertex which are joined by edges and whose
+    /// faces map uvs with the same orientation make a group, and the tangent of the group is
+    /// an average of its corners weighted by their angles. W is the orientation of the group,
+    /// so the bitangent is restored as cross(normal, tangent) * w. Corners of a vertex which
+    /// end up with different tangents (i.e. where mirrored uvs meet) get vertices of their own
+    pub fn generate_tangents(&mut self) {
+        let corner_count = self.indices.len() / 3 * 3;
+        let vertex = |corner: usize| self.vertices[self.indices[corner]];
+
+        // vertices equal in position, normal and uv are the same vertex for MikkTSpace
+        let mut welded: HashMap<[u32; 8], usize> = HashMap::new();
+        let ids = (0..corner_count)
+            .map(|corner| {
+                let v = vertex(corner);
+                let ([px, py, pz], [nx, ny, nz]) = (position_key(v.position), position_key(v.normal));
+                let [u, v, _] = position_key(v.uv.extend(0.0));
+                *welded.entry([px, py, pz, nx, ny, nz, u, v]).or_insert(corner)
+            })
+            .collect::<Vec<_>>();
+
+        let mut faces = (0..corner_count / 3)
+            .map(|face_id| {
+                let [v0, v1, v2] = [vertex(face_id * 3), vertex(face_id * 3 + 1), vertex(face_id * 3 + 2)];
+                let (d1, d2) = (v1.position - v0.position, v2.position - v0.position);
+                let (t1, t2) = (v1.uv - v0.uv, v2.uv - v0.uv);
+                let area = t1.x * t2.y - t1.y * t2.x;
+                let tangent = d1 * t2.y - d2 * t1.y;
+                let id = |i: usize| ids[face_id * 3 + i];
+                FaceTangent {
+                    tangent: if tangent.magnitude2() > 0.0 { tangent.normalize() * area.signum() } else { tangent },
+                    preserves_orientation: area > 0.0,
+                    uv_degenerate: area.abs() <= f32::MIN_POSITIVE,
+                    degenerate: id(0) == id(1) || id(1) == id(2) || id(2) == id(0)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // neighbour of a face across the edge from its corner i to the next one
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for face_id in (0..faces.len()).filter(|&f| !faces[f].degenerate) {
+            for i in 0..3 {
+                edges.entry((ids[face_id * 3 + i], ids[face_id * 3 + (i + 1) % 3])).or_insert(face_id);
+            }
+        }
+        let neighbour = |face_id: usize, i: usize| edges
+            .get(&(ids[face_id * 3 + (i + 1) % 3], ids[face_id * 3 + i]))
+            .copied();
+
+        // faces with degenerate uvs join groups of their neighbours taking their orientation
+        let mut group_of = vec![None; corner_count];
+        let mut orientations = Vec::new();
+        for corner in 0..corner_count {
+            let face = &faces[corner / 3];
+            if face.degenerate || face.uv_degenerate || group_of[corner].is_some() {
+                continue;
+            }
+            let (group, vertex_id, orientation) = (orientations.len(), ids[corner], face.preserves_orientation);
+            orientations.push(orientation);
+            let mut stack = vec![corner / 3];
+            while let Some(face_id) = stack.pop() {
+                let i = match (0..3).find(|&i| ids[face_id * 3 + i] == vertex_id) {
+                    Some(i) if group_of[face_id * 3 + i].is_none() => i,
+                    _ => continue
+                };
+                let face = &mut faces[face_id];
+                if face.uv_degenerate && (0..3).all(|i| group_of[face_id * 3 + i].is_none()) {
+                    face.preserves_orientation = orientation;
+                }
+                if face.preserves_orientation != orientation {
+                    continue;
+                }
+                group_of[face_id * 3 + i] = Some(group);
+                stack.extend(neighbour(face_id, i));
+                stack.extend(neighbour(face_id, (i + 2) % 3));
+            }
+        }
+
+        let mut sums = vec![vec3(0.0, 0.0, 0.0); orientations.len()];
+        for (corner, group) in group_of.iter().enumerate() {
+            let (face_id, i) = (corner / 3, corner % 3);
+            let group = match *group {
+                Some(group) if !faces[face_id].uv_degenerate => group,
+                _ => continue
+            };
+            let n = vertex(corner).normal;
+            let project = |v: Vector3<f32>| {
+                let v = v - n * n.dot(v);
+                if v.magnitude2() > 0.0 { v.normalize() } else { v }
+            };
+            let p = |k: usize| vertex(face_id * 3 + (i + k) % 3).position;
+            let angle = project(p(1) - p(0)).dot(project(p(2) - p(0))).clamp(-1.0, 1.0).acos();
+            sums[group] += project(faces[face_id].tangent) * angle;
+        }
+        let group_tangent = |group: usize, n: Vector3<f32>| {
+            let t = sums[group];
+            let t = if t.magnitude2() > 0.0 { t.normalize() } else { orthonormal_basis(n).0 };
+            t.extend(if orientations[group] { 1.0 } else { -1.0 })
+        };
+
+        // corners left out of groups take a tangent of another corner of the same vertex
+        let mut of_vertex: HashMap<usize, Vector4<f32>> = HashMap::new();
+        for (corner, group) in group_of.iter().enumerate() {
+            if let Some(group) = *group {
+                of_vertex.entry(ids[corner]).or_insert_with(|| group_tangent(group, vertex(corner).normal));
+            }
+        }
+        let corner_tangents = (0..corner_count)
+            .map(|corner| match group_of[corner] {
+                Some(group) => group_tangent(group, vertex(corner).normal),
+                None => of_vertex
+                    .get(&ids[corner])
+                    .copied()
+                    .unwrap_or_else(|| orthonormal_basis(vertex(corner).normal).0.extend(1.0))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tangents: Vec<Option<Vector4<f32>>> = vec![None; self.vertices.len()];
+        let mut copies: HashMap<(usize, [u32; 4]), usize> = HashMap::new();
+        for (corner, tangent) in corner_tangents.into_iter().enumerate() {
+            let vertex_id = self.indices[corner];
+            match tangents[vertex_id] {
+                None => tangents[vertex_id] = Some(tangent),
+                Some(existing) if existing == tangent => {},
+                Some(_) => {
+                    let key = (vertex_id, [tangent.x.to_bits(), tangent.y.to_bits(), tangent.z.to_bits(), tangent.w.to_bits()]);
+                    let (vertices, colors) = (&mut self.vertices, &mut self.colors);
+                    self.indices[corner] = *copies.entry(key).or_insert_with(|| {
+                        vertices.push(vertices[vertex_id]);
+                        if !colors.is_empty() {
+                            colors.push(colors[vertex_id]);
+                        }
+                        tangents.push(Some(tangent));
+                        vertices.len() - 1
+                    });
+                }
+            }
+        }
+        self.tangents = tangents
+            .into_iter()
+            .zip(&self.vertices)
+            .map(|(tangent, vertex)| tangent.unwrap_or_else(|| orthonormal_basis(vertex.normal).0.extend(1.0)))
+            .collect();
+    }
+
+    /// Replaces every normal of the mesh with generated ones. Faces meeting at an angle
+    /// larger than the crease one don't share normals, so vertices on such edges are split
+    pub fn generate_creased_normals(&mut self, crease_angle: f32) {
+        let normals = self.corner_normals(crease_angle);
+        let mut vertex_ids: HashMap<(usize, [u32; 3]), usize> = HashMap::new();
+        let mut vertices = Vec::with_capacity(self.vertices.len());
+        let mut tangents = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = std::mem::take(&mut self.indices);
+        for (index, normal) in indices.iter_mut().zip(normals) {
+            let source = *index;
+            *index = *vertex_ids.entry((source, position_key(normal))).or_insert_with(|| {
+                vertices.push(VertexDescription { normal, ..self.vertices[source] });
+                if let Some(tangent) = self.tangents.get(source) {
+                    tangents.push(*tangent);
+                }
+                if let Some(color) = self.colors.get(source) {
+                    colors.push(*color);
+                }
+                vertices.len() - 1
+            });
+        }
+        self.indices = indices;
+        self.vertices = vertices;
+        self.tangents = tangents;
+        self.colors = colors;
+    }
+}
+
+/// Splits a planar polygon to triangles by ear clipping, so concave polygons are handled too.
+/// Returns triangles as indices into given points keeping the winding of the polygon
+pub fn triangulate(points: &[Vector3<f32>]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // polygon is projected to the plane most perpendicular to its Newell normal
+    let mut normal = vec3(0.0, 0.0, 0.0);
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        normal += vec3(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y)
+        );
+    }
+    let (ax, ay) = if normal.x.abs() > normal.y.abs() && normal.x.abs() > normal.z.abs() {
+        (1, 2)
+    } else if normal.y.abs() > normal.z.abs() {
+        (2, 0)
+    } else {
+        (0, 1)
+    };
+    let flat = points.iter().map(|p| vec2(p[ax], p[ay])).collect::<Vec<_>>();
+    let area: f32 = (0..n)
+        .map(|i| {
+            let (a, b) = (flat[i], flat[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    let orientation = if area < 0.0 { -1.0 } else { 1.0 };
+
+    let cross = |o: usize, a: usize, b: usize| {
+        let (oa, ob) = (flat[a] - flat[o], flat[b] - flat[o]);
+        (oa.x * ob.y - oa.y * ob.x) * orientation
+    };
+
+    let mut remaining = (0..n).collect::<Vec<_>>();
+    let mut triangles = Vec::with_capacity(n - 2);
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&i| {
+            let (prev, cur, next) = (remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]);
+            if cross(prev, cur, next) <= 0.0 {
+                return false;
+            }
+            !remaining.iter().any(|&other| {
+                other != prev && other != cur && other != next &&
+                    cross(prev, cur, other) >= 0.0 &&
+                    cross(cur, next, other) >= 0.0 &&
+                    cross(next, prev, other) >= 0.0
+            })
+        });
+        match ear {
+            Some(i) => {
+                triangles.push([remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]]);
+                remaining.remove(i);
+            },
+            None => {
+                // degenerate or self-intersecting polygon, just fan what is left
+                for i in 1..m - 1 {
+                    triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::collections::HashSet
+    };
+
+    fn cube() -> MeshData {
+        let vertices = (0..8)
+            .map(|i| VertexDescription {
+                position: vec3((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32) * 2.0 - vec3(1.0, 1.0, 1.0),
+                normal: vec3(0.0, 0.0, 0.0),
+                uv: vec2(0.0, 0.0)
+            })
+            .collect();
+        // two triangles per side, all wound counter-clockwise seen from outside
+        let quads = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+        let indices = quads.iter().flat_map(|q| vec![q[0], q[1], q[2], q[0], q[2], q[3]]).collect();
+        MeshData { vertices, indices, ..Default::default() }
+    }
+
+    #[test]
+    fn cube_gets_a_vertex_per_corner_of_every_side() {
+        let mut mesh = cube();
+        mesh.generate_creased_normals(DEFAULT_CREASE_ANGLE);
+        assert_eq!(mesh.vertices.len(), 24);
+        for face in mesh.indices.chunks_exact(3) {
+            let p = |i: usize| mesh.vertices[face[i]].position;
+            let face_normal = (p(1) - p(0)).cross(p(2) - p(0)).normalize();
+            // sides are axis-aligned, and their normals point out of the cube
+            assert!((face_normal.dot(p(0)) - 1.0).abs() < 1e-6);
+            for &id in face {
+                assert!((mesh.vertices[id].normal - face_normal).magnitude() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_sphere_keeps_shared_vertices() {
+        // faces around a vertex are well within the crease angle of each other
+        let mut mesh = MeshData::uv_sphere(2.0, 32, 16);
+        // the last vertices of pole rings aren't used by any face, so they are dropped
+        let used = mesh.indices.iter().collect::<HashSet<_>>().len();
+        assert!(used < mesh.vertices.len());
+        mesh.generate_creased_normals(DEFAULT_CREASE_ANGLE);
+        assert_eq!(mesh.vertices.len(), used);
+        for vertex in &mesh.vertices {
+            // normals are averages of flat faces, so they only come close to ones of the sphere
+            assert!(vertex.normal.dot(vertex.position / 2.0) > 0.99);
+        }
+    }
+
+    #[test]
+    fn tangents_are_orthogonal_to_normals() {
+        let mut mesh = MeshData::uv_sphere(1.0, 16, 8);
+        mesh.generate_tangents();
+        assert_eq!(mesh.tangents.len(), mesh.vertices.len());
+        for (vertex, tangent) in mesh.vertices.iter().zip(&mesh.tangents) {
+            let t = tangent.truncate();
+            assert!((t.magnitude() - 1.0).abs() < 1e-5);
+            assert!(t.dot(vertex.normal).abs() < 1e-5);
+            assert_eq!(tangent.w.abs(), 1.0);
+        }
+    }
+
+    fn flat_vertex(x: f32, y: f32, u: f32, v: f32) -> VertexDescription {
+        VertexDescription { position: vec3(x, y, 0.0), normal: vec3(0.0, 0.0, 1.0), uv: vec2(u, v) }
+    }
+
+    #[test]
+    fn tangents_of_corners_are_weighted_by_angles() {
+        // the right angle at the first vertex has tangent x, the 45 degrees one has (1, 1)
+        let mut mesh = MeshData {
+            vertices: vec![flat_vertex(0.0, 0.0, 0.0, 0.0), flat_vertex(1.0, 0.0, 1.0, 0.0), flat_vertex(0.0, 1.0, 0.0, 1.0), flat_vertex(-1.0, 1.0, -1.0, 2.0)],
+            indices: vec![0, 1, 2, 0, 2, 3],
+            ..Default::default()
+        };
+        mesh.generate_tangents();
+        assert_eq!(mesh.vertices.len(), 4);
+        let expected = (vec3(1.0, 0.0, 0.0) * (PI / 2.0) + vec3(1.0, 1.0, 0.0).normalize() * (PI / 4.0)).normalize();
+        assert!((mesh.tangents[0].truncate() - expected).magnitude() < 1e-5);
+        assert_eq!(mesh.tangents[0].w, 1.0);
+    }
+
+    #[test]
+    fn mirrored_uvs_split_shared_vertices() {
+        // the second face maps u the other way around, so corners along the shared edge
+        // don't share tangents any more
+        let mut mesh = MeshData {
+            vertices: vec![flat_vertex(0.0, 0.0, 0.0, 0.0), flat_vertex(1.0, 0.0, 1.0, 0.0), flat_vertex(0.0, 1.0, 0.0, 1.0), flat_vertex(1.0, 1.0, 0.0, 0.0)],
+            indices: vec![0, 1, 2, 1, 3, 2],
+            ..Default::default()
+        };
+        mesh.generate_tangents();
+        assert_eq!(mesh.vertices.len(), 6);
+        // v goes along y on the first face and along -x on the second one
+        let faces = mesh.indices.chunks_exact(3).zip([(1.0, vec3(0.0, 1.0, 0.0)), (-1.0, vec3(-1.0, 0.0, 0.0))]);
+        for (face, (w, dpdv)) in faces {
+            for &id in face {
+                let tangent = mesh.tangents[id];
+                assert_eq!(tangent.w, w);
+                let bitangent = mesh.vertices[id].normal.cross(tangent.truncate()) * tangent.w;
+                assert!((bitangent - dpdv).magnitude() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use {
+    cgmath::{Vector3, InnerSpace, vec3},
+    crate::{
+        aabb::AaBb,
+        float_utils::{abs, gamma},
+        material::Material,
+        polynomial::{Coefficients, MAX_DEGREE, multiply, roots_in_interval},
+        quadrics::solve_quadratic,
+        ray::{HitInfo, Ray},
+        vector_utils::orthonormal_basis
+    }
+};
+
+/// Source of a blobby field, which falls off as weight * (1 - r^2 / radius^2)^3 with distance
+/// r to its core and is exactly zero from radius on
+#[derive(Copy, Clone)]
+pub enum Metaball {
+    Point { center: Vector3<f32>, radius: f32, weight: f32 },
+    /// core is the segment between a and b, so it is stretched into a sausage
+    Segment { a: Vector3<f32>, b: Vector3<f32>, radius: f32, weight: f32 }
+}
+
+/// Part of the core nearest to points along a piece of a ray
+enum Core {
+    Point(Vector3<f32>),
+    Line(Vector3<f32>, Vector3<f32>)
+}
+
+impl Metaball {
+    fn radius_and_weight(&self) -> (f32, f32) {
+        match *self {
+            Metaball::Point { radius, weight, .. } | Metaball::Segment { radius, weight, .. } => (radius, weight)
+        }
+    }
+
+    /// Sphere outside of which the source has no influence
+    fn influence_sphere(&self) -> (Vector3<f32>, f32) {
+        match *self {
+            Metaball::Point { center, radius, .. } => (center, radius),
+            Metaball::Segment { a, b, radius, .. } => ((a + b) * 0.5, (b - a).magnitude() * 0.5 + radius)
+        }
+    }
+
+    fn core(&self, p: Vector3<f32>) -> Core {
+        match *self {
+            Metaball::Point { center, .. } => Core::Point(center),
+            Metaball::Segment { a, b, .. } => {
+                let ab = b - a;
+                let h = if ab.magnitude2() > 0.0 { (p - a).dot(ab) / ab.magnitude2() } else { 0.0 };
+                if h <= 0.0 {
+                    Core::Point(a)
+                } else if h >= 1.0 {
+                    Core::Point(b)
+                } else {
+                    Core::Line(a, ab.normalize())
+                }
+            }
+        }
+    }
+
+    /// Where the ray crosses from one part of the core to another, or in or out of influence
+    /// of a part. Polynomials of the field only hold between such points. Points the ray
+    /// doesn't have are infinite, so they never come next
+    fn breakpoints(&self, ray: &Ray) -> [f32; 8] {
+        let (o, d) = (ray.origin, ray.direction);
+        let (radius, _) = self.radius_and_weight();
+        let sphere = |center: Vector3<f32>| {
+            let oc = o - center;
+            solve_quadratic(d.dot(d), 2.0 * oc.dot(d), oc.dot(oc) - radius * radius)
+                .map_or([f32::INFINITY; 2], |(enter, leave)| [enter, leave])
+        };
+        let mut breakpoints = [f32::INFINITY; 8];
+        match *self {
+            Metaball::Point { center, .. } => breakpoints[..2].copy_from_slice(&sphere(center)),
+            Metaball::Segment { a, b, .. } => {
+                breakpoints[..2].copy_from_slice(&sphere(a));
+                breakpoints[2..4].copy_from_slice(&sphere(b));
+                let ab = b - a;
+                if ab.magnitude2() == 0.0 {
+                    return breakpoints;
+                }
+                // planes through the ends, which rays perpendicular to the segment never cross
+                let along = d.dot(ab);
+                if along != 0.0 {
+                    breakpoints[4] = (a - o).dot(ab) / along;
+                    breakpoints[5] = (b - o).dot(ab) / along;
+                }
+                // infinite cylinder around the segment
+                let u = ab.normalize();
+                let (oa, d) = (o - a, d - u * d.dot(u));
+                let oa = oa - u * oa.dot(u);
+                if let Some((enter, leave)) = solve_quadratic(d.dot(d), 2.0 * oa.dot(d), oa.dot(oa) - radius * radius) {
+                    breakpoints[6] = enter;
+                    breakpoints[7] = leave;
+                }
+            }
+        }
+        breakpoints
+    }
+
+    /// Squared distance to the core along the ray from o as a quadratic of the distance
+    /// travelled, it holds as long as the nearest part of the core stays the same
+    fn squared_distance(&self, core: &Core, o: Vector3<f64>, d: Vector3<f64>) -> [f64; 3] {
+        let (v, d) = match *core {
+            Core::Point(center) => (o - center.cast::<f64>().unwrap(), d),
+            Core::Line(a, u) => {
+                let u = u.cast::<f64>().unwrap();
+                let v = o - a.cast::<f64>().unwrap();
+                (v - u * v.dot(u), d - u * d.dot(u))
+            }
+        };
+        [v.dot(v), 2.0 * v.dot(d), d.dot(d)]
+    }
+
+    fn gradient(&self, p: Vector3<f32>) -> Vector3<f32> {
+        let (radius, weight) = self.radius_and_weight();
+        let offset = match self.core(p) {
+            Core::Point(center) => p - center,
+            Core::Line(a, u) => (p - a) - u * (p - a).dot(u)
+        };
+        let falloff = 1.0 - offset.magnitude2() / (radius * radius);
+        if falloff <= 0.0 {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        offset * (-6.0 * weight * falloff * falloff / (radius * radius))
+    }
+}
+
+pub fn metaballs_bounds(sources: &[Metaball]) -> Option<AaBb> {
+    sources
+        .iter()
+        .map(|source| {
+            let (center, radius) = source.influence_sphere();
+            let half_sizes = vec3(radius, radius, radius);
+            AaBb { min: center - half_sizes, max: center + half_sizes }
+        })
+        .reduce(|a, b| a.union(&b))
+}
+
+/// Surface where the sum of fields of all sources is threshold. Sources influencing the ray
+/// are found by their spheres, and between breakpoints of all of them the field along the ray
+/// is a polynomial of degree 6, so its roots are found exactly instead of marching. Pieces
+/// are visited in order by looking for the nearest breakpoint past the start of each
+pub fn test_ray_metaballs_intersection<'a>(
+    sources: &[Metaball],
+    threshold: f32,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    let (o, d) = (ray.origin, ray.direction);
+    let influences = |source: &&Metaball| {
+        let (center, radius) = source.influence_sphere();
+        let oc = o - center;
+        solve_quadratic(d.dot(d), 2.0 * oc.dot(d), oc.dot(oc) - radius * radius)
+            .is_some_and(|(enter, leave)| leave > t_min && enter < t_max)
+    };
+    if !sources.iter().any(|source| influences(&source)) {
+        return None;
+    }
+
+    let d64 = d.cast::<f64>().unwrap();
+    let mut start = t_min;
+    while start < t_max {
+        let end = sources
+            .iter()
+            .filter(influences)
+            .flat_map(|source| source.breakpoints(ray))
+            .filter(|&t| t > start)
+            .fold(t_max, f32::min);
+        // coefficients stay small when the piece is measured from its start
+        let o64 = ray.get_point_at(start).cast::<f64>().unwrap();
+        let middle = ray.get_point_at((start + end) * 0.5);
+        let length = (end - start) as f64;
+        let mut field: Coefficients = [0.0; MAX_DEGREE + 1];
+        field[0] = -threshold as f64;
+        for source in sources.iter().filter(influences) {
+            let (radius, weight) = source.radius_and_weight();
+            let r2 = (radius as f64).powi(2);
+            let q = source.squared_distance(&source.core(middle), o64, d64);
+            // the source doesn't reach the piece
+            let s = length * 0.5;
+            if q[0] + s * (q[1] + s * q[2]) >= r2 {
+                continue;
+            }
+            let falloff = [1.0 - q[0] / r2, -q[1] / r2, -q[2] / r2];
+            let cubed = multiply(&multiply(&falloff, &falloff), &falloff);
+            for (c, x) in field.iter_mut().zip(cubed.iter()) {
+                *c += weight as f64 * x;
+            }
+        }
+        let hit = roots_in_interval(&field, 0.0, length)
+            .into_iter()
+            .map(|s| start + s as f32)
+            .find(|&t| t > t_min && t < t_max);
+        if let Some(t) = hit {
+            let p = ray.get_point_at(t);
+            // sources which don't reach the point have no gradient there
+            let gradient = sources.iter().fold(vec3(0.0, 0.0, 0.0), |acc, source| acc + source.gradient(p));
+            // field grows towards cores, so the outward normal goes against its gradient
+            let n = if gradient.magnitude2() > 0.0 { -gradient.normalize() } else { Vector3::unit_y() };
+            let (dpdu, dpdv) = orthonormal_basis(n);
+            return Some(HitInfo {
+                t,
+                p,
+                n,
+                ng: n,
+                p_error: (abs(o) + abs(d * t)) * gamma(7),
+                material: *material,
+                uv: None,
+                dpdu,
+                dpdv
+            });
+        }
+        start = end;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    #[test]
+    fn ray_perpendicular_to_segment_hits_it() {
+        let sources = [Metaball::Segment { a: vec3(0.0, -1.0, 0.0), b: vec3(0.0, 1.0, 0.0), radius: 1.0, weight: 1.0 }];
+        let ray = Ray { origin: vec3(-5.0, 0.0, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        let hit_info = test_ray_metaballs_intersection(&sources, 0.125, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        // (1 - r^2)^3 = 1/8 at r^2 = 1/2
+        assert!((hit_info.t - (5.0 - 0.5f32.sqrt())).abs() < 1e-4);
+        assert!((hit_info.n - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn ray_from_inside_of_point_leaves_it() {
+        let sources = [Metaball::Point { center: vec3(0.0, 0.0, 0.0), radius: 1.0, weight: 1.0 }];
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, 2.0, 0.0) };
+        let hit_info = test_ray_metaballs_intersection(&sources, 0.125, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 0.5f32.sqrt() / 2.0).abs() < 1e-4);
+        assert!((hit_info.n - vec3(0.0, 1.0, 0.0)).magnitude() < 1e-4);
+    }
+}
@@ -0,0 +1,128 @@
+use {
+    std::path::Path,
+    crate::{
+        aabb::AaBb,
+        bvh::Bvh,
+        gltf_loader::GltfScene,
+        material::Material,
+        mesh_data::MeshData,
+        obj_loader::{ObjMaterial, load_obj},
+        ply_loader::load_ply,
+        shape::Shape,
+        stl_loader::load_stl,
+        transform::Transform,
+        vox_loader::VoxModel
+    }
+};
+
+/// Mesh of a model along with its material, which is an index into materials of the model.
+/// Meshes without one are drawn with the default material
+pub struct MeshObject {
+    pub mesh: MeshData,
+    pub material: Option<usize>
+}
+
+/// Meshes loaded from obj, ply or stl. Only obj files split a model to several objects
+/// and come with materials
+pub struct MeshModel {
+    pub objects: Vec<MeshObject>,
+    pub materials: Vec<ObjMaterial>,
+    /// material libraries which couldn't be opened, it is up to the caller to report them
+    pub missing_libraries: Vec<String>
+}
+
+impl MeshModel {
+    /// Wraps a single mesh without materials, like ones loaded from ply or stl
+    pub fn from_mesh(mesh: MeshData) -> Self {
+        MeshModel {
+            objects: vec![MeshObject { mesh, material: None }],
+            materials: Vec::new(),
+            missing_libraries: Vec::new()
+        }
+    }
+
+    pub fn bounds(&self) -> Option<AaBb> {
+        self.objects
+            .iter()
+            .filter_map(|o| AaBb::from_points(&o.mesh.vertices.iter().map(|v| v.position).collect::<Vec<_>>()))
+            .fold(None, |acc, b| Some(acc.map_or(b, |a: AaBb| a.union(&b))))
+    }
+
+    pub fn build_bvhs(&self) -> Vec<Bvh> {
+        self.objects.iter().map(|o| o.mesh.description().build_bvh()).collect()
+    }
+
+    /// Makes a mesh shape for every object. Objects without a material get the default one
+    pub fn shapes<'a>(
+        &'a self,
+        bvhs: &'a [Bvh],
+        transform: Transform,
+        default_material: Material<'a>
+    ) -> Vec<Shape<'a>>
+    {
+        self.objects
+            .iter()
+            .zip(bvhs)
+            .map(|(object, bvh)| Shape::TriangleMesh {
+                transform,
+                mesh: object.mesh.description(),
+                material: object.material.map_or(default_material, |m| self.materials[m].to_material()),
+                bvh: Some(bvh)
+            })
+            .collect()
+    }
+}
+
+/// Model loaded from any of supported formats. Meshes come from obj, ply and stl,
+/// gltf brings whole scenes with lights and cameras, and vox brings voxels
+pub enum Model {
+    Mesh(MeshModel),
+    Scene(GltfScene),
+    Voxels(VoxModel)
+}
+
+impl Model {
+    /// Picks a loader by extension of the file, obj is the default one
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+        let mesh = match extension.as_deref() {
+            Some("ply") => load_ply(path).map_err(|e| e.to_string())?,
+            Some("stl") => load_stl(path).map_err(|e| e.to_string())?,
+            Some("gltf") | Some("glb") => return GltfScene::load(path).map(Model::Scene).map_err(|e| e.to_string()),
+            Some("vox") => return VoxModel::load(path).map(Model::Voxels).map_err(|e| e.to_string()),
+            _ => return load_obj(path).map(Model::Mesh).map_err(|e| e.to_string())
+        };
+        Ok(Model::Mesh(MeshModel::from_mesh(mesh)))
+    }
+
+    pub fn bounds(&self) -> Option<AaBb> {
+        match self {
+            Model::Mesh(model) => model.bounds(),
+            Model::Scene(scene) => scene.bounds(),
+            Model::Voxels(model) => Some(model.bounds())
+        }
+    }
+
+    pub fn build_bvhs(&self) -> Vec<Bvh> {
+        match self {
+            Model::Mesh(model) => model.build_bvhs(),
+            Model::Scene(scene) => scene.build_bvhs(),
+            Model::Voxels(_) => Vec::new()
+        }
+    }
+
+    pub fn shapes<'a>(
+        &'a self,
+        bvhs: &'a [Bvh],
+        transform: Transform,
+        default_material: Material<'a>
+    ) -> Vec<Shape<'a>>
+    {
+        match self {
+            Model::Mesh(model) => model.shapes(bvhs, transform, default_material),
+            Model::Scene(scene) => scene.shapes(bvhs, transform, default_material),
+            // every voxel has a color of its own
+            Model::Voxels(model) => vec![model.shape(transform)]
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use {
+    cgmath::{Vector3, vec3},
+    png::DecodingError,
+    std::{
+        fmt,
+        fs::File,
+        io::{self, BufRead, BufReader},
+        path::Path
+    },
+    crate::{
+        image_loader::{ImgData, load_png},
+        material::{Albedo, Material, MaterialDetails, Relief},
+        model::{MeshModel, MeshObject},
+        obj_parser::{ParseError, parse_error, parse_floats, parse_obj, parse_vector3}
+    }
+};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    UnsupportedTexture(String),
+    BadTexture(String, DecodingError)
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "{}", e),
+            ObjError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ObjError::UnsupportedTexture(path) => write!(f, "unsupported texture {}, only png is supported", path),
+            ObjError::BadTexture(path, e) => write!(f, "bad texture {}: {}", path, e)
+        }
+    }
+}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+impl From<ParseError> for ObjError {
+    fn from(e: ParseError) -> Self {
+        match e {
+            ParseError::Io(e) => ObjError::Io(e),
+            ParseError::Syntax { line, message } => ObjError::Parse { line, message }
+        }
+    }
+}
+
+/// Material as it is described in mtl file
+pub struct ObjMaterial {
+    pub name: String,
+    pub diffuse: Vector3<f32>,
+    pub specular: Vector3<f32>,
+    pub shininess: f32,
+    pub ior: f32,
+    pub dissolve: f32,
+    pub emissive: Vector3<f32>,
+    pub diffuse_map: Option<ImgData>
+}
+
+impl Default for ObjMaterial {
+    fn default() -> Self {
+        ObjMaterial {
+            name: String::new(),
+            diffuse: vec3(0.8, 0.8, 0.8),
+            specular: vec3(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            ior: 1.5,
+            dissolve: 1.0,
+            emissive: vec3(0.0, 0.0, 0.0),
+            diffuse_map: None
+        }
+    }
+}
+
+fn max_component(v: Vector3<f32>) -> f32 {
+    v.x.max(v.y).max(v.z)
+}
+
+impl ObjMaterial {
+    /// Picks the closest of the materials supported by the tracer: emissive surfaces become
+    /// lights, transparent ones become dielectrics, ones with specular color dominating
+    /// the diffuse one become metals and everything else is lambertian
+    pub fn to_material(&self) -> Material<'_> {
+        let albedo = match &self.diffuse_map {
+            Some(ImgData { width, height, colors }) => Albedo::Texture(*width, *height, colors),
+            None => Albedo::Constant(self.diffuse)
+        };
+        // conversion of Blinn-Phong exponent to roughness
+        let roughness = (2.0 / (self.shininess + 2.0)).sqrt().min(1.0);
+        if max_component(self.emissive) > 0.0 {
+            Material {
+                albedo: Albedo::Constant(self.emissive),
+                details: MaterialDetails::Lambertian,
+                emittance: 1.0,
+                relief: Relief::None
+            }
+        } else if self.dissolve < 1.0 {
+            Material {
+                albedo,
+                details: MaterialDetails::Dielectric { ref_idx: self.ior, roughness },
+                emittance: 0.0,
+                relief: Relief::None
+            }
+        } else if max_component(self.specular) > max_component(self.diffuse) {
+            Material {
+                albedo: Albedo::Constant(self.specular),
+                details: MaterialDetails::Metallic { roughness },
+                emittance: 0.0,
+                relief: Relief::None
+            }
+        } else {
+            Material { albedo, details: MaterialDetails::Lambertian, emittance: 0.0, relief: Relief::None }
+        }
+    }
+}
+
+fn load_texture(base_dir: &Path, file_name: &str) -> Result<ImgData, ObjError> {
+    let path = base_dir.join(file_name);
+    let is_png = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png"));
+    if !is_png {
+        return Err(ObjError::UnsupportedTexture(file_name.to_string()));
+    }
+    load_png(BufReader::new(File::open(path)?)).map_err(|e| ObjError::BadTexture(file_name.to_string(), e))
+}
+
+pub fn parse_mtl<R: BufRead>(reader: R, base_dir: &Path) -> Result<Vec<ObjMaterial>, ObjError> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+    for (line_id, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_id + 1;
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue
+        };
+        if keyword == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            materials.push(ObjMaterial { name, ..Default::default() });
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(m) => m,
+            None => return Err(parse_error(line_number, format!("{} before newmtl", keyword)).into())
+        };
+        match keyword {
+            "Kd" => material.diffuse = parse_vector3(line_number, tokens)?,
+            "Ks" => material.specular = parse_vector3(line_number, tokens)?,
+            "Ke" => material.emissive = parse_vector3(line_number, tokens)?,
+            "Ns" => material.shininess = parse_floats(line_number, tokens, 1)?[0],
+            "Ni" => material.ior = parse_floats(line_number, tokens, 1)?[0],
+            "d" => material.dissolve = parse_floats(line_number, tokens, 1)?[0],
+            "Tr" => material.dissolve = 1.0 - parse_floats(line_number, tokens, 1)?[0],
+            "map_Kd" => {
+                // options could precede the file name, which is always the last one
+                let file_name = tokens
+                    .last()
+                    .ok_or_else(|| parse_error(line_number, "map_Kd without a file name"))?;
+                material.diffuse_map = Some(load_texture(base_dir, file_name)?);
+            },
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
+
+pub fn load_obj(path: &Path) -> Result<MeshModel, ObjError> {
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    parse_obj_model(BufReader::new(File::open(path)?), base_dir)
+}
+
+/// Parses obj from a reader. Material libraries and textures are looked up in base_dir.
+/// Models are often shared without their materials, which aren't needed to show the shape,
+/// so libraries which can't be opened are listed in missing_libraries and their faces
+/// get the default material. Libraries which are there but broken are still errors
+pub fn parse_obj_model<R: BufRead>(reader: R, base_dir: &Path) -> Result<MeshModel, ObjError> {
+    let geometry = parse_obj(reader)?;
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+    let mut missing_libraries = Vec::new();
+    for file_name in geometry.material_libraries {
+        match File::open(base_dir.join(&file_name)) {
+            Ok(file) => materials.extend(parse_mtl(BufReader::new(file), base_dir)?),
+            Err(_) => missing_libraries.push(file_name)
+        }
+    }
+    let objects = geometry.parts
+        .into_iter()
+        .map(|part| MeshObject {
+            material: part.material.and_then(|name| materials.iter().position(|m| m.name == name)),
+            mesh: part.mesh
+        })
+        .collect();
+    Ok(MeshModel { objects, materials, missing_libraries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_without_missing_materials() {
+        let obj = "mtllib missing.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n";
+        let model = parse_obj_model(obj.as_bytes(), Path::new("no/such/dir")).unwrap();
+        assert_eq!(model.missing_libraries, vec!["missing.mtl".to_string()]);
+        assert!(model.materials.is_empty());
+        assert_eq!(model.objects.len(), 1);
+        assert_eq!(model.objects[0].mesh.indices.len(), 3);
+        // faces fall back to the default material
+        assert!(model.objects[0].material.is_none());
+    }
+
+    #[test]
+    fn parses_mtl() {
+        let mtl = "# two materials\n\
+                   newmtl shiny red\nKd 1 0 0\nKs 0.5 0.5 0.5\nNs 98\nNi 1.33\nd 0.25\n\
+                   newmtl lamp\nKe 4 4 2\nTr 0.5\n";
+        let materials = parse_mtl(mtl.as_bytes(), Path::new("")).unwrap();
+        assert_eq!(materials.len(), 2);
+        let red = &materials[0];
+        assert_eq!(red.name, "shiny red");
+        assert_eq!(red.diffuse, vec3(1.0, 0.0, 0.0));
+        assert_eq!(red.specular, vec3(0.5, 0.5, 0.5));
+        assert_eq!((red.shininess, red.ior, red.dissolve), (98.0, 1.33, 0.25));
+        let lamp = &materials[1];
+        assert_eq!(lamp.emissive, vec3(4.0, 4.0, 2.0));
+        assert_eq!(lamp.dissolve, 0.5);
+        // everything the file doesn't set keeps its default
+        assert_eq!(lamp.diffuse, ObjMaterial::default().diffuse);
+    }
+
+    #[test]
+    fn rejects_bad_mtl() {
+        let parse = |mtl: &str| parse_mtl(mtl.as_bytes(), Path::new("no/such/dir"));
+        assert!(matches!(parse("Kd 1 0 0\n"), Err(ObjError::Parse { line: 1, .. })));
+        assert!(matches!(parse("newmtl a\nKd 1 0\n"), Err(ObjError::Parse { line: 2, .. })));
+        assert!(matches!(parse("newmtl a\nmap_Kd -s 2 2 1 wood.jpg\n"), Err(ObjError::UnsupportedTexture(_))));
+        assert!(matches!(parse("newmtl a\nmap_Kd wood.png\n"), Err(ObjError::Io(_))));
+    }
+
+    #[test]
+    fn maps_materials_to_tracer_ones() {
+        let lambertian = ObjMaterial { diffuse: vec3(0.2, 0.4, 0.6), ..Default::default() };
+        let material = lambertian.to_material();
+        assert!(matches!(material.details, MaterialDetails::Lambertian));
+        assert!(matches!(material.albedo, Albedo::Constant(c) if c == vec3(0.2, 0.4, 0.6)));
+
+        // Ns of 0 is as rough as it gets, and higher exponents make it smoother
+        let metal = ObjMaterial { specular: vec3(0.9, 0.9, 0.9), shininess: 0.0, ..Default::default() };
+        assert!(matches!(metal.to_material().details, MaterialDetails::Metallic { roughness } if roughness == 1.0));
+        let polished = ObjMaterial { shininess: 198.0, ..metal };
+        let material = polished.to_material();
+        assert!(matches!(material.details, MaterialDetails::Metallic { roughness } if (roughness - 0.1).abs() < 1e-6));
+        assert!(matches!(material.albedo, Albedo::Constant(c) if c == vec3(0.9, 0.9, 0.9)));
+
+        let glass = ObjMaterial { dissolve: 0.5, ior: 1.33, ..Default::default() };
+        assert!(matches!(glass.to_material().details, MaterialDetails::Dielectric { ref_idx, .. } if ref_idx == 1.33));
+
+        let lamp = ObjMaterial { emissive: vec3(4.0, 4.0, 2.0), dissolve: 0.5, ..Default::default() };
+        let material = lamp.to_material();
+        assert_eq!(material.emittance, 1.0);
+        assert!(matches!(material.albedo, Albedo::Constant(c) if c == vec3(4.0, 4.0, 2.0)));
+
+        let textured = ObjMaterial {
+            diffuse_map: Some(ImgData { width: 1, height: 1, colors: vec![vec3(0.0, 1.0, 0.0)] }),
+            ..Default::default()
+        };
+        assert!(matches!(textured.to_material().albedo, Albedo::Texture(1, 1, _)));
+    }
+}
@@ -0,0 +1,201 @@
+use {
+    cgmath::{Vector2, Vector3, vec2, vec3},
+    std::{
+        collections::HashMap,
+        fmt,
+        io::{self, BufRead}
+    },
+    crate::mesh_data::{MeshData, VertexDescription, triangulate}
+};
+
+// Geometry of obj files without their materials, which need textures and the rest of
+// the tracer, so build.rs could share it. Material libraries are only named here
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    Syntax { line: usize, message: String }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "{}", e),
+            ParseError::Syntax { line, message } => write!(f, "line {}: {}", line, message)
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        ParseError::Io(e)
+    }
+}
+
+/// Part of a model which has a single material. Every object, group and material switch
+/// in obj file starts a new one
+pub struct ObjPart {
+    pub mesh: MeshData,
+    pub material: Option<String>
+}
+
+pub struct ObjGeometry {
+    pub parts: Vec<ObjPart>,
+    /// file names of mtllib statements in the order they appear
+    pub material_libraries: Vec<String>
+}
+
+struct PartBuilder {
+    material: Option<String>,
+    mesh: MeshData,
+    vertex_ids: HashMap<(usize, Option<usize>, Option<usize>), usize>
+}
+
+impl PartBuilder {
+    fn new(material: Option<String>) -> Self {
+        PartBuilder {
+            material,
+            mesh: MeshData::default(),
+            vertex_ids: HashMap::new()
+        }
+    }
+
+    fn finish(self, parts: &mut Vec<ObjPart>) {
+        if self.mesh.indices.is_empty() {
+            return;
+        }
+        let mut mesh = self.mesh;
+        mesh.generate_normals();
+        parts.push(ObjPart { mesh, material: self.material });
+    }
+}
+
+pub fn parse_error(line: usize, message: impl Into<String>) -> ParseError {
+    ParseError::Syntax { line, message: message.into() }
+}
+
+pub fn parse_floats<'a>(line: usize, tokens: impl Iterator<Item=&'a str>, count: usize) -> Result<Vec<f32>, ParseError> {
+    let values = tokens
+        .take(count)
+        .map(|t| t.parse::<f32>().map_err(|_| parse_error(line, format!("bad number {}", t))))
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.len() < count {
+        return Err(parse_error(line, format!("expected {} numbers", count)));
+    }
+    Ok(values)
+}
+
+pub fn parse_vector3<'a>(line: usize, tokens: impl Iterator<Item=&'a str>) -> Result<Vector3<f32>, ParseError> {
+    let v = parse_floats(line, tokens, 3)?;
+    Ok(vec3(v[0], v[1], v[2]))
+}
+
+// obj indices are 1-based, negative ones are relative to the end of already defined elements
+fn resolve_index(line: usize, token: &str, count: usize) -> Result<usize, ParseError> {
+    let index = token.parse::<i64>().map_err(|_| parse_error(line, format!("bad index {}", token)))?;
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(parse_error(line, format!("index {} is out of range", index)));
+    }
+    Ok(resolved as usize)
+}
+
+/// Parses positions, uvs, normals and faces of obj, polygons are triangulated
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<ObjGeometry, ParseError> {
+    let mut positions: Vec<Vector3<f32>> = Vec::new();
+    let mut uvs: Vec<Vector2<f32>> = Vec::new();
+    let mut normals: Vec<Vector3<f32>> = Vec::new();
+    let mut material_libraries = Vec::new();
+    let mut parts = Vec::new();
+    let mut builder = PartBuilder::new(None);
+
+    for (line_id, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_id + 1;
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue
+        };
+        match keyword {
+            "v" => positions.push(parse_vector3(line_number, tokens)?),
+            "vn" => normals.push(parse_vector3(line_number, tokens)?),
+            "vt" => {
+                let uv = parse_floats(line_number, tokens, 2)?;
+                uvs.push(vec2(uv[0], uv[1]));
+            },
+            "o" | "g" => {
+                let material = builder.material.clone();
+                builder.finish(&mut parts);
+                builder = PartBuilder::new(material);
+            },
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                builder.finish(&mut parts);
+                builder = PartBuilder::new(Some(name));
+            },
+            "mtllib" => material_libraries.extend(tokens.map(str::to_string)),
+            "f" => {
+                let mut face = Vec::new();
+                for token in tokens {
+                    let mut indices = token.split('/');
+                    let v = resolve_index(line_number, indices.next().unwrap_or(""), positions.len())?;
+                    let vt = match indices.next() {
+                        Some(t) if !t.is_empty() => Some(resolve_index(line_number, t, uvs.len())?),
+                        _ => None
+                    };
+                    let vn = match indices.next() {
+                        Some(t) if !t.is_empty() => Some(resolve_index(line_number, t, normals.len())?),
+                        _ => None
+                    };
+                    let mesh = &mut builder.mesh;
+                    let vertex_id = *builder.vertex_ids.entry((v, vt, vn)).or_insert_with(|| {
+                        mesh.vertices.push(VertexDescription {
+                            position: positions[v],
+                            normal: vn.map_or(vec3(0.0, 0.0, 0.0), |vn| normals[vn]),
+                            uv: vt.map_or(vec2(0.0, 0.0), |vt| uvs[vt])
+                        });
+                        mesh.vertices.len() - 1
+                    });
+                    face.push(vertex_id);
+                }
+                if face.len() < 3 {
+                    return Err(parse_error(line_number, "face has less than 3 vertices"));
+                }
+                let face_positions = face
+                    .iter()
+                    .map(|&id| builder.mesh.vertices[id].position)
+                    .collect::<Vec<_>>();
+                for [a, b, c] in triangulate(&face_positions) {
+                    builder.mesh.indices.extend_from_slice(&[face[a], face[b], face[c]]);
+                }
+            },
+            _ => {}
+        }
+    }
+    builder.finish(&mut parts);
+    Ok(ObjGeometry { parts, material_libraries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_parts_by_materials() {
+        let obj = "mtllib a.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nusemtl red\nf 1 2 3 4\ng side\nf -4 -3 -2\nusemtl blue\nf 1 3 4\n";
+        let geometry = parse_obj(obj.as_bytes()).unwrap();
+        assert_eq!(geometry.material_libraries, vec!["a.mtl".to_string()]);
+        let parts = geometry.parts.iter().map(|p| (p.material.as_deref(), p.mesh.indices.len() / 3)).collect::<Vec<_>>();
+        // groups keep the material of the previous part
+        assert_eq!(parts, vec![(Some("red"), 2), (Some("red"), 1), (Some("blue"), 1)]);
+    }
+
+    #[test]
+    fn rejects_index_out_of_range() {
+        match parse_obj("v 0 0 0\nf 1 2 3\n".as_bytes()) {
+            Err(ParseError::Syntax { line, .. }) => assert_eq!(line, 2),
+            _ => panic!("face with missing vertices is parsed")
+        }
+    }
+}
@@ -15,7 +15,7 @@ impl Picture {
         }
     }
     pub fn mutate<'a, F>(&'a mut self, mutator: F)
-        where F: FnOnce(&'a mut[Color], usize, usize) -> ()
+        where F: FnOnce(&'a mut[Color], usize, usize)
     {
         mutator(&mut self.colors, self.width, self.height);
     }
@@ -25,7 +25,7 @@ impl Picture {
         println!("255");
         for j in (0..self.height).rev() {
             for i in 0..self.width {
-                print!("{}", self.colors[j * self.width + i].to_string())
+                print!("{}", self.colors[j * self.width + i])
             }
             println!()
         }
@@ -0,0 +1,507 @@
+use {
+    cgmath::{vec2, vec3},
+    std::{
+        fmt,
+        fs::File,
+        io::{self, BufRead, BufReader},
+        path::Path
+    },
+    crate::{
+        mesh_data::{MeshData, VertexDescription, triangulate}
+    }
+};
+
+#[derive(Debug)]
+pub enum PlyError {
+    Io(io::Error),
+    Header(String),
+    Data(String)
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlyError::Io(e) => write!(f, "{}", e),
+            PlyError::Header(message) => write!(f, "bad header: {}", message),
+            PlyError::Data(message) => write!(f, "bad data: {}", message)
+        }
+    }
+}
+
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => PlyError::Data("file is truncated".to_string()),
+            _ => PlyError::Io(e)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Result<Self, PlyError> {
+        match name {
+            "char" | "int8" => Ok(ScalarType::Int8),
+            "uchar" | "uint8" => Ok(ScalarType::UInt8),
+            "short" | "int16" => Ok(ScalarType::Int16),
+            "ushort" | "uint16" => Ok(ScalarType::UInt16),
+            "int" | "int32" => Ok(ScalarType::Int32),
+            "uint" | "uint32" => Ok(ScalarType::UInt32),
+            "float" | "float32" => Ok(ScalarType::Float32),
+            "double" | "float64" => Ok(ScalarType::Float64),
+            _ => Err(PlyError::Header(format!("unknown property type {}", name)))
+        }
+    }
+
+    /// Value which stands for full intensity of a color channel of this type
+    fn full_intensity(self) -> f64 {
+        match self {
+            ScalarType::Int8 => i8::MAX as f64,
+            ScalarType::UInt8 => u8::MAX as f64,
+            ScalarType::Int16 => i16::MAX as f64,
+            ScalarType::UInt16 => u16::MAX as f64,
+            ScalarType::Int32 => i32::MAX as f64,
+            ScalarType::UInt32 => u32::MAX as f64,
+            ScalarType::Float32 | ScalarType::Float64 => 1.0
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8
+        }
+    }
+}
+
+enum PropertyType {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType }
+}
+
+struct Property {
+    name: String,
+    ty: PropertyType
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>
+}
+
+impl Element {
+    fn property(&self, names: &[&str]) -> Option<usize> {
+        self.properties.iter().position(|p| names.contains(&p.name.as_str()))
+    }
+
+    /// Type of a property which has to hold a single value, like coordinates of vertices do
+    fn scalar_type(&self, property: usize) -> Result<ScalarType, PlyError> {
+        match self.properties[property].ty {
+            PropertyType::Scalar(ty) => Ok(ty),
+            PropertyType::List { .. } => Err(PlyError::Header(format!(
+                "property {} of element {} should be a scalar, not a list", self.properties[property].name, self.name
+            )))
+        }
+    }
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<Element>
+}
+
+fn read_header<R: BufRead>(reader: &mut R) -> Result<Header, PlyError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.trim_end() != "ply" {
+        return Err(PlyError::Header("file doesn't start with ply".to_string()));
+    }
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(PlyError::Header("end_header is missing".to_string()));
+        }
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            [] | ["comment", ..] | ["obj_info", ..] => {},
+            ["format", name, version] => {
+                if *version != "1.0" {
+                    return Err(PlyError::Header(format!("unsupported version {}", version)));
+                }
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(PlyError::Header(format!("unknown format {}", name)))
+                });
+            },
+            ["element", name, count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| PlyError::Header(format!("bad count {} of element {}", count, name)))?;
+                elements.push(Element { name: name.to_string(), count, properties: Vec::new() });
+            },
+            ["property", rest @ ..] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| PlyError::Header("property before any element".to_string()))?;
+                let property = match rest {
+                    ["list", count, item, name] => Property {
+                        name: name.to_string(),
+                        ty: PropertyType::List { count: ScalarType::parse(count)?, item: ScalarType::parse(item)? }
+                    },
+                    [ty, name] => Property { name: name.to_string(), ty: PropertyType::Scalar(ScalarType::parse(ty)?) },
+                    _ => return Err(PlyError::Header(format!("malformed property: {}", line.trim_end())))
+                };
+                element.properties.push(property);
+            },
+            ["end_header"] => break,
+            _ => return Err(PlyError::Header(format!("unexpected line: {}", line.trim_end())))
+        }
+    }
+    let format = format.ok_or_else(|| PlyError::Header("format is missing".to_string()))?;
+    Ok(Header { format, elements })
+}
+
+/// Values of a single element read from the file. Scalar properties have one value,
+/// list properties have as many as the list has
+struct Record {
+    values: Vec<f64>,
+    ranges: Vec<(usize, usize)>
+}
+
+impl Record {
+    fn scalar(&self, property: usize) -> f64 {
+        self.values[self.ranges[property].0]
+    }
+
+    fn list(&self, property: usize) -> &[f64] {
+        let (start, end) = self.ranges[property];
+        &self.values[start..end]
+    }
+}
+
+struct DataReader<R> {
+    reader: R,
+    format: Format,
+    line: String
+}
+
+impl<R: BufRead> DataReader<R> {
+    fn read_binary(&mut self, ty: ScalarType) -> Result<f64, PlyError> {
+        let mut buf = [0u8; 8];
+        let bytes = &mut buf[..ty.size()];
+        self.reader.read_exact(bytes)?;
+        if self.format == Format::BinaryBigEndian {
+            bytes.reverse();
+        }
+        let b = &buf;
+        Ok(match ty {
+            ScalarType::Int8 => b[0] as i8 as f64,
+            ScalarType::UInt8 => b[0] as f64,
+            ScalarType::Int16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            ScalarType::UInt16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            ScalarType::Int32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::UInt32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::Float32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::Float64 => f64::from_le_bytes(buf)
+        })
+    }
+
+    fn read_record(&mut self, element: &Element, record: &mut Record) -> Result<(), PlyError> {
+        record.values.clear();
+        record.ranges.clear();
+        if self.format == Format::Ascii {
+            // every element takes a line of its own in ascii files
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Err(PlyError::Data("file is truncated".to_string()));
+            }
+            let mut tokens = self.line.split_whitespace();
+            let mut next = || -> Result<f64, PlyError> {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| PlyError::Data(format!("{} has too few values", element.name)))?;
+                token.parse().map_err(|_| PlyError::Data(format!("bad number {}", token)))
+            };
+            for property in &element.properties {
+                let start = record.values.len();
+                match property.ty {
+                    PropertyType::Scalar(_) => record.values.push(next()?),
+                    PropertyType::List { .. } => {
+                        let count = next()? as usize;
+                        for _ in 0..count {
+                            record.values.push(next()?);
+                        }
+                    }
+                }
+                record.ranges.push((start, record.values.len()));
+            }
+        } else {
+            for property in &element.properties {
+                let start = record.values.len();
+                match property.ty {
+                    PropertyType::Scalar(ty) => {
+                        let value = self.read_binary(ty)?;
+                        record.values.push(value);
+                    },
+                    PropertyType::List { count, item } => {
+                        let count = self.read_binary(count)? as usize;
+                        for _ in 0..count {
+                            let value = self.read_binary(item)?;
+                            record.values.push(value);
+                        }
+                    }
+                }
+                record.ranges.push((start, record.values.len()));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find_triple(element: &Element, names: [&[&str]; 3]) -> Option<[usize; 3]> {
+    Some([element.property(names[0])?, element.property(names[1])?, element.property(names[2])?])
+}
+
+fn find_pair(element: &Element, names: [&[&str]; 2]) -> Option<[usize; 2]> {
+    Some([element.property(names[0])?, element.property(names[1])?])
+}
+
+/// Loads a mesh from a ply file, see parse_ply for what is read of it
+pub fn load_ply(path: &Path) -> Result<MeshData, PlyError> {
+    parse_ply(BufReader::new(File::open(path)?))
+}
+
+/// Reads vertices with their optional normals, colors and uvs and faces of a ply file.
+/// Integer colors are scaled to [0, 1] by the largest value of their type, and alpha is
+/// skipped since albedo has no transparency. Elements other than vertex and face are skipped
+pub fn parse_ply<R: BufRead>(mut reader: R) -> Result<MeshData, PlyError> {
+    let header = read_header(&mut reader)?;
+    let mut data = DataReader { reader, format: header.format, line: String::new() };
+    let mut record = Record { values: Vec::new(), ranges: Vec::new() };
+    let mut mesh = MeshData::default();
+    let mut has_normals = false;
+    let mut has_faces = false;
+    let mut vertex_count = None;
+
+    for element in &header.elements {
+        match element.name.as_str() {
+            "vertex" => {
+                let position = find_triple(element, [&["x"], &["y"], &["z"]])
+                    .ok_or_else(|| PlyError::Header("vertex element has no x, y or z property".to_string()))?;
+                let normal = find_triple(element, [&["nx"], &["ny"], &["nz"]]);
+                let uv = find_pair(element, [&["u", "s", "texture_u", "texture_s"], &["v", "t", "texture_v", "texture_t"]]);
+                let color = find_triple(element, [&["red", "diffuse_red"], &["green", "diffuse_green"], &["blue", "diffuse_blue"]]);
+                for &i in position.iter().chain(normal.iter().flatten()).chain(uv.iter().flatten()) {
+                    element.scalar_type(i)?;
+                }
+                let color_scale = match color {
+                    Some(channels) => Some([
+                        element.scalar_type(channels[0])?.full_intensity(),
+                        element.scalar_type(channels[1])?.full_intensity(),
+                        element.scalar_type(channels[2])?.full_intensity()
+                    ]),
+                    None => None
+                };
+                has_normals = normal.is_some();
+                // counts come from the file, so vertices are only stored as they are read
+                for _ in 0..element.count {
+                    data.read_record(element, &mut record)?;
+                    let vector = |[x, y, z]: [usize; 3]| vec3(
+                        record.scalar(x) as f32,
+                        record.scalar(y) as f32,
+                        record.scalar(z) as f32
+                    );
+                    mesh.vertices.push(VertexDescription {
+                        position: vector(position),
+                        normal: normal.map_or(vec3(0.0, 0.0, 0.0), vector),
+                        uv: uv.map_or(vec2(0.0, 0.0), |[u, v]| vec2(record.scalar(u) as f32, record.scalar(v) as f32))
+                    });
+                    if let (Some(channels), Some(scale)) = (color, color_scale) {
+                        let channel = |i: usize| (record.scalar(channels[i]) / scale[i]).clamp(0.0, 1.0) as f32;
+                        mesh.colors.push(vec3(channel(0), channel(1), channel(2)));
+                    }
+                }
+                vertex_count = Some(element.count);
+            },
+            "face" => {
+                let indices = element
+                    .property(&["vertex_indices", "vertex_index"])
+                    .ok_or_else(|| PlyError::Header("face element has no vertex_indices property".to_string()))?;
+                let vertex_count = vertex_count
+                    .ok_or_else(|| PlyError::Header("faces are defined before vertices".to_string()))?;
+                for face_id in 0..element.count {
+                    data.read_record(element, &mut record)?;
+                    let face = record.list(indices);
+                    if face.len() < 3 {
+                        return Err(PlyError::Data(format!("face {} has less than 3 vertices", face_id)));
+                    }
+                    let face = face
+                        .iter()
+                        .map(|&i| {
+                            if i < 0.0 || i.fract() != 0.0 || i.is_nan() {
+                                Err(PlyError::Data(format!("face {} has bad vertex index {}", face_id, i)))
+                            } else if i >= vertex_count as f64 {
+                                Err(PlyError::Data(format!("face {} refers to missing vertex {}", face_id, i)))
+                            } else {
+                                Ok(i as usize)
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let face_positions = face
+                        .iter()
+                        .map(|&i| mesh.vertices[i].position)
+                        .collect::<Vec<_>>();
+                    for [a, b, c] in triangulate(&face_positions) {
+                        mesh.indices.extend_from_slice(&[face[a], face[b], face[c]]);
+                    }
+                }
+                has_faces = true;
+            },
+            _ => {
+                for _ in 0..element.count {
+                    data.read_record(element, &mut record)?;
+                }
+            }
+        }
+    }
+    if !has_faces {
+        return Err(PlyError::Header("file has no faces, point clouds can't be rendered".to_string()));
+    }
+    if !has_normals {
+        mesh.generate_normals();
+    }
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(indices: &str) -> String {
+        format!(
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+             element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+             0 0 0\n1 0 0\n0 1 0\n3 {}\n",
+            indices
+        )
+    }
+
+    #[test]
+    fn reads_triangle() {
+        let mesh = parse_ply(triangle("0 1 2").as_bytes()).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn rejects_bad_indices() {
+        for indices in ["0 1 -1", "0 1 3", "0 1 1.5"] {
+            assert!(matches!(parse_ply(triangle(indices).as_bytes()), Err(PlyError::Data(_))), "{}", indices);
+        }
+    }
+
+    const FORMATS: [&str; 2] = ["binary_little_endian", "binary_big_endian"];
+
+    /// Triangle with a red, a green and a blue corner
+    fn binary_triangle(format: &str, indices: [i32; 3]) -> Vec<u8> {
+        let mut bytes = format!(
+            "ply\nformat {} 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\n\
+             element face 1\nproperty list uchar int vertex_indices\nend_header\n",
+            format
+        ).into_bytes();
+        let big_endian = format == "binary_big_endian";
+        let corners = [([0.0f32, 0.0, 0.0], [255u8, 0, 0]), ([1.0, 0.0, 0.0], [0, 255, 0]), ([0.0, 1.0, 0.0], [0, 0, 255])];
+        for (position, color) in corners {
+            for x in position {
+                bytes.extend_from_slice(&if big_endian { x.to_be_bytes() } else { x.to_le_bytes() });
+            }
+            bytes.extend_from_slice(&color);
+        }
+        bytes.push(3);
+        for i in indices {
+            bytes.extend_from_slice(&if big_endian { i.to_be_bytes() } else { i.to_le_bytes() });
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_binary_triangle() {
+        for format in FORMATS {
+            let mesh = parse_ply(binary_triangle(format, [0, 1, 2]).as_slice()).unwrap();
+            assert_eq!(mesh.indices, vec![0, 1, 2], "{}", format);
+            assert_eq!(mesh.vertices[1].position, vec3(1.0, 0.0, 0.0), "{}", format);
+            assert_eq!(mesh.colors, vec![vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)], "{}", format);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_binary_indices() {
+        for format in FORMATS {
+            for indices in [[0, 1, -1], [0, 1, 3]] {
+                let bytes = binary_triangle(format, indices);
+                assert!(matches!(parse_ply(bytes.as_slice()), Err(PlyError::Data(_))), "{} {:?}", format, indices);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_binary() {
+        for format in FORMATS {
+            let bytes = binary_triangle(format, [0, 1, 2]);
+            let truncated = &bytes[..bytes.len() - 1];
+            assert!(matches!(parse_ply(truncated), Err(PlyError::Data(_))), "{}", format);
+        }
+    }
+
+    #[test]
+    fn scales_colors_by_their_type() {
+        let ply = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                   property ushort red\nproperty ushort green\nproperty ushort blue\nproperty ushort alpha\n\
+                   element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+                   0 0 0 65535 0 0 0\n1 0 0 0 65535 0 0\n0 1 0 0 0 65535 0\n3 0 1 2\n";
+        let mesh = parse_ply(ply.as_bytes()).unwrap();
+        assert_eq!(mesh.colors, vec![vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)]);
+        // files without colors leave the albedo to the material
+        assert!(parse_ply(triangle("0 1 2").as_bytes()).unwrap().colors.is_empty());
+    }
+
+    #[test]
+    fn rejects_list_vertex_attributes() {
+        // an empty list as the last property used to be read past the end of the record
+        let attributes = [
+            "property float x\nproperty float y\nproperty list uchar float z\n",
+            "property float x\nproperty float y\nproperty float z\nproperty float nx\nproperty float ny\nproperty list uchar float nz\n",
+            "property float x\nproperty float y\nproperty float z\nproperty float u\nproperty list uchar float v\n",
+            "property float x\nproperty float y\nproperty float z\nproperty uchar red\nproperty uchar green\nproperty list uchar uchar blue\n"
+        ];
+        for attributes in attributes {
+            let ply = format!("ply\nformat ascii 1.0\nelement vertex 1\n{}end_header\n0 0 0 0 0 0\n", attributes);
+            assert!(matches!(parse_ply(ply.as_bytes()), Err(PlyError::Header(_))), "{}", attributes);
+        }
+    }
+}
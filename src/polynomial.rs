@@ -0,0 +1,143 @@
+// Polynomials are slices of coefficients going from the constant term up, they are evaluated
+// in f64 since roots of quartics are very sensitive to rounding of coefficients. Their degree
+// is at most MAX_DEGREE, so everything is kept in fixed arrays instead of allocating
+
+const MAX_ITERATIONS: usize = 100;
+/// Highest degree of polynomials here, fields of metaballs are sextics
+pub const MAX_DEGREE: usize = 6;
+
+/// Coefficients of a polynomial of degree up to MAX_DEGREE, higher ones being zeros
+pub type Coefficients = [f64; MAX_DEGREE + 1];
+
+pub fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+/// Product of polynomials, whose degree shouldn't be higher than MAX_DEGREE
+pub fn multiply(a: &[f64], b: &[f64]) -> Coefficients {
+    let mut product = [0.0; MAX_DEGREE + 1];
+    // zero coefficients are skipped, so padded polynomials can be multiplied as well
+    for (i, x) in a.iter().enumerate().filter(|(_, x)| **x != 0.0) {
+        for (j, y) in b.iter().enumerate().filter(|(_, y)| **y != 0.0) {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+fn derivative(coefficients: &[f64]) -> Coefficients {
+    let mut slope = [0.0; MAX_DEGREE + 1];
+    for (power, c) in coefficients.iter().enumerate().skip(1) {
+        slope[power - 1] = c * power as f64;
+    }
+    slope
+}
+
+/// Roots of a polynomial in ascending order, there are at most as many as its degree and
+/// a spare one for a root rounding puts at both ends of a piece
+pub struct Roots {
+    values: [f64; MAX_DEGREE + 1],
+    count: usize
+}
+
+impl Roots {
+    fn new() -> Self {
+        Roots { values: [0.0; MAX_DEGREE + 1], count: 0 }
+    }
+
+    fn push(&mut self, root: f64) {
+        self.values[self.count] = root;
+        self.count += 1;
+    }
+
+    fn last(&self) -> Option<f64> {
+        self.count.checked_sub(1).map(|last| self.values[last])
+    }
+}
+
+impl IntoIterator for Roots {
+    type Item = f64;
+    type IntoIter = std::iter::Take<std::array::IntoIter<f64, { MAX_DEGREE + 1 }>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.values).take(self.count)
+    }
+}
+
+/// Real roots of a polynomial within [lo, hi] in ascending order. Roots of the derivative
+/// split the interval into pieces where the polynomial is monotonic, so every piece with
+/// a change of sign has exactly one root. Unlike closed form solutions of cubics and
+/// quartics, this doesn't lose roots to cancellation when some of them are close
+pub fn roots_in_interval(coefficients: &[f64], lo: f64, hi: f64) -> Roots {
+    let mut roots = Roots::new();
+    let degree = match coefficients.iter().rposition(|&c| c != 0.0) {
+        Some(degree) => degree,
+        None => return roots
+    };
+    assert!(degree <= MAX_DEGREE, "polynomial of degree {} is too high", degree);
+    let coefficients = &coefficients[..=degree];
+    match degree {
+        0 => {},
+        1 => {
+            let root = -coefficients[0] / coefficients[1];
+            if root >= lo && root <= hi {
+                roots.push(root);
+            }
+        },
+        _ => {
+            // extrema split the interval, which are the roots of the derivative between its ends
+            let mut bounds = [0.0; MAX_DEGREE + 3];
+            let mut bound_count = 0;
+            for bound in std::iter::once(lo).chain(roots_in_interval(&derivative(coefficients), lo, hi)).chain([hi]) {
+                bounds[bound_count] = bound;
+                bound_count += 1;
+            }
+            for piece in bounds[..bound_count].windows(2) {
+                let (a, b) = (piece[0], piece[1]);
+                let (fa, fb) = (evaluate(coefficients, a), evaluate(coefficients, b));
+                let root = if fa == 0.0 {
+                    Some(a)
+                } else if fa * fb < 0.0 {
+                    Some(find_root(coefficients, a, b, fa))
+                } else {
+                    None
+                };
+                // a root at a bound between pieces would be found by both of them
+                if let Some(root) = root.filter(|&root| roots.last() != Some(root)) {
+                    roots.push(root);
+                }
+            }
+            if evaluate(coefficients, hi) == 0.0 && roots.last() != Some(hi) {
+                roots.push(hi);
+            }
+        }
+    }
+    roots
+}
+
+/// Newton iterations which fall back to bisection whenever they leave the bracket
+fn find_root(coefficients: &[f64], a: f64, b: f64, fa: f64) -> f64 {
+    let slope = derivative(coefficients);
+    let (mut a, mut b) = (a, b);
+    let a_negative = fa < 0.0;
+    let mut x = 0.5 * (a + b);
+    for _ in 0..MAX_ITERATIONS {
+        let fx = evaluate(coefficients, x);
+        if fx == 0.0 {
+            return x;
+        }
+        if (fx < 0.0) == a_negative {
+            a = x;
+        } else {
+            b = x;
+        }
+        let dfx = evaluate(&slope, x);
+        let newton = x - fx / dfx;
+        let next = if dfx != 0.0 && newton > a && newton < b { newton } else { 0.5 * (a + b) };
+        if (next - x).abs() <= f64::EPSILON * x.abs().max(1e-300) * 4.0 {
+            return next;
+        }
+        x = next;
+    }
+    x
+}
@@ -0,0 +1,293 @@
+use {
+    cgmath::{Vector2, Vector3, InnerSpace, vec2, vec3},
+    std::f32::consts::PI,
+    crate::{
+        float_utils::{abs, gamma},
+        material::Material,
+        ray::{HitInfo, Ray},
+        vector_utils::orthonormal_basis
+    }
+};
+
+// Hit tests of shapes made of quadric surfaces around the y axis. Every surface of a shape
+// gives its candidate hits and the nearest one within the interval is reported
+
+struct Candidate {
+    t: f32,
+    p: Vector3<f32>,
+    n: Vector3<f32>,
+    uv: Vector2<f32>,
+    dpdu: Vector3<f32>,
+    dpdv: Vector3<f32>
+}
+
+fn nearest<'a>(
+    candidates: impl Iterator<Item = Candidate>,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    candidates
+        .filter(|c| c.t > t_min && c.t < t_max)
+        .min_by(|a, b| a.t.total_cmp(&b.t))
+        .map(|Candidate { t, p, n, uv, dpdu, dpdv }| HitInfo {
+            t,
+            p,
+            n,
+            ng: n,
+            // points are projected back to surfaces, so the error doesn't depend on the ray
+            p_error: abs(p) * gamma(7),
+            material: *material,
+            uv: Some(uv),
+            dpdu,
+            dpdv
+        })
+}
+
+/// Real roots of a*t^2 + b*t + c in ascending order. The larger by magnitude root is found
+/// first and the other one from their product, so there is no cancellation
+pub fn solve_quadratic(a: f32, b: f32, c: f32) -> Option<(f32, f32)> {
+    if a == 0.0 {
+        return if b == 0.0 { None } else { Some((-c / b, -c / b)) };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let q = -0.5 * (b + discriminant.sqrt().copysign(b));
+    if q == 0.0 {
+        return Some((0.0, 0.0));
+    }
+    let (t0, t1) = (q / a, c / q);
+    Some((t0.min(t1), t0.max(t1)))
+}
+
+// u turns around y the same way it does on spheres
+fn angle_u(p: Vector3<f32>) -> f32 {
+    p.z.atan2(p.x) / (2.0 * PI) + 0.5
+}
+
+fn around_y(p: Vector3<f32>) -> Vector3<f32> {
+    vec3(-p.z, 0.0, p.x) * (2.0 * PI)
+}
+
+/// Flat cap at height y facing up or down, which is hit from both sides since it closes a solid
+fn cap(y: f32, radius: f32, facing: f32, ray: &Ray) -> Option<Candidate> {
+    if ray.direction.y == 0.0 || radius <= 0.0 {
+        return None;
+    }
+    let t = (y - ray.origin.y) / ray.direction.y;
+    let p = ray.get_point_at(t);
+    let p = vec3(p.x, y, p.z);
+    let rho = (p.x * p.x + p.z * p.z).sqrt();
+    if rho > radius {
+        return None;
+    }
+    let n = vec3(0.0, facing, 0.0);
+    let (dpdu, dpdv) = if rho > 0.0 {
+        (around_y(p), vec3(p.x, 0.0, p.z) * (radius / rho))
+    } else {
+        orthonormal_basis(n)
+    };
+    Some(Candidate { t, p, n, uv: vec2(angle_u(p), rho / radius), dpdu, dpdv })
+}
+
+/// Cone cut by planes at y = ±height / 2 with given radii at them, which is a cylinder when
+/// radii are equal. Uvs are the angle around y and the height from the bottom. Without caps
+/// it is a surface rather than a solid, and hits face the ray from either side. Cones
+/// without height have no slope and aren't hit, like caps without radius
+pub fn test_ray_cone_intersection<'a>(
+    (bottom_radius, top_radius): (f32, f32),
+    height: f32,
+    capped: bool,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    if height <= 0.0 {
+        return None;
+    }
+    let half_height = height * 0.5;
+    // radius grows by slope per unit of y starting from the middle one at y = 0
+    let slope = (top_radius - bottom_radius) / height;
+    let middle_radius = (bottom_radius + top_radius) * 0.5;
+    let radius_at = |y: f32| middle_radius + slope * y;
+
+    let (o, d) = (ray.origin, ray.direction);
+    let a = d.x * d.x + d.z * d.z - slope * slope * d.y * d.y;
+    let b = 2.0 * (o.x * d.x + o.z * d.z - slope * radius_at(o.y) * d.y);
+    let c = o.x * o.x + o.z * o.z - radius_at(o.y) * radius_at(o.y);
+    let roots = solve_quadratic(a, b, c).into_iter().flat_map(|(t0, t1)| [t0, t1]);
+    let side = roots.filter_map(|t| {
+        let p = ray.get_point_at(t);
+        let radius = radius_at(p.y);
+        let rho = (p.x * p.x + p.z * p.z).sqrt();
+        // the other nappe of the cone is behind its apex
+        if p.y.abs() > half_height || radius < 0.0 {
+            return None;
+        }
+        let (p, n, dpdv) = if rho > 0.0 && radius > 0.0 {
+            let (x, z) = (p.x / rho, p.z / rho);
+            let n = vec3(x, -slope, z).normalize();
+            (vec3(x * radius, p.y, z * radius), n, vec3(x * slope * height, height, z * slope * height))
+        } else {
+            // apex of the cone
+            (p, vec3(0.0, -slope.signum(), 0.0), vec3(0.0, height, 0.0))
+        };
+        let dpdu = if rho > 0.0 && radius > 0.0 { around_y(p) } else { orthonormal_basis(n).0 };
+        Some(Candidate { t, p, n, uv: vec2(angle_u(p), (p.y + half_height) / height), dpdu, dpdv })
+    });
+    let caps = if capped {
+        [cap(-half_height, bottom_radius, -1.0, ray), cap(half_height, top_radius, 1.0, ray)]
+    } else {
+        [None, None]
+    };
+    let hit_info = nearest(side.chain(IntoIterator::into_iter(caps).flatten()), t_min, t_max, material)?;
+    Some(if !capped && hit_info.ng.dot(ray.direction) > 0.0 {
+        HitInfo { n: -hit_info.n, ng: -hit_info.ng, dpdv: -hit_info.dpdv, ..hit_info }
+    } else {
+        hit_info
+    })
+}
+
+/// Cylinder of given height between hemispheres, so the whole capsule is taller by
+/// the diameter. V goes along y through hemispheres as well
+pub fn test_ray_capsule_intersection<'a>(
+    radius: f32,
+    height: f32,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    let half_height = height * 0.5;
+    let length = height + 2.0 * radius;
+    let (o, d) = (ray.origin, ray.direction);
+    let candidate = |t: f32, p: Vector3<f32>, n: Vector3<f32>| {
+        let dpdu = around_y(p);
+        let dpdv = if dpdu.magnitude2() > 0.0 { dpdu.cross(n).normalize() * length } else { orthonormal_basis(n).1 };
+        Candidate { t, p, n, uv: vec2(angle_u(p), (p.y + half_height + radius) / length), dpdu, dpdv }
+    };
+
+    let side = solve_quadratic(d.x * d.x + d.z * d.z, 2.0 * (o.x * d.x + o.z * d.z), o.x * o.x + o.z * o.z - radius * radius)
+        .into_iter()
+        .flat_map(|(t0, t1)| [t0, t1])
+        .filter_map(|t| {
+            let p = ray.get_point_at(t);
+            let rho = (p.x * p.x + p.z * p.z).sqrt();
+            if p.y.abs() > half_height || rho == 0.0 {
+                return None;
+            }
+            let n = vec3(p.x, 0.0, p.z) / rho;
+            Some(candidate(t, vec3(n.x * radius, p.y, n.z * radius), n))
+        });
+    let hemispheres = [-1.0f32, 1.0].iter().flat_map(|&facing| {
+        let center = vec3(0.0, half_height * facing, 0.0);
+        let oc = o - center;
+        solve_quadratic(d.dot(d), 2.0 * oc.dot(d), oc.dot(oc) - radius * radius)
+            .into_iter()
+            .flat_map(|(t0, t1)| [t0, t1])
+            .filter_map(move |t| {
+                let n = (ray.get_point_at(t) - center).normalize();
+                // each hemisphere is the half of its sphere facing away from the cylinder
+                if n.y * facing >= 0.0 { Some(candidate(t, center + n * radius, n)) } else { None }
+            })
+    });
+    nearest(side.chain(hemispheres), t_min, t_max, material)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn assert_hit(hit_info: Option<HitInfo>, t: f32, n: Vector3<f32>) {
+        let hit_info = hit_info.unwrap();
+        assert!((hit_info.t - t).abs() < 1e-4, "t is {} instead of {}", hit_info.t, t);
+        assert!((hit_info.n - n).magnitude() < 1e-4, "n is {:?} instead of {:?}", hit_info.n, n);
+    }
+
+    #[test]
+    fn cylinder_is_hit_on_side_and_caps() {
+        let test = |ray: &Ray| test_ray_cone_intersection((1.0, 1.0), 2.0, true, ray, 0.0, 100.0, &MATERIAL);
+        let ray = Ray { origin: vec3(-5.0, 0.5, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        assert_hit(test(&ray), 4.0, vec3(-1.0, 0.0, 0.0));
+        let ray = Ray { origin: vec3(0.5, 5.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        assert_hit(test(&ray), 4.0, vec3(0.0, 1.0, 0.0));
+        // ray passing above the top cap misses it
+        let ray = Ray { origin: vec3(-5.0, 1.5, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        assert!(test(&ray).is_none());
+    }
+
+    #[test]
+    fn ray_from_inside_of_cylinder_leaves_it() {
+        let test = |ray: &Ray, capped| test_ray_cone_intersection((1.0, 1.0), 2.0, capped, ray, 0.0, 100.0, &MATERIAL);
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, 0.0, 2.0) };
+        assert_hit(test(&ray, true), 0.5, vec3(0.0, 0.0, 1.0));
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        assert_hit(test(&ray, true), 1.0, vec3(0.0, -1.0, 0.0));
+        // without caps the ray goes out through the open end
+        assert!(test(&ray, false).is_none());
+    }
+
+    #[test]
+    fn inner_wall_of_open_cylinder_faces_ray() {
+        let test = |ray: &Ray| test_ray_cone_intersection((1.0, 1.0), 2.0, false, ray, 0.0, 100.0, &MATERIAL);
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, 0.0, 2.0) };
+        assert_hit(test(&ray), 0.5, vec3(0.0, 0.0, -1.0));
+        // coming through the open top
+        let ray = Ray { origin: vec3(0.0, 1.5, 0.0), direction: vec3(0.6, -0.8, 0.0) };
+        let hit_info = test(&ray).unwrap();
+        assert!(hit_info.ng.dot(ray.direction) < 0.0);
+        assert!((hit_info.p.x - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn cone_normal_leans_towards_apex() {
+        // whole cone of height 2 with unit radius at its bottom
+        let test = |ray: &Ray| test_ray_cone_intersection((1.0, 0.0), 2.0, true, ray, 0.0, 100.0, &MATERIAL);
+        let ray = Ray { origin: vec3(-5.0, 0.0, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        assert_hit(test(&ray), 4.5, vec3(-2.0, 1.0, 0.0).normalize());
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        assert_hit(test(&ray), 0.5, vec3(2.0, 1.0, 0.0).normalize());
+        let ray = Ray { origin: vec3(0.0, -5.0, 0.0), direction: vec3(0.0, 1.0, 0.0) };
+        assert_hit(test(&ray), 4.0, vec3(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn capsule_is_hit_on_side_and_hemispheres() {
+        let test = |ray: &Ray| test_ray_capsule_intersection(1.0, 2.0, ray, 0.0, 100.0, &MATERIAL);
+        let ray = Ray { origin: vec3(-5.0, 0.5, 0.0), direction: vec3(1.0, 0.0, 0.0) };
+        assert_hit(test(&ray), 4.0, vec3(-1.0, 0.0, 0.0));
+        let ray = Ray { origin: vec3(0.0, 5.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        assert_hit(test(&ray), 3.0, vec3(0.0, 1.0, 0.0));
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        assert_hit(test(&ray), 2.0, vec3(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn flat_cone_is_not_hit() {
+        let ray = Ray { origin: vec3(0.5, 5.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        assert!(test_ray_cone_intersection((1.0, 0.5), 0.0, true, &ray, 0.0, 100.0, &MATERIAL).is_none());
+        assert!(test_ray_cone_intersection((1.0, 1.0), 0.0, false, &ray, 0.0, 100.0, &MATERIAL).is_none());
+    }
+
+    #[test]
+    fn capsule_without_height_is_sphere() {
+        let ray = Ray { origin: vec3(0.0, 0.0, -5.0), direction: vec3(0.0, 0.0, 1.0) };
+        assert_hit(test_ray_capsule_intersection(1.0, 0.0, &ray, 0.0, 100.0, &MATERIAL), 4.0, vec3(0.0, 0.0, -1.0));
+    }
+}
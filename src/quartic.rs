@@ -0,0 +1,166 @@
+use {
+    cgmath::{InnerSpace, Vector3, vec3},
+    crate::{
+        aabb::AaBb,
+        float_utils::{abs, gamma},
+        material::Material,
+        polynomial::{Coefficients, MAX_DEGREE, multiply, roots_in_interval},
+        ray::{HitInfo, Ray},
+        vector_utils::orthonormal_basis
+    }
+};
+
+/// Highest total degree of terms, which keeps products of their factors within MAX_DEGREE
+pub const MAX_TERM_DEGREE: usize = 4;
+
+/// Term coefficient * x^i * y^j * z^k of the polynomial of an implicit surface, where
+/// powers are [i, j, k] and add up to at most MAX_TERM_DEGREE
+#[derive(Copy, Clone)]
+pub struct Monomial {
+    pub coefficient: f32,
+    pub powers: [usize; 3]
+}
+
+fn gradient(terms: &[Monomial], p: Vector3<f64>) -> Vector3<f64> {
+    let power = |x: f64, n: usize| if n == 0 { 1.0 } else { x.powi(n as i32) };
+    let mut gradient = vec3(0.0, 0.0, 0.0);
+    for term in terms {
+        let [i, j, k] = term.powers;
+        let c = term.coefficient as f64;
+        let (x, y, z) = (power(p.x, i), power(p.y, j), power(p.z, k));
+        if i > 0 { gradient.x += c * i as f64 * power(p.x, i - 1) * y * z; }
+        if j > 0 { gradient.y += c * j as f64 * x * power(p.y, j - 1) * z; }
+        if k > 0 { gradient.z += c * k as f64 * x * y * power(p.z, k - 1); }
+    }
+    gradient
+}
+
+/// Powers of o + s d up to MAX_TERM_DEGREE as polynomials of s
+fn powers_along(o: f64, d: f64) -> [Coefficients; MAX_TERM_DEGREE + 1] {
+    let mut powers = [[0.0; MAX_DEGREE + 1]; MAX_TERM_DEGREE + 1];
+    powers[0][0] = 1.0;
+    for n in 1..=MAX_TERM_DEGREE {
+        powers[n] = multiply(&powers[n - 1], &[o, d]);
+    }
+    powers
+}
+
+/// Surface where the sum of terms is zero, searched for within bounds. Its normal goes
+/// towards where the sum is positive, and it has no uvs since there is no parametrization
+/// shared by all such surfaces
+pub fn test_ray_quartic_intersection<'a>(
+    terms: &[Monomial],
+    bounds: &AaBb,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    debug_assert!(terms.iter().all(|term| term.powers.iter().sum::<usize>() <= MAX_TERM_DEGREE));
+    // like for tori, the polynomial along the ray starts where it enters bounds
+    let inv_dir = vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+    let (near, far) = bounds.hit_interval(ray, &inv_dir, t_min, t_max)?;
+    let o = (ray.origin + ray.direction * near).cast::<f64>().unwrap();
+    let d = ray.direction.cast::<f64>().unwrap();
+    let (xs, ys, zs) = (powers_along(o.x, d.x), powers_along(o.y, d.y), powers_along(o.z, d.z));
+
+    let mut coefficients = [0.0; MAX_DEGREE + 1];
+    for term in terms {
+        let [i, j, k] = term.powers;
+        let product = multiply(&multiply(&xs[i], &ys[j]), &zs[k]);
+        for (sum, c) in coefficients.iter_mut().zip(product.iter()) {
+            *sum += term.coefficient as f64 * c;
+        }
+    }
+    // roots are searched a bit past both ends, so surfaces touching bounds aren't lost
+    // to rounding at the point where the ray enters them
+    let margin = (far - near) as f64 * 1e-3;
+    let roots = roots_in_interval(&coefficients, -margin, (far - near) as f64 + margin);
+    let t = roots.into_iter().map(|s| near + s as f32).find(|&t| t > t_min && t < t_max)?;
+
+    let p = ray.get_point_at(t);
+    let gradient = gradient(terms, p.cast::<f64>().unwrap());
+    // singular points such as the apex of a cone have no gradient
+    let n = if gradient.magnitude2() > 0.0 {
+        gradient.normalize().cast::<f32>().unwrap()
+    } else {
+        -ray.direction.normalize()
+    };
+    let (dpdu, dpdv) = orthonormal_basis(n);
+    // the root is exact up to rounding of t, which moves the point along the ray
+    let p_error = (abs(p) + abs(ray.direction) * t.abs()) * gamma(7);
+    Some(HitInfo { t, p, n, ng: n, p_error, material: *material, uv: None, dpdu, dpdv })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        rand::{Rng, SeedableRng, rngs::StdRng},
+        crate::{
+            material::{Albedo, MaterialDetails, Relief},
+            torus::test_ray_torus_intersection
+        }
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn term(coefficient: f32, powers: [usize; 3]) -> Monomial {
+        Monomial { coefficient, powers }
+    }
+
+    /// (x^2 + y^2 + z^2 + R^2 - r^2)^2 - 4 R^2 (x^2 + z^2) expanded
+    fn torus(major_radius: f32, minor_radius: f32) -> Vec<Monomial> {
+        let r2 = major_radius * major_radius;
+        let k = r2 - minor_radius * minor_radius;
+        vec![
+            term(1.0, [4, 0, 0]), term(1.0, [0, 4, 0]), term(1.0, [0, 0, 4]),
+            term(2.0, [2, 2, 0]), term(2.0, [2, 0, 2]), term(2.0, [0, 2, 2]),
+            term(2.0 * k - 4.0 * r2, [2, 0, 0]), term(2.0 * k, [0, 2, 0]), term(2.0 * k - 4.0 * r2, [0, 0, 2]),
+            term(k * k, [0, 0, 0])
+        ]
+    }
+
+    #[test]
+    fn sphere_is_hit_at_its_radius() {
+        let sphere = [term(1.0, [2, 0, 0]), term(1.0, [0, 2, 0]), term(1.0, [0, 0, 2]), term(-1.0, [0, 0, 0])];
+        let bounds = AaBb { min: vec3(-2.0, -2.0, -2.0), max: vec3(2.0, 2.0, 2.0) };
+        let ray = Ray { origin: vec3(0.0, 0.0, -5.0), direction: vec3(0.0, 0.0, 1.0) };
+        let hit_info = test_ray_quartic_intersection(&sphere, &bounds, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 4.0).abs() < 1e-4);
+        assert!((hit_info.n - vec3(0.0, 0.0, -1.0)).magnitude() < 1e-4);
+        assert!(test_ray_quartic_intersection(&sphere, &bounds, &ray, 0.0, 3.0, &MATERIAL).is_none());
+    }
+
+    #[test]
+    fn torus_terms_hit_like_torus() {
+        let (major_radius, minor_radius) = (2.0, 0.5);
+        let terms = torus(major_radius, minor_radius);
+        let bounds = AaBb { min: vec3(-2.5, -0.5, -2.5), max: vec3(2.5, 0.5, 2.5) };
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let origin = vec3(rng.gen_range(-4.0, 4.0), rng.gen_range(-2.0, 2.0), rng.gen_range(-4.0, 4.0));
+            let target = vec3(rng.gen_range(-2.5, 2.5), rng.gen_range(-0.5, 0.5), rng.gen_range(-2.5, 2.5));
+            let ray = Ray { origin, direction: (target - origin).normalize() };
+            let quartic = test_ray_quartic_intersection(&terms, &bounds, &ray, 0.0, 100.0, &MATERIAL);
+            let torus = test_ray_torus_intersection(major_radius, minor_radius, &ray, 0.0, 100.0, &MATERIAL);
+            match (quartic, torus) {
+                (Some(quartic), Some(torus)) => {
+                    assert!((quartic.t - torus.t).abs() < 1e-3);
+                    assert!((quartic.n - torus.n).magnitude() < 1e-2);
+                    hits += 1;
+                },
+                (None, None) => {},
+                (quartic, torus) => panic!("quartic hits at {:?}, torus at {:?}", quartic.map(|h| h.t), torus.map(|h| h.t))
+            }
+        }
+        assert!(hits > 100);
+    }
+}
@@ -1,7 +1,8 @@
 use {
     cgmath::Vector3,
     cgmath::Vector2,
-    crate::material::Material
+    crate::material::Material,
+    crate::float_utils::offset_ray_origin
 };
 
 #[derive(Clone, Copy)]
@@ -15,19 +16,44 @@ pub struct HitInfo<'a> {
     pub t: f32,
     pub p: Vector3<f32>,
     pub n: Vector3<f32>,
+    // geometric normal, which could differ from the shading one (i.e. for meshes)
+    pub ng: Vector3<f32>,
+    // conservative bound of an absolute error of p
+    pub p_error: Vector3<f32>,
     pub material: Material<'a>,
-    pub uv: Option<Vector2<f32>>
+    pub uv: Option<Vector2<f32>>,
+    // derivatives of p by u and v, which make the tangent frame normals are perturbed in
+    pub dpdu: Vector3<f32>,
+    pub dpdv: Vector3<f32>
 }
 
 pub trait HitTestable {
-    fn hit_test(&self, ray: &Ray) -> Option<HitInfo>;
+    /// Finds the nearest hit with a distance strictly inside (t_min, t_max)
+    fn hit_test(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitInfo<'_>>;
+
+    /// Tells if there is anything between ray origin and t_max. Unlike hit_test it is free to
+    /// stop at the first hit found, so it should be preferred for shadow rays and alike
+    fn occluded(&self, ray: &Ray, t_max: f32) -> bool {
+        self.hit_test(ray, 0.0, t_max).is_some()
+    }
 }
 
 impl Ray {
     pub fn get_point_at(&self, t: f32) -> Vector3<f32> {
         self.origin + self.direction * t
     }
-    pub fn hit_test<'a, T: HitTestable>(&self, hit_testable: &'a T) -> Option<HitInfo<'a>> {
-        hit_testable.hit_test(self)
+    pub fn hit_test<'a, T: HitTestable>(&self, hit_testable: &'a T, t_min: f32, t_max: f32) -> Option<HitInfo<'a>> {
+        hit_testable.hit_test(self, t_min, t_max)
+    }
+}
+
+impl HitInfo<'_> {
+    /// Makes a ray leaving the hit point. Its origin is moved out of the error bounds of
+    /// the point to the side the ray goes to, so it could be traced starting from t = 0
+    pub fn spawn_ray(&self, direction: Vector3<f32>) -> Ray {
+        Ray {
+            origin: offset_ray_origin(self.p, self.p_error, self.ng, direction),
+            direction
+        }
     }
 }
\ No newline at end of file
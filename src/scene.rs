@@ -4,7 +4,7 @@ use {
     rayon::prelude::*,
     crate::camera::{Camera, Origin, Up, Fov, Target},
     crate::picture::Picture,
-    crate::vector_utils,
+    crate::sampling,
     crate::material::AlbedoFn,
     crate::world::World,
     rand::prelude::*
@@ -19,31 +19,27 @@ pub struct Scene<'a> {
     pub focus_distance: f32,
     pub aperture: f32,
     pub num_samples: u16,
-    pub max_t: f32
+    pub max_t: f32,
+    // camera orbits around the center of the scene if there is none
+    pub camera: Option<Camera>
 }
 
 impl Scene<'_> {
     pub fn sample_color(&self, ray: &Ray, rng: &mut ThreadRng, depth: u8) -> Vector3<f32> {
-        let nearest_hit = ray.hit_test(&self.world);
         if depth == 0 {
             return Vector3::new(0.0, 0.0, 0.0);
         }
-        let sky_clr = sky_color(&ray);
-        if let Some(HitInfo{material, t, uv, ..}) = nearest_hit {
-            if let Some((clr, ray_reflect)) = material.scatter(&ray, rng, &nearest_hit.unwrap()) {
+        let nearest_hit = ray.hit_test(&self.world, 0.0, self.max_t);
+        if let Some(HitInfo{material, uv, ..}) = nearest_hit {
+            if let Some((clr, ray_reflect)) = material.scatter(ray, rng, &nearest_hit.unwrap()) {
                 let uv = uv.unwrap_or(vec2(0.0, 0.0));
                 let albedo = material.albedo.get_color(uv);
-                let c = mul(self.sample_color(&ray_reflect, rng, depth-1), clr).lerp(albedo, material.emittance);
-                if t > self.max_t {
-                    sky_clr
-                } else {
-                    c
-                }
+                mul(self.sample_color(&ray_reflect, rng, depth-1), clr).lerp(albedo, material.emittance)
             } else {
                 Vector3::new(0.0, 0.0, 0.0)
             }
         } else {
-            sky_clr
+            sky_color(ray)
         }
     }
 
@@ -66,7 +62,7 @@ impl Scene<'_> {
             up_vector * j +
             forward_vector).normalize() * self.focus_distance;
 
-        let offset_disk = vector_utils::get_random_in_unit_disk(&mut rng) * self.aperture;
+        let offset_disk = sampling::get_random_in_unit_disk(&mut rng) * self.aperture;
         let origin_with_offset =
             camera.origin +
             right_vector * offset_disk.x +
@@ -85,12 +81,12 @@ impl Scene<'_> {
         let t = (times + 100) as f32 / 50.0;
         pic.mutate(|colors, w, h| {
             let aspect = w as f32 / h as f32;
-            let camera = Camera::new(
+            let camera = self.camera.unwrap_or_else(|| Camera::new(
                 Origin(Vector3::new(0.0, 0.0, 1.0) + Vector3::new(2.0 * t.cos(), 0.0, 2.0 * t.sin())),
                 Up(Vector3::unit_y()),
                 Fov(70.0f32.to_radians()),
                 Target(Vector3::new(0.0, 0.0, 1.0))
-            );
+            ));
             let basis_vectors = camera.get_basis_vectors(aspect);
             let mut stride = 0;
             let fact_samples = self.num_samples as f32;
@@ -0,0 +1,82 @@
+use {
+    crate::shape::Shape,
+    crate::transform::Transform
+};
+
+/// Node of a scene hierarchy. Transform of a group is applied on top of transforms of all its
+/// children, so assemblies of shapes could be moved around as a whole
+pub enum SceneNode<'a> {
+    Shape(Shape<'a>),
+    Group {
+        transform: Transform,
+        children: Vec<SceneNode<'a>>
+    }
+}
+
+impl<'a> SceneNode<'a> {
+    pub fn group(transform: Transform, children: Vec<SceneNode<'a>>) -> Self {
+        SceneNode::Group { transform, children }
+    }
+
+    /// Collects every shape of the hierarchy with transforms of all its ancestors baked in.
+    /// Result could be passed to World::construct directly, or it could be made a World
+    /// of its own and placed as many times as needed with Shape::Group
+    pub fn flatten(&self) -> Vec<Shape<'a>> {
+        let mut shapes = Vec::new();
+        self.flatten_into(&Transform::identity(), &mut shapes);
+        shapes
+    }
+
+    fn flatten_into(&self, parent_transform: &Transform, shapes: &mut Vec<Shape<'a>>) {
+        match self {
+            SceneNode::Shape(shape) => {
+                let mut shape = *shape;
+                let transform = shape.transform_mut();
+                *transform = *parent_transform * *transform;
+                shapes.push(shape);
+            },
+            SceneNode::Group { transform, children } => {
+                let transform = *parent_transform * *transform;
+                for child in children {
+                    child.flatten_into(&transform, shapes);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::{InnerSpace, Vector3, vec3},
+        crate::material::{Albedo, Material, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn sphere(transform: Transform) -> SceneNode<'static> {
+        SceneNode::Shape(Shape::Sphere { radius: 1.0, transform, material: MATERIAL })
+    }
+
+    #[test]
+    fn transforms_compose_from_root_down() {
+        let scaled = Transform::new(vec3(0.0, 0.0, 0.0), cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0), vec3(2.0, 2.0, 2.0)).unwrap();
+        let root = SceneNode::group(Transform::translation(vec3(10.0, 0.0, 0.0)), vec![
+            sphere(Transform::identity()),
+            SceneNode::group(scaled, vec![sphere(Transform::translation(vec3(0.0, 1.0, 0.0)))])
+        ]);
+        let shapes = root.flatten();
+        assert_eq!(shapes.len(), 2);
+        let origin = vec3(0.0, 0.0, 0.0);
+        assert!((shapes[0].transform().point(origin) - vec3(10.0, 0.0, 0.0)).magnitude() < 1e-5);
+        // the child is moved up in the scaled space of its group, so it ends up twice as high
+        assert!((shapes[1].transform().point(origin) - vec3(10.0, 2.0, 0.0)).magnitude() < 1e-5);
+        assert!((shapes[1].transform().vector(Vector3::unit_x()) - vec3(2.0, 0.0, 0.0)).magnitude() < 1e-5);
+    }
+}
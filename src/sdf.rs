@@ -0,0 +1,250 @@
+use {
+    cgmath::{Vector3, Quaternion, InnerSpace, Rotation, vec3},
+    crate::{
+        aabb::AaBb,
+        material::Material,
+        ray::{HitInfo, Ray},
+        vector_utils::orthonormal_basis
+    }
+};
+
+/// Sphere tracing gives up after this many steps, which happens to rays grazing a surface
+const MAX_STEPS: usize = 256;
+/// Distance at which a surface is considered hit, in units of the local space of a shape
+const SURFACE_DISTANCE: f32 = 1e-4;
+/// Offset of points the gradient is estimated at
+const GRADIENT_STEP: f32 = 1e-4;
+const MANDELBULB_BAILOUT: f32 = 2.0;
+
+/// Signed distance function, negative inside of a surface. Operators refer to other
+/// functions, so whole trees could be built out of borrowed nodes
+#[derive(Copy, Clone)]
+pub enum Sdf<'a> {
+    Sphere { radius: f32 },
+    Box { half_sizes: Vector3<f32> },
+    /// box with its edges rounded by radius, which still fits into half sizes
+    RoundBox { half_sizes: Vector3<f32>, radius: f32 },
+    /// torus around y like Shape::Torus
+    Torus { major_radius: f32, minor_radius: f32 },
+    /// segment between points a and b thickened by radius
+    Capsule { a: Vector3<f32>, b: Vector3<f32>, radius: f32 },
+    /// power 8 gives the classic bulb, iterations add detail at the cost of speed
+    Mandelbulb { power: f32, iterations: usize },
+    /// Operators blend surfaces within smoothness of each other, zero gives sharp edges
+    Union { left: &'a Sdf<'a>, right: &'a Sdf<'a>, smoothness: f32 },
+    Intersection { left: &'a Sdf<'a>, right: &'a Sdf<'a>, smoothness: f32 },
+    /// the right function is cut out of the left one
+    Subtraction { left: &'a Sdf<'a>, right: &'a Sdf<'a>, smoothness: f32 },
+    /// moves and rotates a function, which unlike scaling keeps distances right
+    Placed { translation: Vector3<f32>, rotation: Quaternion<f32>, sdf: &'a Sdf<'a> },
+    /// repeats a function every period along axes, zero period leaves an axis alone.
+    /// The function should fit into a single cell
+    Repeat { period: Vector3<f32>, sdf: &'a Sdf<'a> },
+    /// twists a function around y by rate radians per unit of height
+    Twist { rate: f32, sdf: &'a Sdf<'a> }
+}
+
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+impl Sdf<'_> {
+    pub fn distance(&self, p: Vector3<f32>) -> f32 {
+        match *self {
+            Sdf::Sphere { radius } => p.magnitude() - radius,
+            Sdf::Box { half_sizes } => box_distance(p, half_sizes),
+            Sdf::RoundBox { half_sizes, radius } => box_distance(p, half_sizes - vec3(radius, radius, radius)) - radius,
+            Sdf::Torus { major_radius, minor_radius } => {
+                let ring = (p.x * p.x + p.z * p.z).sqrt() - major_radius;
+                (ring * ring + p.y * p.y).sqrt() - minor_radius
+            },
+            Sdf::Capsule { a, b, radius } => {
+                let (pa, ba) = (p - a, b - a);
+                let h = (pa.dot(ba) / ba.magnitude2()).clamp(0.0, 1.0);
+                (pa - ba * h).magnitude() - radius
+            },
+            Sdf::Mandelbulb { power, iterations } => mandelbulb_distance(p, power, iterations),
+            // smooth minimum and maximum by Inigo Quilez
+            Sdf::Union { left, right, smoothness } => {
+                let (a, b) = (left.distance(p), right.distance(p));
+                if smoothness <= 0.0 {
+                    return a.min(b);
+                }
+                let h = (0.5 + 0.5 * (b - a) / smoothness).clamp(0.0, 1.0);
+                mix(b, a, h) - smoothness * h * (1.0 - h)
+            },
+            Sdf::Intersection { left, right, smoothness } => {
+                let (a, b) = (left.distance(p), right.distance(p));
+                if smoothness <= 0.0 {
+                    return a.max(b);
+                }
+                let h = (0.5 - 0.5 * (b - a) / smoothness).clamp(0.0, 1.0);
+                mix(b, a, h) + smoothness * h * (1.0 - h)
+            },
+            Sdf::Subtraction { left, right, smoothness } => {
+                let (a, b) = (left.distance(p), right.distance(p));
+                if smoothness <= 0.0 {
+                    return a.max(-b);
+                }
+                let h = (0.5 - 0.5 * (a + b) / smoothness).clamp(0.0, 1.0);
+                mix(a, -b, h) + smoothness * h * (1.0 - h)
+            },
+            Sdf::Placed { translation, rotation, sdf } => sdf.distance(rotation.invert().rotate_vector(p - translation)),
+            Sdf::Repeat { period, sdf } => {
+                let repeat = |x: f32, period: f32| if period > 0.0 { x - period * (x / period).round() } else { x };
+                sdf.distance(vec3(repeat(p.x, period.x), repeat(p.y, period.y), repeat(p.z, period.z)))
+            },
+            Sdf::Twist { rate, sdf } => {
+                let (sin, cos) = (-rate * p.y).sin_cos();
+                sdf.distance(vec3(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z))
+            }
+        }
+    }
+
+    /// How much faster than the distance to a surface the function could change. It is one
+    /// for exact distances, but twisting stretches space further from the axis
+    fn lipschitz(&self, radius: f32) -> f32 {
+        match *self {
+            Sdf::Union { left, right, .. } |
+            Sdf::Intersection { left, right, .. } |
+            Sdf::Subtraction { left, right, .. } => left.lipschitz(radius).max(right.lipschitz(radius)),
+            Sdf::Placed { sdf, .. } | Sdf::Repeat { sdf, .. } => sdf.lipschitz(radius),
+            Sdf::Twist { rate, sdf } => sdf.lipschitz(radius) * (1.0 + (rate * radius).powi(2)).sqrt(),
+            _ => 1.0
+        }
+    }
+
+    /// Gradient estimated from four samples at corners of a tetrahedron
+    fn normal(&self, p: Vector3<f32>) -> Vector3<f32> {
+        let corners = [vec3(1.0, -1.0, -1.0), vec3(-1.0, -1.0, 1.0), vec3(-1.0, 1.0, -1.0), vec3(1.0, 1.0, 1.0)];
+        let gradient = corners
+            .iter()
+            .fold(vec3(0.0, 0.0, 0.0), |acc, k| acc + k * self.distance(p + k * GRADIENT_STEP));
+        if gradient.magnitude2() > 0.0 { gradient.normalize() } else { Vector3::unit_y() }
+    }
+}
+
+fn box_distance(p: Vector3<f32>, half_sizes: Vector3<f32>) -> f32 {
+    let q = vec3(p.x.abs(), p.y.abs(), p.z.abs()) - half_sizes;
+    let outside = vec3(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).magnitude();
+    outside + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+/// Distance estimate of the power Mandelbulb with y as its axis
+fn mandelbulb_distance(p: Vector3<f32>, power: f32, iterations: usize) -> f32 {
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut r = z.magnitude();
+    for _ in 0..iterations {
+        if r > MANDELBULB_BAILOUT || r == 0.0 {
+            break;
+        }
+        let theta = (z.y / r).acos() * power;
+        let phi = z.z.atan2(z.x) * power;
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        z = vec3(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()) * r.powf(power) + p;
+        r = z.magnitude();
+    }
+    if r == 0.0 { 0.0 } else { 0.5 * r.ln() * r / dr }
+}
+
+/// Marches the ray by distances to the surface, which can't overshoot it. Rays starting
+/// inside (i.e. refracted ones) march by negated distances to find their way out
+pub fn test_ray_sdf_intersection<'a>(
+    sdf: &Sdf,
+    bounds: &AaBb,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    let inv_dir = vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+    let (near, far) = bounds.hit_interval(ray, &inv_dir, t_min, t_max)?;
+    let speed = ray.direction.magnitude();
+    let radius = (bounds.max - bounds.min).magnitude() * 0.5;
+    let step_scale = 1.0 / (sdf.lipschitz(radius) * speed);
+    let side = if sdf.distance(ray.get_point_at(near)) < 0.0 { -1.0 } else { 1.0 };
+
+    let mut t = near;
+    for _ in 0..MAX_STEPS {
+        let p = ray.get_point_at(t);
+        let distance = sdf.distance(p) * side;
+        if distance < SURFACE_DISTANCE {
+            if t <= t_min {
+                // the ray starts on the surface, so it is stepped off it
+                t += SURFACE_DISTANCE * step_scale;
+                continue;
+            }
+            let n = sdf.normal(p);
+            let (dpdu, dpdv) = orthonormal_basis(n);
+            return Some(HitInfo {
+                t,
+                p,
+                n,
+                ng: n,
+                // spawned rays have to start farther than the surface distance from it
+                p_error: vec3(SURFACE_DISTANCE, SURFACE_DISTANCE, SURFACE_DISTANCE) * 2.0,
+                material: *material,
+                uv: None,
+                dpdu,
+                dpdv
+            });
+        }
+        t += distance * step_scale;
+        if t >= far {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn bounds(half_size: f32) -> AaBb {
+        AaBb { min: vec3(-half_size, -half_size, -half_size), max: vec3(half_size, half_size, half_size) }
+    }
+
+    #[test]
+    fn ray_from_outside_hits_sphere() {
+        let sdf = Sdf::Sphere { radius: 1.0 };
+        let ray = Ray { origin: vec3(0.0, 0.0, -5.0), direction: vec3(0.0, 0.0, 1.0) };
+        let hit_info = test_ray_sdf_intersection(&sdf, &bounds(1.5), &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 4.0).abs() < 1e-3);
+        assert!((hit_info.n - vec3(0.0, 0.0, -1.0)).magnitude() < 1e-3);
+        // the surface is only searched for within bounds
+        assert!(test_ray_sdf_intersection(&sdf, &bounds(0.5), &ray, 0.0, 100.0, &MATERIAL).is_none());
+    }
+
+    #[test]
+    fn ray_from_inside_leaves_box() {
+        let sdf = Sdf::Box { half_sizes: vec3(1.0, 1.0, 1.0) };
+        let ray = Ray { origin: vec3(0.0, 0.0, 0.0), direction: vec3(0.0, 2.0, 0.0) };
+        let hit_info = test_ray_sdf_intersection(&sdf, &bounds(1.5), &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.t - 0.5).abs() < 1e-3);
+        assert!((hit_info.n - vec3(0.0, 1.0, 0.0)).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn ray_hits_mandelbulb() {
+        let sdf = Sdf::Mandelbulb { power: 8.0, iterations: 8 };
+        let ray = Ray { origin: vec3(0.1, 0.2, -5.0), direction: vec3(0.0, 0.0, 1.0) };
+        let hit_info = test_ray_sdf_intersection(&sdf, &bounds(1.5), &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        // the bulb fits into a sphere of radius about 1.2
+        assert!(hit_info.t > 3.5 && hit_info.t < 5.0);
+        assert!(sdf.distance(hit_info.p).abs() < SURFACE_DISTANCE);
+        assert!(hit_info.n.dot(ray.direction) < 0.0);
+    }
+}
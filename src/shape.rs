@@ -1,131 +1,190 @@
 use{
     cgmath::{
-        Vector2,
         Vector3,
+        Vector4,
         InnerSpace,
-        Quaternion,
-        vec2
+        vec2,
+        vec3
     },
     crate::ray::{HitTestable, HitInfo, Ray},
-    crate::material::Material
+    crate::float_utils::{abs, gamma},
+    crate::material::{Albedo, Material},
+    crate::mesh_data::{MeshDescription, Shading, VertexDescription},
+    crate::transform::Transform,
+    crate::world::World,
+    crate::vector_utils::orthonormal_basis,
+    crate::quadrics::{test_ray_capsule_intersection, test_ray_cone_intersection},
+    crate::torus::test_ray_torus_intersection,
+    crate::quartic::{Monomial, test_ray_quartic_intersection},
+    crate::csg::{CsgOperation, test_ray_csg_intersection},
+    crate::sdf::{Sdf, test_ray_sdf_intersection},
+    crate::voxel::{VoxelGrid, test_ray_voxels_intersection},
+    crate::heightfield::{Heightfield, test_ray_heightfield_intersection},
+    crate::metaballs::{Metaball, test_ray_metaballs_intersection}
 };
 use crate::aabb::AaBb;
-
-#[derive(Copy, Clone)]
-pub struct VertexDescription {
-    pub position: Vector3<f32>,
-    pub normal: Vector3<f32>,
-    pub uv: Vector2<f32>
-}
-
-pub enum FaceOctTree {
-    Node {
-        aabb: AaBb,
-        children: Vec<FaceOctTree>
-    },
-    Leaf {
-        aabb: AaBb,
-        face_indices: Vec<usize>
-    }
-}
-
-impl FaceOctTree {
-    pub fn fill(&mut self, v0: &Vector3<f32>, v1: &Vector3<f32>, v2: &Vector3<f32>, face_id: usize) {
-        match self {
-            FaceOctTree::Node { aabb, children } => {
-                if !(aabb.is_point_inside(v0) || aabb.is_point_inside(v1) || aabb.is_point_inside(v2)) {
-                    return;
-                }
-                for child in children {
-                    child.fill(v0, v1, v2, face_id)
-                }
-            },
-            FaceOctTree::Leaf { aabb, face_indices } => {
-                if !(aabb.is_point_inside(v0) || aabb.is_point_inside(v1) || aabb.is_point_inside(v2)) {
-                    return;
-                }
-                face_indices.push(face_id)
-            },
-        }
-    }
-    pub fn make(initial_aabb: AaBb, depth_levels: u8) -> Self {
-        if depth_levels == 0 {
-            FaceOctTree::Leaf {
-                aabb: initial_aabb,
-                face_indices: Vec::new()
-            }
-        } else {
-            let [bb0, bb1, bb2, bb3, bb4, bb5, bb6, bb7] = initial_aabb.slice_octal();
-            let next_depth_levels = depth_levels - 1;
-            FaceOctTree::Node {
-                aabb: initial_aabb,
-                children: vec![
-                    FaceOctTree::make(bb0, next_depth_levels),
-                    FaceOctTree::make(bb1, next_depth_levels),
-                    FaceOctTree::make(bb2, next_depth_levels),
-                    FaceOctTree::make(bb3, next_depth_levels),
-                    FaceOctTree::make(bb4, next_depth_levels),
-                    FaceOctTree::make(bb5, next_depth_levels),
-                    FaceOctTree::make(bb6, next_depth_levels),
-                    FaceOctTree::make(bb7, next_depth_levels)
-                ]
-            }
-        }
-    }
-    pub fn hit_test<F: FnMut(usize) -> ()>(&self, ray: &Ray, f: &mut F) {
-        match self {
-            FaceOctTree::Leaf { aabb, face_indices } => {
-                if !aabb.is_hit(ray) {
-                    return;
-                }
-                for id in face_indices {
-                    f(*id);
-                }
-            },
-            FaceOctTree::Node { aabb, children } => {
-                if !aabb.is_hit(ray) {
-                    return;
-                }
-                for child in children {
-                    child.hit_test(ray, f);
-                }
-            },
-        }
+use crate::bvh::Bvh;
+use std::f32::consts::PI;
+
+impl MeshDescription<'_> {
+    /// Builds bvh over faces of the mesh in its local space, so it doesn't depend on where
+    /// the mesh is placed
+    pub fn build_bvh(&self) -> Bvh {
+        let faces = (0..self.triangle_count)
+            .filter_map(|face_id| self.get_triangle(face_id).and_then(|[v0, v1, v2]| {
+                AaBb::from_points(&[v0.position, v1.position, v2.position])
+                    .map(|aabb| (face_id, aabb))
+            }))
+            .collect::<Vec<_>>();
+        Bvh::build(&faces)
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct MeshDescription<'a> {
-    pub vertices: &'a [VertexDescription],
-    pub indices: &'a [usize],
-    pub triangle_count: usize
-}
-
+/// Every shape is defined in its local space, where spheres, disks and cubes are centered
+/// at the origin (disks are facing +y), and is placed to the world by its transform
 #[derive(Copy, Clone)]
 pub enum Shape<'a> {
     Sphere {
-        center: Vector3<f32>,
         radius: f32,
-        rotation: Quaternion<f32>,
+        transform: Transform,
         material: Material<'a>
     },
     Disk {
-        center: Vector3<f32>,
         radius: f32,
-        rotation: Quaternion<f32>,
+        transform: Transform,
         material: Material<'a>
     },
     Cube {
-        center: Vector3<f32>,
         sizes: Vector3<f32>,
-        rotation: Quaternion<f32>,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Ring between two circles, facing +y like a disk
+    Annulus {
+        inner_radius: f32,
+        outer_radius: f32,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Cylinders, cones and capsules are centered at the origin with their axis along y
+    Cylinder {
+        radius: f32,
+        height: f32,
+        capped: bool,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Cone truncated by planes at given radii, a whole one has zero top radius
+    Cone {
+        bottom_radius: f32,
+        top_radius: f32,
+        height: f32,
+        capped: bool,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Height is the one of its cylinder, which is between hemispheres
+    Capsule {
+        radius: f32,
+        height: f32,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Tube of minor radius around a circle of major radius in the xz plane
+    Torus {
+        major_radius: f32,
+        minor_radius: f32,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Implicit surface where the sum of terms of degree up to four is zero, see Monomial.
+    /// Like distance fields it is only searched for within bounds
+    Quartic {
+        terms: &'a [Monomial],
+        bounds: AaBb,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Infinite plane through the origin facing +y. Uvs are coordinates along x and z, so
+    /// checkers keep their size in world units as long as the plane isn't scaled
+    Plane {
+        two_sided: bool,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Parallelogram spanned by two edges going from its corner, which is a rectangle when
+    /// edges are perpendicular. It faces along cross(edge_u, edge_v)
+    Quad {
+        corner: Vector3<f32>,
+        edge_u: Vector3<f32>,
+        edge_v: Vector3<f32>,
+        two_sided: bool,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Hit from both sides unlike planes and quads, which have to be asked for it
+    Triangle {
+        vertices: [VertexDescription; 3],
+        transform: Transform,
         material: Material<'a>
     },
     TriangleMesh {
-        center: Vector3<f32>,
+        transform: Transform,
         mesh: MeshDescription<'a>,
         material: Material<'a>,
-        face_oct_tree: Option<&'a FaceOctTree>
+        bvh: Option<&'a Bvh>
+    },
+    /// Places another shape once more without copying it, so meshes with their bvh could be
+    /// shared by any number of instances. Transform of the instance is applied on top of
+    /// the transform of the shape
+    Instance {
+        transform: Transform,
+        shape: &'a Shape<'a>,
+        material: Option<Material<'a>>
+    },
+    /// Surface where the signed distance function is zero, it is only searched for within
+    /// bounds, which should enclose the surface
+    DistanceField {
+        sdf: &'a Sdf<'a>,
+        bounds: AaBb,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Blobby surface where the summed field of sources is threshold, it is empty when
+    /// threshold is above weights of all of them
+    Metaballs {
+        sources: &'a [Metaball],
+        threshold: f32,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Terrain traced through the min/max hierarchy of its heights, see Heightfield
+    Heightfield {
+        field: &'a Heightfield,
+        transform: Transform,
+        material: Material<'a>
+    },
+    /// Grid of unit voxels centered at the origin, an occupied voxel takes the material
+    /// at its value minus one (or magenta one if there is no such material)
+    Voxels {
+        grid: &'a VoxelGrid,
+        materials: &'a [Material<'a>],
+        transform: Transform
+    },
+    /// Solid combined from two closed shapes, which are placed by their own transforms
+    /// on top of the transform of the node. Material replaces the ones of both shapes if set
+    Csg {
+        operation: CsgOperation,
+        left: &'a Shape<'a>,
+        right: &'a Shape<'a>,
+        transform: Transform,
+        material: Option<Material<'a>>
+    },
+    /// Places a whole world (with its own bvh) as a single shape, which makes possible
+    /// to instance assemblies of shapes built by scene graph
+    Group {
+        transform: Transform,
+        world: &'a World<'a>
     }
 }
 
@@ -133,6 +192,8 @@ fn test_ray_plane_intersection<'a>(
     center: &Vector3<f32>,
     normal: &Vector3<f32>,
     ray: &Ray,
+    t_min: f32,
+    t_max: f32,
     material: &Material<'a>
 ) -> Option<HitInfo<'a>>
 {
@@ -144,115 +205,357 @@ fn test_ray_plane_intersection<'a>(
     } else {
         let oc = ray.origin - center;
         let t = -normal.dot(oc) / ray_normal_proj;
-        if t <= 0.0001 {
+        if t <= t_min || t >= t_max {
             None
         } else {
             let p = ray.get_point_at(t);
+            // snapping the point back to the plane makes its error depend only on magnitudes
+            // of coordinates and not on how far the ray has travelled
+            let p = p - normal * normal.dot(p - center);
+            let p_error = (abs(p) + abs(*center)) * gamma(7);
             let n = *normal;
             let material = *material;
-            Some(HitInfo{ t, p, n, material, uv: None })
+            let (dpdu, dpdv) = orthonormal_basis(n);
+            Some(HitInfo{ t, p, n, ng: n, p_error, material, uv: None, dpdu, dpdv })
         }
     }
 }
 
-impl HitTestable for Shape<'_> {
-    fn hit_test(&self, ray: &Ray) -> Option<HitInfo> {
+/// Plane test which hits back faces too when the plane is two sided, normals of such hits
+/// face the ray
+fn test_ray_sided_plane_intersection<'a>(
+    center: &Vector3<f32>,
+    normal: &Vector3<f32>,
+    two_sided: bool,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    test_ray_plane_intersection(center, normal, ray, t_min, t_max, material).or_else(|| if two_sided {
+        test_ray_plane_intersection(center, &-*normal, ray, t_min, t_max, material)
+    } else {
+        None
+    })
+}
+
+fn permute(v: Vector3<f32>, x: usize, y: usize, z: usize) -> Vector3<f32> {
+    vec3(v[x], v[y], v[z])
+}
+
+fn max_component(v: Vector3<f32>) -> f32 {
+    v.x.max(v.y).max(v.z)
+}
+
+// "Watertight Ray/Triangle Intersection" by Woop, Benthin and Wald. Triangle is moved to a space
+// where the ray goes along +z from the origin, so edge tests become 2D and edges shared by
+// neighbouring triangles are tested exactly the same way, which leaves no gaps between them.
+// Triangles are hit from both sides, since rays refracted into closed meshes and csg have
+// to find back faces. Normals keep facing the way the winding (or vertex normals) say, so
+// materials turn them to the ray when they scatter it
+pub fn test_ray_triangle_intersection<'a>(
+    triangle: &[VertexDescription; 3],
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>,
+    shading: Shading,
+    tangents: Option<[Vector4<f32>; 3]>
+) -> Option<HitInfo<'a>>
+{
+    let [vertex_0, vertex_1, vertex_2] = triangle;
+
+    let d_abs = abs(ray.direction);
+    let kz = if d_abs.x > d_abs.y && d_abs.x > d_abs.z {
+        0
+    } else if d_abs.y > d_abs.z {
+        1
+    } else {
+        2
+    };
+    let (kx, ky) = ((kz + 1) % 3, (kz + 2) % 3);
+    let d = permute(ray.direction, kx, ky, kz);
+    let mut p0t = permute(vertex_0.position - ray.origin, kx, ky, kz);
+    let mut p1t = permute(vertex_1.position - ray.origin, kx, ky, kz);
+    let mut p2t = permute(vertex_2.position - ray.origin, kx, ky, kz);
+
+    let (sx, sy, sz) = (-d.x / d.z, -d.y / d.z, 1.0 / d.z);
+    for pt in [&mut p0t, &mut p1t, &mut p2t].iter_mut() {
+        pt.x += sx * pt.z;
+        pt.y += sy * pt.z;
+    }
+
+    let mut e0 = p1t.x * p2t.y - p1t.y * p2t.x;
+    let mut e1 = p2t.x * p0t.y - p2t.y * p0t.x;
+    let mut e2 = p0t.x * p1t.y - p0t.y * p1t.x;
+    // an edge exactly through the ray is decided in double precision
+    if e0 == 0.0 || e1 == 0.0 || e2 == 0.0 {
+        e0 = (p1t.x as f64 * p2t.y as f64 - p1t.y as f64 * p2t.x as f64) as f32;
+        e1 = (p2t.x as f64 * p0t.y as f64 - p2t.y as f64 * p0t.x as f64) as f32;
+        e2 = (p0t.x as f64 * p1t.y as f64 - p0t.y as f64 * p1t.x as f64) as f32;
+    }
+    if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+        return None;
+    }
+    let det = e0 + e1 + e2;
+    if det == 0.0 {
+        return None;
+    }
+
+    p0t.z *= sz;
+    p1t.z *= sz;
+    p2t.z *= sz;
+    let inv_det = 1.0 / det;
+    let t = (e0 * p0t.z + e1 * p1t.z + e2 * p2t.z) * inv_det;
+
+    // conservative bound of the error of t, so hits which could actually be behind
+    // the ray origin are not reported
+    let max_zt = max_component(abs(vec3(p0t.z, p1t.z, p2t.z)));
+    let max_xt = max_component(abs(vec3(p0t.x, p1t.x, p2t.x)));
+    let max_yt = max_component(abs(vec3(p0t.y, p1t.y, p2t.y)));
+    let delta_z = gamma(3) * max_zt;
+    let delta_x = gamma(5) * (max_xt + max_zt);
+    let delta_y = gamma(5) * (max_yt + max_zt);
+    let delta_e = 2.0 * (gamma(2) * max_xt * max_yt + delta_y * max_xt + delta_x * max_yt);
+    let max_e = max_component(abs(vec3(e0, e1, e2)));
+    let delta_t = 3.0 * (gamma(3) * max_e * max_zt + delta_e * max_zt + delta_z * max_e) * inv_det.abs();
+    if t <= t_min.max(delta_t) || t >= t_max {
+        return None;
+    }
+
+    let (b0, b1, b2) = (e0 * inv_det, e1 * inv_det, e2 * inv_det);
+
+    let p0 = vertex_0.position * b0;
+    let p1 = vertex_1.position * b1;
+    let p2 = vertex_2.position * b2;
+    let p = p0 + p1 + p2;
+    let p_error = (abs(p0) + abs(p1) + abs(p2)) * gamma(7);
+
+    let ng = (vertex_0.position - vertex_2.position)
+        .cross(vertex_1.position - vertex_2.position)
+        .normalize();
+    let n = match shading {
+        Shading::Flat => ng,
+        Shading::Smooth => {
+            let n =
+                vertex_0.normal * b0 +
+                vertex_1.normal * b1 +
+                vertex_2.normal * b2;
+            if n.magnitude2() > 0.0 { n.normalize() } else { ng }
+        }
+    };
+    // geometric normal looks to the same side as the shading one
+    let ng = if ng.dot(n) < 0.0 { -ng } else { ng };
+
+    let uv =
+        vertex_0.uv * b0 +
+        vertex_1.uv * b1 +
+        vertex_2.uv * b2;
+
+    let (duv02, duv12) = (vertex_0.uv - vertex_2.uv, vertex_1.uv - vertex_2.uv);
+    let (dp02, dp12) = (vertex_0.position - vertex_2.position, vertex_1.position - vertex_2.position);
+    let uv_det = duv02.x * duv12.y - duv02.y * duv12.x;
+    let (dpdu, dpdv) = match tangents {
+        // tangents of vertices are interpolated like normals are, and the bitangent
+        // is restored from the tangent the same way MikkTSpace does it
+        Some([t0, t1, t2]) => {
+            let t = (t0 * b0 + t1 * b1 + t2 * b2).truncate();
+            (t, n.cross(t) * t0.w)
+        },
+        None if uv_det.abs() > 1e-12 => (
+            (dp02 * duv12.y - dp12 * duv02.y) / uv_det,
+            (dp12 * duv02.x - dp02 * duv12.x) / uv_det
+        ),
+        None => orthonormal_basis(n)
+    };
+
+    Some(HitInfo { t, p, n, ng, p_error, material: *material, uv: Some(uv), dpdu, dpdv })
+}
+
+/// Hit with vertex colors of the triangle interpolated at its point, which take place of
+/// the albedo of the material
+fn with_vertex_colors<'a>(hit_info: HitInfo<'a>, triangle: &[VertexDescription; 3], colors: [Vector3<f32>; 3]) -> HitInfo<'a> {
+    let (e1, e2) = (triangle[1].position - triangle[0].position, triangle[2].position - triangle[0].position);
+    let d = hit_info.p - triangle[0].position;
+    let (d11, d12, d22) = (e1.dot(e1), e1.dot(e2), e2.dot(e2));
+    let (d1, d2) = (d.dot(e1), d.dot(e2));
+    let det = d11 * d22 - d12 * d12;
+    let (b1, b2) = if det > 0.0 {
+        ((d22 * d1 - d12 * d2) / det, (d11 * d2 - d12 * d1) / det)
+    } else {
+        (0.0, 0.0)
+    };
+    let color = colors[0] * (1.0 - b1 - b2) + colors[1] * b1 + colors[2] * b2;
+    HitInfo { material: Material { albedo: Albedo::Constant(color), ..hit_info.material }, ..hit_info }
+}
+
+impl<'a> Shape<'a> {
+    pub fn transform(&self) -> &Transform {
         match self {
-            Shape::Sphere { center, radius, rotation, material } => {
+            Shape::Sphere { transform, .. } |
+            Shape::Disk { transform, .. } |
+            Shape::Cube { transform, .. } |
+            Shape::Annulus { transform, .. } |
+            Shape::Cylinder { transform, .. } |
+            Shape::Cone { transform, .. } |
+            Shape::Capsule { transform, .. } |
+            Shape::Torus { transform, .. } |
+            Shape::Quartic { transform, .. } |
+            Shape::Plane { transform, .. } |
+            Shape::Quad { transform, .. } |
+            Shape::Triangle { transform, .. } |
+            Shape::TriangleMesh { transform, .. } |
+            Shape::Instance { transform, .. } |
+            Shape::DistanceField { transform, .. } |
+            Shape::Metaballs { transform, .. } |
+            Shape::Heightfield { transform, .. } |
+            Shape::Voxels { transform, .. } |
+            Shape::Csg { transform, .. } |
+            Shape::Group { transform, .. } => transform
+        }
+    }
+
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        match self {
+            Shape::Sphere { transform, .. } |
+            Shape::Disk { transform, .. } |
+            Shape::Cube { transform, .. } |
+            Shape::Annulus { transform, .. } |
+            Shape::Cylinder { transform, .. } |
+            Shape::Cone { transform, .. } |
+            Shape::Capsule { transform, .. } |
+            Shape::Torus { transform, .. } |
+            Shape::Quartic { transform, .. } |
+            Shape::Plane { transform, .. } |
+            Shape::Quad { transform, .. } |
+            Shape::Triangle { transform, .. } |
+            Shape::TriangleMesh { transform, .. } |
+            Shape::Instance { transform, .. } |
+            Shape::DistanceField { transform, .. } |
+            Shape::Metaballs { transform, .. } |
+            Shape::Heightfield { transform, .. } |
+            Shape::Voxels { transform, .. } |
+            Shape::Csg { transform, .. } |
+            Shape::Group { transform, .. } => transform
+        }
+    }
+
+    fn hit_test_local(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitInfo<'a>> {
+        match self {
+            Shape::Sphere { radius, material, .. } => {
                 let radius = *radius;
                 let material = *material;
 
-                let i = (rotation * Vector3::unit_x()).normalize();
-                let j = (rotation * Vector3::unit_y()).normalize();
-                let k = (rotation * Vector3::unit_z()).normalize();
-
-                let oc = ray.origin - center;
+                let oc = ray.origin;
                 let a = ray.direction.dot(ray.direction);
-                let b = 2.0 * oc.dot(ray.direction);
+                let half_b = oc.dot(ray.direction);
                 let c = oc.dot(oc) - radius * radius;
-                let discriminant = b * b - 4.0 * a * c;
+                // discriminant is computed through the distance between the center and the ray
+                // line, which doesn't suffer from cancellation when the sphere is small or far
+                let f = oc - ray.direction * (half_b / a);
+                let discriminant = a * (radius * radius - f.dot(f));
                 if discriminant < 0.0 {
                     None
                 } else {
-                    let t = (-b - discriminant.sqrt()) / (2.0 * a);
-                    if t <= 0.0001 {
+                    let q = -(half_b + discriminant.sqrt().copysign(half_b));
+                    let (near_t, far_t) = if q == 0.0 {
+                        (0.0, 0.0)
+                    } else {
+                        let (t0, t1) = (q / a, c / q);
+                        (t0.min(t1), t0.max(t1))
+                    };
+                    // when the near root is out of the interval the ray could still leave
+                    // the sphere through the far one (i.e. when it starts inside the sphere)
+                    let t = if near_t > t_min { near_t } else { far_t };
+                    if t <= t_min || t >= t_max {
                         None
                     } else {
+                        // the hit point is projected back to the sphere, so its error only
+                        // depends on the sphere and not on the ray
                         let p = ray.get_point_at(t);
-                        let n = (p - center) / radius;
-                        let x_proj = i.dot(n);
-                        let y_proj = j.dot(n);
-                        let z_proj = k.dot(n);
-                        let uv = vec2(z_proj.atan2(x_proj).to_degrees() / 360.0 + 0.5, (y_proj + 1.0) * 0.5);
-                        Some(HitInfo{ t, p, n, material, uv: Some(uv) })
+                        let p = p * (radius / p.magnitude());
+                        let p_error = abs(p) * gamma(5);
+                        let n = p / radius;
+                        let uv = vec2(n.z.atan2(n.x).to_degrees() / 360.0 + 0.5, (n.y + 1.0) * 0.5);
+                        // u turns around y and v goes along y, so the frame degenerates at poles
+                        let rho2 = p.x * p.x + p.z * p.z;
+                        let (dpdu, dpdv) = if rho2 > radius * radius * 1e-6 {
+                            (
+                                vec3(-p.z, 0.0, p.x) * (2.0 * PI),
+                                vec3(-p.x * p.y / rho2, 1.0, -p.z * p.y / rho2) * (2.0 * radius)
+                            )
+                        } else {
+                            orthonormal_basis(n)
+                        };
+                        Some(HitInfo{ t, p, n, ng: n, p_error, material, uv: Some(uv), dpdu, dpdv })
                     }
                 }
             },
-            Shape::Disk { center, radius, rotation, material } => {
-                let i = (rotation * Vector3::unit_x()).normalize();
-                let j = (rotation * Vector3::unit_y()).normalize();
-                let k = (rotation * Vector3::unit_z()).normalize();
-                match test_ray_plane_intersection(&center, &j, ray, &material) {
+            Shape::Disk { radius, material, .. } => {
+                let center = vec3(0.0, 0.0, 0.0);
+                match test_ray_plane_intersection(&center, &Vector3::unit_y(), ray, t_min, t_max, material) {
                     None => None,
                     Some(hit_info) => {
-                        let pc = hit_info.p - center;
+                        let pc = hit_info.p;
                         let r = pc.dot(pc) / (radius*radius);
                         if r > 1.0 {
                             None
                         } else {
                             let uv = vec2(
-                                k.dot(pc).atan2(i.dot(pc)).to_degrees() / 360.0 + 0.5,
+                                pc.z.atan2(pc.x).to_degrees() / 360.0 + 0.5,
                                 r
                             );
-                            Some(HitInfo{uv: Some(uv), ..hit_info})
+                            // u turns around the center and v grows with squared distance to it
+                            let (dpdu, dpdv) = if r > 1e-6 {
+                                (vec3(-pc.z, 0.0, pc.x) * (2.0 * PI), pc / (2.0 * r))
+                            } else {
+                                (hit_info.dpdu, hit_info.dpdv)
+                            };
+                            Some(HitInfo{uv: Some(uv), dpdu, dpdv, ..hit_info})
                         }
                     }
                 }
             },
-            Shape::Cube { center, sizes, material, rotation } => {
-                let center = *center;
-                let mut hit_info_maybe = None;
+            Shape::Cube { sizes, material, .. } => {
+                let mut hit_info_maybe: Option<HitInfo> = None;
                 let half_sizes = sizes / 2.0;
 
-                let i = (rotation * Vector3::unit_x()).normalize();
-                let j = (rotation * Vector3::unit_y()).normalize();
-                let k = (rotation * Vector3::unit_z()).normalize();
+                let (i, j, k) = (Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z());
 
                 let Vector3{x: xs, y: ys, z: zs} = half_sizes;
 
                 for (normal, center) in &[
-                    ( i.clone(), center + i.clone() * xs),
-                    (-i.clone(), center - i.clone() * xs),
-                    ( j.clone(), center + j.clone() * ys),
-                    (-j.clone(), center - j.clone() * ys),
-                    ( k.clone(), center + k.clone() * zs),
-                    (-k.clone(), center - k.clone() * zs)
+                    ( i,  i * xs),
+                    (-i, -i * xs),
+                    ( j,  j * ys),
+                    (-j, -j * ys),
+                    ( k,  k * zs),
+                    (-k, -k * zs)
                 ] {
-                    match test_ray_plane_intersection(&center, &normal, &ray, &material) {
+                    let t_max = hit_info_maybe.map_or(t_max, |hit_info| hit_info.t);
+                    let face_hit = test_ray_plane_intersection(center, normal, ray, t_min, t_max, material)
+                        // rays from inside leave the cube through faces looking away from them,
+                        // normals still point outwards so the cube is a closed solid
+                        .or_else(|| test_ray_plane_intersection(center, &-*normal, ray, t_min, t_max, material)
+                            .map(|hit_info| HitInfo{n: *normal, ng: *normal, ..hit_info}));
+                    match face_hit {
                         None => {},
                         Some(hit_info) => {
-                            let old_t = hit_info_maybe.map_or(100000.0, |i : HitInfo| i.t);
-                            if hit_info.t > old_t {
-                                continue;
-                            }
-
                             let diff = hit_info.p - center;
-                            let x_project = diff.dot(i.clone());
-                            let y_project = diff.dot(j.clone());
-                            let z_project = diff.dot(k.clone());
-
-                            let nx_project = normal.dot(i);
-                            let ny_project = normal.dot(j);
-
-                            let uv = if nx_project >= 0.999 || nx_project <= -0.999 {
-                                vec2(y_project / ys + 1.0, z_project / zs + 1.0) * 0.5
-                            } else if ny_project >= 0.999 || ny_project <= -0.999 {
-                                vec2(x_project / xs + 1.0, z_project / zs + 1.0) * 0.5
+                            let x_project = diff.x;
+                            let y_project = diff.y;
+                            let z_project = diff.z;
+
+                            let (uv, dpdu, dpdv) = if normal.x != 0.0 {
+                                (vec2(y_project / ys + 1.0, z_project / zs + 1.0) * 0.5, j * ys * 2.0, k * zs * 2.0)
+                            } else if normal.y != 0.0 {
+                                (vec2(x_project / xs + 1.0, z_project / zs + 1.0) * 0.5, i * xs * 2.0, k * zs * 2.0)
                             } else {
-                                vec2(x_project / xs + 1.0, y_project / ys + 1.0) * 0.5
+                                (vec2(x_project / xs + 1.0, y_project / ys + 1.0) * 0.5, i * xs * 2.0, j * ys * 2.0)
                             };
 
-                            let hit_info = HitInfo{uv: Some(uv), ..hit_info};
+                            let hit_info = HitInfo{uv: Some(uv), dpdu, dpdv, ..hit_info};
 
                             if !(
                                 x_project.abs() > xs ||
@@ -266,202 +569,195 @@ impl HitTestable for Shape<'_> {
                 }
                 hit_info_maybe
             }
-            Shape::TriangleMesh { center, mesh, material, face_oct_tree } => {
-                let mut hit_info_maybe = None;
-                if let Some(face_oct_tree) = *face_oct_tree {
-                    face_oct_tree.hit_test(ray, &mut (|face_id| {
-                        let offset = face_id * 3;
-                        if offset + 3 <= mesh.indices.len() {
-                            let(ix0, ix1, ix2) = (
-                                mesh.indices[offset],
-                                mesh.indices[offset+1],
-                                mesh.indices[offset+2]
-                            );
-
-                            let vertex_0 = VertexDescription{
-                                position: mesh.vertices[ix0].position + center,
-                                ..mesh.vertices[ix0]
-                            };
-                            let vertex_1 = VertexDescription{
-                                position: mesh.vertices[ix1].position + center,
-                                ..mesh.vertices[ix1]
-                            };
-                            let vertex_2 = VertexDescription{
-                                position: mesh.vertices[ix2].position + center,
-                                ..mesh.vertices[ix2]
-                            };
-
-                            let v0 = vertex_1.position - vertex_0.position;
-                            let v1 = vertex_2.position - vertex_0.position;
-                            let n = v0.cross(v1);
-                            let whole_area = n.magnitude();
-                            let n = n.normalize();
-
-                            if let Some(hit_info) = test_ray_plane_intersection(
-                                &(vertex_0.position),
-                                &n,
-                                &ray,
-                                &material
-                            ) {
-                                let old_t = hit_info_maybe.map_or(100000.0, |i : HitInfo| i.t);
-                                if hit_info.t <= old_t {
-                                    let p0p = vertex_0.position - hit_info.p;
-                                    let p1p = vertex_1.position - hit_info.p;
-                                    let p2p = vertex_2.position - hit_info.p;
-
-                                    let p0_area_cross = p1p.cross(p2p);
-                                    let p1_area_cross = p2p.cross(p0p);
-                                    let p2_area_cross = p0p.cross(p1p);
-
-                                    if  p0_area_cross.dot(hit_info.n) > 0.0 &&
-                                        p1_area_cross.dot(hit_info.n) > 0.0 &&
-                                        p2_area_cross.dot(hit_info.n) > 0.0
-                                    {
-                                        let (u, v, w) = (
-                                            p0_area_cross.magnitude() / whole_area,
-                                            p1_area_cross.magnitude() / whole_area,
-                                            p2_area_cross.magnitude() / whole_area
-                                        );
-
-                                        let normal =
-                                            vertex_0.normal * u +
-                                            vertex_1.normal * v +
-                                            vertex_2.normal * w;
-
-                                        let uv =
-                                            vertex_0.uv * u +
-                                            vertex_1.uv * v +
-                                            vertex_2.uv * w;
-
-                                        hit_info_maybe = Some(
-                                            HitInfo {
-                                                n: normal,
-                                                uv: Some(uv),
-                                                ..hit_info
-                                            }
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }));
+            Shape::Annulus { inner_radius, outer_radius, material, .. } => {
+                let center = vec3(0.0, 0.0, 0.0);
+                let hit_info = test_ray_plane_intersection(&center, &Vector3::unit_y(), ray, t_min, t_max, material)?;
+                let pc = hit_info.p;
+                let r = pc.magnitude();
+                if r < *inner_radius || r > *outer_radius {
+                    return None;
                 }
-                else {
-                    let mut offset = 0;
-                    for _ in 0..mesh.triangle_count {
-                        if offset + 3 > mesh.indices.len() {
-                            break;
-                        }
-
-                        let(ix0, ix1, ix2) = (
-                            mesh.indices[offset],
-                            mesh.indices[offset+1],
-                            mesh.indices[offset+2]
-                        );
-
-                        let vertex_0 = VertexDescription{
-                            position: mesh.vertices[ix0].position + center,
-                            ..mesh.vertices[ix0]
-                        };
-                        let vertex_1 = VertexDescription{
-                            position: mesh.vertices[ix1].position + center,
-                            ..mesh.vertices[ix1]
-                        };
-                        let vertex_2 = VertexDescription{
-                            position: mesh.vertices[ix2].position + center,
-                            ..mesh.vertices[ix2]
-                        };
-
-                        let v0 = vertex_1.position - vertex_0.position;
-                        let v1 = vertex_2.position - vertex_0.position;
-                        let n = v0.cross(v1);
-                        let whole_area = n.magnitude();
-                        let n = n.normalize();
-
-                        if let Some(hit_info) = test_ray_plane_intersection(
-                            &(vertex_0.position),
-                            &n,
-                            &ray,
-                            &material
-                        ) {
-                            let old_t = hit_info_maybe.map_or(100000.0, |i : HitInfo| i.t);
-                            if hit_info.t <= old_t {
-                                let p0p = vertex_0.position - hit_info.p;
-                                let p1p = vertex_1.position - hit_info.p;
-                                let p2p = vertex_2.position - hit_info.p;
-
-                                let p0_area_cross = p1p.cross(p2p);
-                                let p1_area_cross = p2p.cross(p0p);
-                                let p2_area_cross = p0p.cross(p1p);
-
-                                if  p0_area_cross.dot(hit_info.n) > 0.0 &&
-                                    p1_area_cross.dot(hit_info.n) > 0.0 &&
-                                    p2_area_cross.dot(hit_info.n) > 0.0
-                                {
-                                    let (u, v, w) = (
-                                        p0_area_cross.magnitude() / whole_area,
-                                        p1_area_cross.magnitude() / whole_area,
-                                        p2_area_cross.magnitude() / whole_area
-                                    );
-
-                                    let normal =
-                                        vertex_0.normal * u +
-                                        vertex_1.normal * v +
-                                        vertex_2.normal * w;
-
-                                    let uv =
-                                        vertex_0.uv * u +
-                                        vertex_1.uv * v +
-                                        vertex_2.uv * w;
-
-                                    hit_info_maybe = Some(
-                                        HitInfo {
-                                            n: normal,
-                                            uv: Some(uv),
-                                            ..hit_info
-                                        }
-                                    );
-                                }
+                // v goes across the ring from its inner edge to the outer one
+                let width = outer_radius - inner_radius;
+                let uv = vec2(pc.z.atan2(pc.x) / (2.0 * PI) + 0.5, (r - inner_radius) / width);
+                let (dpdu, dpdv) = if r > 0.0 {
+                    (vec3(-pc.z, 0.0, pc.x) * (2.0 * PI), pc * (width / r))
+                } else {
+                    (hit_info.dpdu, hit_info.dpdv)
+                };
+                Some(HitInfo{uv: Some(uv), dpdu, dpdv, ..hit_info})
+            },
+            Shape::Cylinder { radius, height, capped, material, .. } => {
+                test_ray_cone_intersection((*radius, *radius), *height, *capped, ray, t_min, t_max, material)
+            },
+            Shape::Cone { bottom_radius, top_radius, height, capped, material, .. } => {
+                test_ray_cone_intersection((*bottom_radius, *top_radius), *height, *capped, ray, t_min, t_max, material)
+            },
+            Shape::Capsule { radius, height, material, .. } => {
+                test_ray_capsule_intersection(*radius, *height, ray, t_min, t_max, material)
+            },
+            Shape::Torus { major_radius, minor_radius, material, .. } => {
+                test_ray_torus_intersection(*major_radius, *minor_radius, ray, t_min, t_max, material)
+            },
+            Shape::Quartic { terms, bounds, material, .. } => {
+                test_ray_quartic_intersection(terms, bounds, ray, t_min, t_max, material)
+            },
+            Shape::Plane { two_sided, material, .. } => {
+                let center = vec3(0.0, 0.0, 0.0);
+                let hit_info = test_ray_sided_plane_intersection(&center, &Vector3::unit_y(), *two_sided, ray, t_min, t_max, material)?;
+                let p = hit_info.p;
+                Some(HitInfo{uv: Some(vec2(p.x, p.z)), dpdu: Vector3::unit_x(), dpdv: Vector3::unit_z(), ..hit_info})
+            },
+            Shape::Quad { corner, edge_u, edge_v, two_sided, material, .. } => {
+                let n = edge_u.cross(*edge_v);
+                if n.magnitude2() == 0.0 {
+                    return None;
+                }
+                let hit_info = test_ray_sided_plane_intersection(corner, &n.normalize(), *two_sided, ray, t_min, t_max, material)?;
+                // coordinates of the hit along edges, found by projecting it to the normal
+                let d = hit_info.p - corner;
+                let w = n / n.magnitude2();
+                let uv = vec2(w.dot(d.cross(*edge_v)), w.dot(edge_u.cross(d)));
+                if uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0 {
+                    None
+                } else {
+                    Some(HitInfo{uv: Some(uv), dpdu: *edge_u, dpdv: *edge_v, ..hit_info})
+                }
+            },
+            Shape::Triangle { vertices, material, .. } => {
+                test_ray_triangle_intersection(vertices, ray, t_min, t_max, material, Shading::Smooth, None)
+            },
+            Shape::TriangleMesh { mesh, material, bvh, .. } => {
+                let mut hit_info_maybe: Option<HitInfo> = None;
+                let mut test_face = |face_id, t_max| {
+                    let hit_info = mesh
+                        .get_triangle(face_id)
+                        .and_then(|triangle| test_ray_triangle_intersection(
+                            &triangle, ray, t_min, t_max, material, mesh.shading, mesh.get_tangents(face_id)
+                        ).map(|hit_info| match mesh.get_colors(face_id) {
+                            Some(colors) => with_vertex_colors(hit_info, &triangle, colors),
+                            None => hit_info
+                        }));
+                    if hit_info.is_some() {
+                        hit_info_maybe = hit_info;
+                    }
+                    hit_info.map(|hit_info| hit_info.t)
+                };
+                match bvh {
+                    Some(bvh) => bvh.traverse(ray, t_min, t_max, test_face),
+                    None => {
+                        let mut t_max = t_max;
+                        for face_id in 0..mesh.triangle_count {
+                            if let Some(t) = test_face(face_id, t_max) {
+                                t_max = t;
                             }
                         }
-                        offset += 3;
                     }
                 }
                 hit_info_maybe
-            }
+            },
+            Shape::Instance { shape, material, .. } => {
+                let hit_info = shape.hit_test(ray, t_min, t_max)?;
+                Some(HitInfo { material: material.unwrap_or(hit_info.material), ..hit_info })
+            },
+            Shape::DistanceField { sdf, bounds, material, .. } => {
+                test_ray_sdf_intersection(sdf, bounds, ray, t_min, t_max, material)
+            },
+            Shape::Metaballs { sources, threshold, material, .. } => {
+                test_ray_metaballs_intersection(sources, *threshold, ray, t_min, t_max, material)
+            },
+            Shape::Heightfield { field, material, .. } => {
+                test_ray_heightfield_intersection(field, ray, t_min, t_max, material)
+            },
+            Shape::Voxels { grid, materials, .. } => {
+                test_ray_voxels_intersection(grid, materials, ray, t_min, t_max)
+            },
+            Shape::Csg { operation, left, right, material, .. } => {
+                let hit_info = test_ray_csg_intersection(*operation, left, right, ray, t_min, t_max)?;
+                Some(HitInfo { material: material.unwrap_or(hit_info.material), ..hit_info })
+            },
+            Shape::Group { world, .. } => world.hit_test(ray, t_min, t_max)
         }
     }
 }
 
-impl<'a> Shape<'a> {
-    pub fn extend_with_oct_tree(&'a self, oct_tree: &'a mut FaceOctTree) -> Self {
-        if let Shape::TriangleMesh { center, mesh, material, .. } = self {
-            let mut offset = 0;
-            for face_id in 0..mesh.triangle_count {
-                if offset + 3 > mesh.indices.len() {
-                    break;
-                }
-                let ix0 = mesh.indices[offset];
-                let ix1 = mesh.indices[offset+1];
-                let ix2 = mesh.indices[offset+2];
+impl HitTestable for Shape<'_> {
+    fn hit_test(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitInfo<'_>> {
+        let transform = self.transform();
+        self.hit_test_local(&transform.inverse_ray(ray), t_min, t_max)
+            .map(|hit_info| transform.hit_info(hit_info))
+    }
 
-                let (v0, v1, v2) = (
-                    mesh.vertices[ix0].position + center,
-                    mesh.vertices[ix1].position + center,
-                    mesh.vertices[ix2].position + center
-                );
+    fn occluded(&self, ray: &Ray, t_max: f32) -> bool {
+        match self {
+            Shape::TriangleMesh { transform, mesh, material, bvh: Some(bvh) } => {
+                let local_ray = transform.inverse_ray(ray);
+                bvh.any(&local_ray, 0.0, t_max, |face_id| {
+                    mesh.get_triangle(face_id)
+                        .and_then(|triangle| test_ray_triangle_intersection(
+                            &triangle, &local_ray, 0.0, t_max, material, mesh.shading, None
+                        ))
+                        .is_some()
+                })
+            },
+            Shape::Instance { transform, shape, .. } => shape.occluded(&transform.inverse_ray(ray), t_max),
+            Shape::Group { transform, world } => world.occluded(&transform.inverse_ray(ray), t_max),
+            _ => self.hit_test(ray, 0.0, t_max).is_some()
+        }
+    }
+}
 
-                oct_tree.fill(&v0, &v1, &v2, face_id);
-            }
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    #[test]
+    fn annulus_is_hit_between_its_radii() {
+        let annulus = Shape::Annulus { inner_radius: 0.5, outer_radius: 1.0, transform: Transform::identity(), material: MATERIAL };
+        let ray_at = |x: f32| Ray { origin: vec3(x, 5.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        let hit_info = annulus.hit_test(&ray_at(0.75), 0.0, 100.0).unwrap();
+        assert!((hit_info.t - 5.0).abs() < 1e-4);
+        assert!((hit_info.n - vec3(0.0, 1.0, 0.0)).magnitude() < 1e-4);
+        assert!((hit_info.uv.unwrap().y - 0.5).abs() < 1e-4);
+        assert!(annulus.hit_test(&ray_at(0.25), 0.0, 100.0).is_none());
+        assert!(annulus.hit_test(&ray_at(1.25), 0.0, 100.0).is_none());
+    }
 
-            Shape::TriangleMesh {
-                center: *center,
-                mesh: *mesh,
-                material: *material,
-                face_oct_tree: Some(oct_tree)
-            }
-        } else {
-            *self
+    #[test]
+    fn triangle_is_hit_from_both_sides() {
+        let vertex = |x, y| VertexDescription { position: vec3(x, y, 0.0), normal: vec3(0.0, 0.0, 1.0), uv: vec2(x, y) };
+        let triangle = [vertex(0.0, 0.0), vertex(1.0, 0.0), vertex(0.0, 1.0)];
+        for &z in [-1.0f32, 1.0].iter() {
+            let ray = Ray { origin: vec3(0.25, 0.25, z), direction: vec3(0.0, 0.0, -z) };
+            let hit_info = test_ray_triangle_intersection(&triangle, &ray, 0.0, 100.0, &MATERIAL, Shading::Smooth, None).unwrap();
+            assert!((hit_info.t - 1.0).abs() < 1e-6);
+            // the normal isn't turned to the ray
+            assert!((hit_info.ng - vec3(0.0, 0.0, 1.0)).magnitude() < 1e-6);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn vertex_colors_are_interpolated_into_albedo() {
+        let vertex = |x, y| VertexDescription { position: vec3(x, y, 0.0), normal: vec3(0.0, 0.0, 1.0), uv: vec2(0.0, 0.0) };
+        let vertices = [vertex(0.0, 0.0), vertex(1.0, 0.0), vertex(0.0, 1.0)];
+        let colors = [vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)];
+        let mesh = MeshDescription { vertices: &vertices, indices: &[0, 1, 2], triangle_count: 1, shading: Shading::Smooth, tangents: &[], colors: &colors };
+        let shape = Shape::TriangleMesh { transform: Transform::identity(), mesh, material: MATERIAL, bvh: None };
+        let ray = Ray { origin: vec3(0.25, 0.5, 1.0), direction: vec3(0.0, 0.0, -1.0) };
+        let hit_info = shape.hit_test(&ray, 0.0, 100.0).unwrap();
+        match hit_info.material.albedo {
+            Albedo::Constant(color) => assert!((color - vec3(0.25, 0.25, 0.5)).magnitude() < 1e-5),
+            _ => panic!("albedo of a colored mesh should be the color of the hit")
+        }
+    }
+}
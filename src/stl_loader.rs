@@ -0,0 +1,249 @@
+use {
+    cgmath::{Vector3, vec2, vec3},
+    std::{
+        collections::HashMap,
+        fmt,
+        fs,
+        io,
+        path::Path
+    },
+    crate::{
+        mesh_data::{DEFAULT_CREASE_ANGLE, MeshData, VertexDescription, position_key, triangulate}
+    }
+};
+
+const BINARY_HEADER_SIZE: usize = 80;
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+#[derive(Debug)]
+pub enum StlError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Truncated
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StlError::Io(e) => write!(f, "{}", e),
+            StlError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            StlError::Truncated => write!(f, "file is truncated")
+        }
+    }
+}
+
+impl From<io::Error> for StlError {
+    fn from(e: io::Error) -> Self {
+        StlError::Io(e)
+    }
+}
+
+/// Merges vertices sharing a position, since stl stores every triangle on its own.
+/// Normals are generated afterwards with sharp edges kept sharp
+struct Welder {
+    mesh: MeshData,
+    vertex_ids: HashMap<[u32; 3], usize>
+}
+
+impl Welder {
+    fn vertex(&mut self, position: Vector3<f32>) -> usize {
+        let mesh = &mut self.mesh;
+        *self.vertex_ids.entry(position_key(position)).or_insert_with(|| {
+            mesh.vertices.push(VertexDescription {
+                position,
+                normal: vec3(0.0, 0.0, 0.0),
+                uv: vec2(0.0, 0.0)
+            });
+            mesh.vertices.len() - 1
+        })
+    }
+
+    fn polygon(&mut self, positions: &[Vector3<f32>]) {
+        let ids = positions.iter().map(|p| self.vertex(*p)).collect::<Vec<_>>();
+        for [a, b, c] in triangulate(positions) {
+            let (a, b, c) = (ids[a], ids[b], ids[c]);
+            // welding collapses triangles which were too thin to have distinct vertices
+            if a != b && b != c && c != a {
+                self.mesh.indices.extend_from_slice(&[a, b, c]);
+            }
+        }
+    }
+
+    fn finish(self) -> MeshData {
+        let mut mesh = self.mesh;
+        mesh.generate_creased_normals(DEFAULT_CREASE_ANGLE);
+        mesh
+    }
+}
+
+pub fn load_stl(path: &Path) -> Result<MeshData, StlError> {
+    parse_stl(&fs::read(path)?)
+}
+
+/// Reads either ascii or binary stl. Binary files may start with "solid" as well,
+/// so a file is considered binary when its size agrees with the triangle count in it
+pub fn parse_stl(bytes: &[u8]) -> Result<MeshData, StlError> {
+    let mut welder = Welder { mesh: MeshData::default(), vertex_ids: HashMap::new() };
+    if is_binary(bytes) {
+        parse_binary(bytes, &mut welder)?;
+    } else {
+        parse_ascii(bytes, &mut welder)?;
+    }
+    Ok(welder.finish())
+}
+
+fn triangle_count(bytes: &[u8]) -> Option<usize> {
+    let count = bytes.get(BINARY_HEADER_SIZE..BINARY_HEADER_SIZE + 4)?;
+    Some(u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize)
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    let sized_right = triangle_count(bytes)
+        .is_some_and(|count| bytes.len() == BINARY_HEADER_SIZE + 4 + count * BINARY_TRIANGLE_SIZE);
+    sized_right || !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+fn parse_binary(bytes: &[u8], welder: &mut Welder) -> Result<(), StlError> {
+    let count = triangle_count(bytes).ok_or(StlError::Truncated)?;
+    let triangles = &bytes[BINARY_HEADER_SIZE + 4..];
+    if triangles.len() < count * BINARY_TRIANGLE_SIZE {
+        return Err(StlError::Truncated);
+    }
+    let read_f32 = |b: &[u8], offset: usize| f32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]]);
+    let read_vector = |b: &[u8], offset: usize| vec3(read_f32(b, offset), read_f32(b, offset + 4), read_f32(b, offset + 8));
+    for triangle in triangles.chunks_exact(BINARY_TRIANGLE_SIZE).take(count) {
+        // facet normal goes first, it is ignored since normals are generated after welding
+        welder.polygon(&[read_vector(triangle, 12), read_vector(triangle, 24), read_vector(triangle, 36)]);
+    }
+    Ok(())
+}
+
+fn parse_ascii(bytes: &[u8], welder: &mut Welder) -> Result<(), StlError> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut facet: Option<Vec<Vector3<f32>>> = None;
+    for (line_id, line) in text.lines().enumerate() {
+        let line_number = line_id + 1;
+        let error = |message: &str| StlError::Parse { line: line_number, message: message.to_string() };
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("facet") => {
+                if facet.is_some() {
+                    return Err(error("facet inside of another facet"));
+                }
+                facet = Some(Vec::new());
+            },
+            Some("vertex") => {
+                let vertices = facet.as_mut().ok_or_else(|| error("vertex outside of a facet"))?;
+                let coords = tokens
+                    .map(|t| t.parse::<f32>().map_err(|_| error(&format!("bad number {}", t))))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if coords.len() != 3 {
+                    return Err(error("vertex should have 3 coordinates"));
+                }
+                vertices.push(vec3(coords[0], coords[1], coords[2]));
+            },
+            Some("endfacet") => {
+                let vertices = facet.take().ok_or_else(|| error("endfacet without a facet"))?;
+                if vertices.len() < 3 {
+                    return Err(error("facet has less than 3 vertices"));
+                }
+                welder.polygon(&vertices);
+            },
+            Some("solid") | Some("endsolid") | Some("outer") | Some("endloop") | None => {},
+            Some(keyword) => return Err(error(&format!("unexpected {}", keyword)))
+        }
+    }
+    if facet.is_some() {
+        return Err(StlError::Truncated);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a unit square made of two triangles sharing the diagonal
+    const SQUARE: [[[f32; 3]; 3]; 2] = [
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+        [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]
+    ];
+
+    fn ascii(triangles: &[[[f32; 3]; 3]]) -> String {
+        let mut text = "solid square\n".to_string();
+        for triangle in triangles {
+            text += "facet normal 0 0 1\nouter loop\n";
+            for [x, y, z] in triangle {
+                text += &format!("vertex {} {} {}\n", x, y, z);
+            }
+            text += "endloop\nendfacet\n";
+        }
+        text + "endsolid square\n"
+    }
+
+    fn binary(header: &[u8], triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut bytes = header.to_vec();
+        bytes.resize(BINARY_HEADER_SIZE, b' ');
+        bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            let values = [0.0f32, 0.0, 1.0].iter().chain(triangle.iter().flatten()).copied().collect::<Vec<_>>();
+            for value in values {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    fn assert_square(mesh: &MeshData) {
+        // corners of the diagonal are shared by both triangles
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices.len(), 6);
+        for vertex in &mesh.vertices {
+            assert_eq!(vertex.normal, vec3(0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn reads_ascii() {
+        assert_square(&parse_stl(ascii(&SQUARE).as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn reads_binary() {
+        assert_square(&parse_stl(&binary(b"square", &SQUARE)).unwrap());
+    }
+
+    #[test]
+    fn binary_may_start_with_solid() {
+        let bytes = binary(b"solid square exported as binary", &SQUARE);
+        assert!(is_binary(&bytes));
+        assert_square(&parse_stl(&bytes).unwrap());
+        assert!(!is_binary(ascii(&SQUARE).as_bytes()));
+    }
+
+    #[test]
+    fn drops_triangles_collapsed_by_welding() {
+        // -0.0 and 0.0 are the same position, so the second triangle has two equal corners
+        let collapsed = [[0.0, 0.0, 0.0], [-0.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let mesh = parse_stl(ascii(&[SQUARE[0], SQUARE[1], collapsed]).as_bytes()).unwrap();
+        assert_square(&mesh);
+    }
+
+    #[test]
+    fn reports_truncated_files() {
+        let bytes = binary(b"square", &SQUARE);
+        assert!(matches!(parse_stl(&bytes[..bytes.len() - 1]), Err(StlError::Truncated)));
+        let text = ascii(&SQUARE);
+        let unfinished = &text[..text.find("endfacet").unwrap()];
+        assert!(matches!(parse_stl(unfinished.as_bytes()), Err(StlError::Truncated)));
+    }
+
+    #[test]
+    fn reports_line_of_parse_errors() {
+        let text = ascii(&SQUARE).replacen("vertex 1 0 0", "vertex 1 zero 0", 1);
+        assert!(matches!(parse_stl(text.as_bytes()), Err(StlError::Parse { line: 5, .. })));
+        let text = ascii(&SQUARE).replacen("vertex 1 0 0", "vertex 1 0", 1);
+        assert!(matches!(parse_stl(text.as_bytes()), Err(StlError::Parse { line: 5, .. })));
+    }
+}
@@ -0,0 +1,131 @@
+use {
+    cgmath::{InnerSpace, vec2, vec3},
+    std::f32::consts::PI,
+    crate::{
+        float_utils::{abs, gamma},
+        material::Material,
+        polynomial::roots_in_interval,
+        quadrics::solve_quadratic,
+        ray::{HitInfo, Ray}
+    }
+};
+
+/// Torus around the y axis with its tube of minor_radius going around a circle of
+/// major_radius. U goes around y like it does on spheres and v around the tube starting
+/// from its outer side
+pub fn test_ray_torus_intersection<'a>(
+    major_radius: f32,
+    minor_radius: f32,
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32,
+    material: &Material<'a>
+) -> Option<HitInfo<'a>>
+{
+    // the quartic is solved only where the ray is inside of the bounding sphere, and from
+    // the point it enters it, so coefficients stay small however far the ray comes from
+    let (o, d) = (ray.origin, ray.direction);
+    // the sphere touches the outer equator of the tube, so it is padded to keep roots there
+    // from rounding off the start of the interval
+    let bounding_radius = (major_radius + minor_radius) * 1.01;
+    let (enter, leave) = solve_quadratic(d.dot(d), 2.0 * o.dot(d), o.dot(o) - bounding_radius * bounding_radius)?;
+    let (near, far) = (enter.max(t_min), leave.min(t_max));
+    if near >= far {
+        return None;
+    }
+
+    let (r2, rr2) = ((major_radius as f64).powi(2), (minor_radius as f64).powi(2));
+    let o = o + d * near;
+    let (o, d) = (o.cast::<f64>().unwrap(), d.cast::<f64>().unwrap());
+    let (dd, od, oo) = (d.dot(d), o.dot(d), o.dot(o));
+    let k = oo + r2 - rr2;
+    // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2) with p = o + s d
+    let coefficients = [
+        k * k - 4.0 * r2 * (o.x * o.x + o.z * o.z),
+        4.0 * od * k - 8.0 * r2 * (o.x * d.x + o.z * d.z),
+        4.0 * od * od + 2.0 * dd * k - 4.0 * r2 * (d.x * d.x + d.z * d.z),
+        4.0 * dd * od,
+        dd * dd
+    ];
+    let roots = roots_in_interval(&coefficients, 0.0, (far - near) as f64);
+    let t = roots.into_iter().map(|s| near + s as f32).find(|&t| t > t_min && t < t_max)?;
+
+    // the point is projected back to the tube around the nearest point of the major circle
+    let p = ray.get_point_at(t);
+    let rho = (p.x * p.x + p.z * p.z).sqrt();
+    let ring = if rho > 0.0 { vec3(p.x, 0.0, p.z) * (major_radius / rho) } else { vec3(major_radius, 0.0, 0.0) };
+    let n = (p - ring).normalize();
+    let p = ring + n * minor_radius;
+    let rho = (p.x * p.x + p.z * p.z).sqrt();
+
+    let uv = vec2(
+        p.z.atan2(p.x) / (2.0 * PI) + 0.5,
+        p.y.atan2(major_radius - rho) / (2.0 * PI) + 0.5
+    );
+    let dpdu = vec3(-p.z, 0.0, p.x) * (2.0 * PI);
+    // spindle tori reach the axis, where the direction of the ring is the only one left
+    let dpdv = vec3(ring.x * p.y / major_radius, major_radius - rho, ring.z * p.y / major_radius) * (2.0 * PI);
+    Some(HitInfo { t, p, n, ng: n, p_error: abs(p) * gamma(7), material: *material, uv: Some(uv), dpdu, dpdv })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cgmath::Vector3,
+        crate::material::{Albedo, MaterialDetails, Relief}
+    };
+
+    const MATERIAL: Material<'static> = Material {
+        albedo: Albedo::Constant(vec3(1.0, 1.0, 1.0)),
+        details: MaterialDetails::Lambertian,
+        emittance: 0.0,
+        relief: Relief::None
+    };
+
+    fn test(origin: Vector3<f32>, direction: Vector3<f32>) -> Option<HitInfo<'static>> {
+        test_ray_torus_intersection(2.0, 0.5, &Ray { origin, direction }, 0.0, 1e6, &MATERIAL)
+    }
+
+    #[test]
+    fn ray_along_axis_of_tube_hits_it() {
+        let hit_info = test(vec3(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.t - 2.5).abs() < 1e-4);
+        assert!((hit_info.n - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+        // the inner side of the tube faces the center
+        let hit_info = test(vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.t - 1.5).abs() < 1e-4);
+        assert!((hit_info.n - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-4);
+        assert!(test(vec3(0.0, 5.0, 0.0), vec3(0.0, -1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn ray_from_inside_of_tube_leaves_it() {
+        let hit_info = test(vec3(2.0, 0.0, 0.0), vec3(0.0, 2.0, 0.0)).unwrap();
+        assert!((hit_info.t - 0.25).abs() < 1e-4);
+        assert!((hit_info.n - vec3(0.0, 1.0, 0.0)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn distant_ray_hits_precisely() {
+        let hit_info = test(vec3(-1e4, 0.0, 0.0), vec3(1.0, 0.0, 0.0)).unwrap();
+        assert!((hit_info.p - vec3(-2.5, 0.0, 0.0)).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn spindle_torus_has_tangents_on_axis() {
+        // both points where the tube meets the axis are double roots, so either could be found
+        let ray = Ray { origin: vec3(0.0, 5.0, 0.0), direction: vec3(0.0, -1.0, 0.0) };
+        let hit_info = test_ray_torus_intersection(0.5, 1.0, &ray, 0.0, 100.0, &MATERIAL).unwrap();
+        assert!((hit_info.p.y.abs() - 0.75f32.sqrt()).abs() < 1e-4);
+        assert!(hit_info.dpdv.magnitude() > 1.0);
+        assert!(hit_info.dpdv.dot(hit_info.n).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ray_entering_at_outer_equator_hits_it() {
+        // the point where the ray enters is on the bounding sphere as well
+        let hit_info = test(vec3(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.3)).unwrap();
+        assert!((hit_info.p.magnitude() - 2.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,172 @@
+use {
+    cgmath::{
+        Matrix4,
+        SquareMatrix,
+        Vector3,
+        Vector4,
+        InnerSpace,
+        Quaternion,
+        vec3
+    },
+    std::ops::Mul,
+    crate::aabb::AaBb,
+    crate::ray::{HitInfo, Ray},
+    crate::float_utils::{abs, gamma}
+};
+
+/// Affine transform from a local space of a shape to the world space.
+/// Inverse is kept together with the matrix since rays are transformed by it on every hit test
+#[derive(Clone, Copy)]
+pub struct Transform {
+    pub matrix: Matrix4<f32>,
+    pub inverse: Matrix4<f32>
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Transform {
+            matrix: Matrix4::identity(),
+            inverse: Matrix4::identity()
+        }
+    }
+
+    /// None for singular matrices (i.e. ones scaling by zero), which can't be inverted
+    pub fn from_matrix(matrix: Matrix4<f32>) -> Option<Self> {
+        matrix.invert().map(|inverse| Transform { matrix, inverse })
+    }
+
+    pub fn translation(translation: Vector3<f32>) -> Self {
+        Transform {
+            matrix: Matrix4::from_translation(translation),
+            inverse: Matrix4::from_translation(-translation)
+        }
+    }
+
+    /// Rotates and then translates, which can always be inverted unlike scaling
+    pub fn rigid(translation: Vector3<f32>, rotation: Quaternion<f32>) -> Self {
+        Transform::new(translation, rotation, vec3(1.0, 1.0, 1.0)).expect("rotations can be inverted")
+    }
+
+    /// Scales first, then rotates and then translates. Basis vectors are rotated and normalized
+    /// one by one, so quaternions which are not unit still give a rotation. None when any
+    /// component of the scale is zero, like from_matrix
+    pub fn new(translation: Vector3<f32>, rotation: Quaternion<f32>, scale: Vector3<f32>) -> Option<Self> {
+        let i = (rotation * Vector3::unit_x()).normalize();
+        let j = (rotation * Vector3::unit_y()).normalize();
+        let k = (rotation * Vector3::unit_z()).normalize();
+        let matrix = Matrix4::from_cols(
+            (i * scale.x).extend(0.0),
+            (j * scale.y).extend(0.0),
+            (k * scale.z).extend(0.0),
+            translation.extend(1.0)
+        );
+        Transform::from_matrix(matrix)
+    }
+
+    pub fn point(&self, p: Vector3<f32>) -> Vector3<f32> {
+        (self.matrix * p.extend(1.0)).truncate()
+    }
+
+    pub fn vector(&self, v: Vector3<f32>) -> Vector3<f32> {
+        (self.matrix * v.extend(0.0)).truncate()
+    }
+
+    /// Normals are transformed by inverse transpose to stay perpendicular to the surface
+    pub fn normal(&self, n: Vector3<f32>) -> Vector3<f32> {
+        vec3(
+            self.inverse.x.truncate().dot(n),
+            self.inverse.y.truncate().dot(n),
+            self.inverse.z.truncate().dot(n)
+        ).normalize()
+    }
+
+    /// Transforms a point along with the bound of its absolute error
+    pub fn point_with_error(&self, p: Vector3<f32>, p_error: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+        let m = &self.matrix;
+        let abs_row = |r: usize| vec3(m.x[r].abs(), m.y[r].abs(), m.z[r].abs());
+        let (p_abs, m_t) = (abs(p), abs(m.w.truncate()));
+        let error = vec3(
+            abs_row(0).dot(p_error) * (gamma(3) + 1.0) + (abs_row(0).dot(p_abs) + m_t.x) * gamma(3),
+            abs_row(1).dot(p_error) * (gamma(3) + 1.0) + (abs_row(1).dot(p_abs) + m_t.y) * gamma(3),
+            abs_row(2).dot(p_error) * (gamma(3) + 1.0) + (abs_row(2).dot(p_abs) + m_t.z) * gamma(3)
+        );
+        (self.point(p), error)
+    }
+
+    /// Moves world space ray to the local space. Direction is not normalized afterwards,
+    /// so distances along the ray stay the same in both spaces
+    pub fn inverse_ray(&self, ray: &Ray) -> Ray {
+        Ray {
+            origin: (self.inverse * ray.origin.extend(1.0)).truncate(),
+            direction: (self.inverse * ray.direction.extend(0.0)).truncate()
+        }
+    }
+
+    /// Moves a hit found in the local space to the world space
+    pub fn hit_info<'a>(&self, hit_info: HitInfo<'a>) -> HitInfo<'a> {
+        let (p, p_error) = self.point_with_error(hit_info.p, hit_info.p_error);
+        HitInfo {
+            p,
+            p_error,
+            n: self.normal(hit_info.n),
+            ng: self.normal(hit_info.ng),
+            dpdu: self.vector(hit_info.dpdu),
+            dpdv: self.vector(hit_info.dpdv),
+            ..hit_info
+        }
+    }
+
+    pub fn aabb(&self, aabb: &AaBb) -> AaBb {
+        let corners = [
+            vec3(aabb.min.x, aabb.min.y, aabb.min.z),
+            vec3(aabb.min.x, aabb.min.y, aabb.max.z),
+            vec3(aabb.min.x, aabb.max.y, aabb.min.z),
+            vec3(aabb.min.x, aabb.max.y, aabb.max.z),
+            vec3(aabb.max.x, aabb.min.y, aabb.min.z),
+            vec3(aabb.max.x, aabb.min.y, aabb.max.z),
+            vec3(aabb.max.x, aabb.max.y, aabb.min.z),
+            vec3(aabb.max.x, aabb.max.y, aabb.max.z)
+        ];
+        AaBb::from_points(&corners.iter().map(|p| self.point(*p)).collect::<Vec<_>>()).unwrap()
+    }
+
+    /// Length of the image of each world axis under the linear part of the transform restricted
+    /// to given local axes. Used to find extents of round shapes
+    pub fn axis_extents(&self, local_axes: &[Vector3<f32>]) -> Vector3<f32> {
+        let row = |r: usize| local_axes
+            .iter()
+            .map(|a| {
+                let v: Vector4<f32> = self.matrix * a.extend(0.0);
+                v[r] * v[r]
+            })
+            .sum::<f32>()
+            .sqrt();
+        vec3(row(0), row(1), row(2))
+    }
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    /// Composition which applies rhs first
+    fn mul(self, rhs: Transform) -> Transform {
+        Transform {
+            matrix: self.matrix * rhs.matrix,
+            inverse: rhs.inverse * self.inverse
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn singular_matrix_has_no_transform() {
+        assert!(Transform::from_matrix(Matrix4::from_nonuniform_scale(1.0, 0.0, 1.0)).is_none());
+        assert!(Transform::new(vec3(1.0, 0.0, 0.0), Quaternion::new(1.0, 0.0, 0.0, 0.0), vec3(1.0, 0.0, 1.0)).is_none());
+        let transform = Transform::from_matrix(Matrix4::from_scale(2.0)).unwrap();
+        let product = transform.inverse * transform.matrix;
+        assert!((0..4).all(|c| (product[c] - Matrix4::identity()[c]).magnitude() < 1e-6));
+    }
+}
@@ -0,0 +1,245 @@
+use {
+    cgmath::{Vector3, vec3},
+    std::{
+        fmt,
+        fs,
+        io,
+        path::Path
+    },
+    crate::{
+        aabb::AaBb,
+        material::{Albedo, Material, MaterialDetails, Relief},
+        shape::Shape,
+        transform::Transform,
+        voxel::VoxelGrid
+    }
+};
+
+const CHUNK_HEADER_SIZE: usize = 12;
+const PALETTE_SIZE: usize = 256;
+/// MagicaVoxel models are at most this big along every axis, voxel coordinates are bytes
+const MAX_VOX_SIZE: usize = 256;
+
+#[derive(Debug)]
+pub enum VoxError {
+    Io(io::Error),
+    NotVox,
+    Truncated,
+    Invalid(String)
+}
+
+impl fmt::Display for VoxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VoxError::Io(e) => write!(f, "{}", e),
+            VoxError::NotVox => write!(f, "file doesn't start with VOX header"),
+            VoxError::Truncated => write!(f, "file is truncated"),
+            VoxError::Invalid(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl From<io::Error> for VoxError {
+    fn from(e: io::Error) -> Self {
+        VoxError::Io(e)
+    }
+}
+
+/// Voxels of a MagicaVoxel model with a lambertian material for every color of its palette,
+/// the value of a voxel is the index of its color like it is in the file
+pub struct VoxModel {
+    pub grid: VoxelGrid,
+    pub materials: Vec<Material<'static>>
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, VoxError> {
+    let b = offset.checked_add(4).and_then(|end| bytes.get(offset..end)).ok_or(VoxError::Truncated)?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Palette MagicaVoxel uses for files without one: a cube of six levels of every channel
+/// from white down (without black) followed by ramps of red, green, blue and grey. Like
+/// in the file, it starts with the color of empty voxels
+fn default_palette() -> Vec<Vector3<f32>> {
+    let cube = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    let ramp = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+    let mut palette = Vec::with_capacity(PALETTE_SIZE);
+    palette.push([0, 0, 0]);
+    for &r in &cube {
+        for &g in &cube {
+            for &b in &cube {
+                palette.push([r, g, b]);
+            }
+        }
+    }
+    palette.pop();
+    palette.extend(ramp.iter().map(|&r| [r, 0, 0]));
+    palette.extend(ramp.iter().map(|&g| [0, g, 0]));
+    palette.extend(ramp.iter().map(|&b| [0, 0, b]));
+    palette.extend(ramp.iter().map(|&l| [l, l, l]));
+    palette.iter().map(|c| vec3(c[0] as f32, c[1] as f32, c[2] as f32) / 255.0).collect()
+}
+
+impl VoxModel {
+    pub fn load(path: &Path) -> Result<Self, VoxError> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    /// Reads the first model of a file along with its palette, scene graph and material
+    /// chunks of newer versions are skipped. Z is up in MagicaVoxel, so it becomes y here
+    pub fn parse(bytes: &[u8]) -> Result<Self, VoxError> {
+        if bytes.get(..4) != Some(b"VOX ".as_slice()) {
+            return Err(VoxError::NotVox);
+        }
+        let mut size: Option<[usize; 3]> = None;
+        let mut grid: Option<VoxelGrid> = None;
+        let mut palette = default_palette();
+
+        // main chunk only has children, so chunks are read one after another from its content
+        let mut offset = 8 + CHUNK_HEADER_SIZE;
+        while offset < bytes.len() {
+            let id = bytes.get(offset..offset + 4).ok_or(VoxError::Truncated)?;
+            let content_size = read_u32(bytes, offset + 4)? as usize;
+            let children_size = read_u32(bytes, offset + 8)? as usize;
+            // sizes come from the file, so they could point anywhere
+            let content_start = offset + CHUNK_HEADER_SIZE;
+            let content = content_start
+                .checked_add(content_size)
+                .and_then(|end| bytes.get(content_start..end))
+                .ok_or(VoxError::Truncated)?;
+            match id {
+                b"SIZE" if size.is_none() => {
+                    let dimension = |i: usize| read_u32(content, i * 4).map(|d| d as usize);
+                    let dimensions = [dimension(0)?, dimension(1)?, dimension(2)?];
+                    if dimensions.contains(&0) {
+                        return Err(VoxError::Invalid("model has zero size".to_string()));
+                    }
+                    // the grid is allocated for every voxel of the size before any is read
+                    let cells = dimensions
+                        .iter()
+                        .try_fold(1usize, |cells, &d| cells.checked_mul(d).filter(|_| d <= MAX_VOX_SIZE));
+                    if cells.is_none() {
+                        return Err(VoxError::Invalid(format!(
+                            "model size {:?} is larger than {} along some axis", dimensions, MAX_VOX_SIZE
+                        )));
+                    }
+                    size = Some(dimensions);
+                },
+                b"XYZI" if grid.is_none() => {
+                    let [sx, sy, sz] = size.ok_or_else(|| VoxError::Invalid("voxels come before size".to_string()))?;
+                    let count = read_u32(content, 0)? as usize;
+                    let voxels = count
+                        .checked_mul(4)
+                        .and_then(|length| content.get(4..).and_then(|voxels| voxels.get(..length)))
+                        .ok_or(VoxError::Truncated)?;
+                    let mut model = VoxelGrid::dense([sx, sz, sy]);
+                    for voxel in voxels.chunks_exact(4) {
+                        let (x, y, z) = (voxel[0] as usize, voxel[1] as usize, voxel[2] as usize);
+                        if x >= sx || y >= sy || z >= sz {
+                            return Err(VoxError::Invalid(format!("voxel ({}, {}, {}) is out of the model", x, y, z)));
+                        }
+                        model.set(x, z, sy - 1 - y, voxel[3]);
+                    }
+                    grid = Some(model);
+                },
+                b"RGBA" => {
+                    let colors = content.get(..PALETTE_SIZE * 4).ok_or(VoxError::Truncated)?;
+                    // the first color goes to index 1, since 0 is empty
+                    palette = std::iter::once(vec3(0.0, 0.0, 0.0))
+                        .chain(colors.chunks_exact(4).take(PALETTE_SIZE - 1).map(|c| vec3(c[0] as f32, c[1] as f32, c[2] as f32) / 255.0))
+                        .collect();
+                },
+                _ => {}
+            }
+            offset = content_start
+                .checked_add(content_size)
+                .and_then(|end| end.checked_add(children_size))
+                .ok_or(VoxError::Truncated)?;
+        }
+
+        let grid = grid.ok_or_else(|| VoxError::Invalid("file has no voxels".to_string()))?;
+        // values of voxels index materials from 1
+        let materials = palette
+            .into_iter()
+            .skip(1)
+            .map(|color| Material {
+                albedo: Albedo::Constant(color),
+                details: MaterialDetails::Lambertian,
+                emittance: 0.0,
+                relief: Relief::None
+            })
+            .collect();
+        Ok(VoxModel { grid, materials })
+    }
+
+    pub fn bounds(&self) -> AaBb {
+        self.grid.bounds()
+    }
+
+    pub fn shape(&self, transform: Transform) -> Shape<'_> {
+        Shape::Voxels { grid: &self.grid, materials: &self.materials, transform }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], content: &[u8], children: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(children.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(content);
+        bytes.extend_from_slice(children);
+        bytes
+    }
+
+    fn vox(size: [u32; 3], voxels: &[[u8; 4]], palette: Option<&[u8]>) -> Vec<u8> {
+        let size = size.iter().flat_map(|d| d.to_le_bytes()).collect::<Vec<_>>();
+        let mut xyzi = (voxels.len() as u32).to_le_bytes().to_vec();
+        xyzi.extend(voxels.iter().flatten());
+        let mut children = chunk(b"SIZE", &size, &[]);
+        children.extend(chunk(b"XYZI", &xyzi, &[]));
+        if let Some(palette) = palette {
+            children.extend(chunk(b"RGBA", palette, &[]));
+        }
+        let mut bytes = b"VOX ".to_vec();
+        bytes.extend_from_slice(&150u32.to_le_bytes());
+        bytes.extend(chunk(b"MAIN", &[], &children));
+        bytes
+    }
+
+    #[test]
+    fn reads_voxels_and_palette() {
+        let mut palette = vec![255; PALETTE_SIZE * 4];
+        // the fifth color of the file is the color of voxels with value 5
+        palette[16..20].copy_from_slice(&[255, 0, 0, 255]);
+        let model = VoxModel::parse(&vox([2, 3, 4], &[[1, 2, 3, 5], [0, 0, 0, 1]], Some(&palette))).unwrap();
+
+        // z goes up and y goes back in the grid
+        assert_eq!(model.grid.size, [2, 4, 3]);
+        assert_eq!(model.grid.get(1, 3, 0), 5);
+        assert_eq!(model.grid.get(0, 0, 2), 1);
+        assert_eq!(model.grid.get(0, 0, 0), 0);
+        assert_eq!(model.materials.len(), PALETTE_SIZE - 1);
+        match model.materials[4].albedo {
+            Albedo::Constant(color) => assert_eq!(color, vec3(1.0, 0.0, 0.0)),
+            _ => panic!("voxel material isn't a constant color")
+        }
+    }
+
+    #[test]
+    fn rejects_bad_sizes() {
+        for &size in &[[257, 1, 1], [u32::MAX, u32::MAX, u32::MAX], [0, 1, 1]] {
+            match VoxModel::parse(&vox(size, &[], None)) {
+                Err(VoxError::Invalid(_)) => {},
+                _ => panic!("model of size {:?} is read", size)
+            }
+        }
+        // voxel count goes past the end of its chunk
+        let mut bytes = vox([1, 1, 1], &[[0, 0, 0, 1]], None);
+        let count_offset = bytes.len() - 8;
+        bytes[count_offset..count_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(VoxModel::parse(&bytes), Err(VoxError::Truncated)));
+    }
+}
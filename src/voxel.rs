@@ -0,0 +1,241 @@
+use {
+    cgmath::{Vector3, vec2, vec3},
+    std::collections::HashMap,
+    crate::{
+        aabb::AaBb,
+        float_utils::{abs, gamma},
+        material::{Albedo, Material, MaterialDetails, Relief},
+        ray::{HitInfo, Ray}
+    }
+};
+
+/// Voxels which are empty, any other value is an index into materials of a grid plus one
+pub const EMPTY: u8 = 0;
+const CHUNK_BITS: usize = 4;
+const CHUNK_SIZE: usize = 1 << CHUNK_BITS;
+/// Voxels with values past the end of materials are magenta, so they are easy to spot
+const MISSING_MATERIAL: Material<'static> = Material {
+    albedo: Albedo::Constant(vec3(1.0, 0.0, 1.0)),
+    details: MaterialDetails::Lambertian,
+    emittance: 0.0,
+    relief: Relief::None
+};
+
+pub enum VoxelStorage {
+    Dense(Vec<u8>),
+    /// chunks of 16^3 voxels, which are only allocated once something is put into them
+    Sparse(HashMap<[usize; 3], Box<[u8; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE]>>)
+}
+
+/// Grid of unit voxels, which is centered at the origin of its local space
+pub struct VoxelGrid {
+    pub size: [usize; 3],
+    pub storage: VoxelStorage
+}
+
+fn chunk_offset(x: usize, y: usize, z: usize) -> usize {
+    let mask = CHUNK_SIZE - 1;
+    (x & mask) + ((y & mask) << CHUNK_BITS) + ((z & mask) << (2 * CHUNK_BITS))
+}
+
+impl VoxelGrid {
+    pub fn dense(size: [usize; 3]) -> Self {
+        assert!(size.iter().all(|&s| s > 0), "voxel grid can't be empty");
+        VoxelGrid { size, storage: VoxelStorage::Dense(vec![EMPTY; size[0] * size[1] * size[2]]) }
+    }
+
+    /// Grid for large and mostly empty volumes
+    pub fn sparse(size: [usize; 3]) -> Self {
+        assert!(size.iter().all(|&s| s > 0), "voxel grid can't be empty");
+        VoxelGrid { size, storage: VoxelStorage::Sparse(HashMap::new()) }
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        match &self.storage {
+            VoxelStorage::Dense(voxels) => voxels[x + self.size[0] * (y + self.size[1] * z)],
+            VoxelStorage::Sparse(chunks) => chunks
+                .get(&[x >> CHUNK_BITS, y >> CHUNK_BITS, z >> CHUNK_BITS])
+                .map_or(EMPTY, |chunk| chunk[chunk_offset(x, y, z)])
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, value: u8) {
+        assert!(x < self.size[0] && y < self.size[1] && z < self.size[2], "voxel is out of the grid");
+        match &mut self.storage {
+            VoxelStorage::Dense(voxels) => voxels[x + self.size[0] * (y + self.size[1] * z)] = value,
+            VoxelStorage::Sparse(chunks) => {
+                let key = [x >> CHUNK_BITS, y >> CHUNK_BITS, z >> CHUNK_BITS];
+                if value == EMPTY && !chunks.contains_key(&key) {
+                    return;
+                }
+                chunks.entry(key).or_insert_with(|| Box::new([EMPTY; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE]))
+                    [chunk_offset(x, y, z)] = value;
+            }
+        }
+    }
+
+    pub fn bounds(&self) -> AaBb {
+        let half_size = vec3(self.size[0] as f32, self.size[1] as f32, self.size[2] as f32) * 0.5;
+        AaBb { min: -half_size, max: half_size }
+    }
+}
+
+/// Walks voxels pierced by the ray in order with the DDA of Amanatides and Woo, so empty space
+/// costs a few additions per voxel. The surface is where the ray goes from empty voxels to
+/// occupied ones, or the other way around for rays starting inside (i.e. refracted ones)
+pub fn test_ray_voxels_intersection<'a>(
+    grid: &VoxelGrid,
+    materials: &[Material<'a>],
+    ray: &Ray,
+    t_min: f32,
+    t_max: f32
+) -> Option<HitInfo<'a>>
+{
+    let bounds = grid.bounds();
+    let (o, d) = (ray.origin, ray.direction);
+    let inv_dir = vec3(1.0 / d.x, 1.0 / d.y, 1.0 / d.z);
+    let (near, far) = bounds.hit_interval(ray, &inv_dir, t_min, t_max)?;
+    // faces the ray goes through are the ones nearest to where it enters and leaves the grid
+    let face_axis = |t: f32| {
+        let p = ray.get_point_at(t);
+        let distance = |a: usize| (p[a] - bounds.min[a]).abs().min((bounds.max[a] - p[a]).abs());
+        (0..3).min_by(|&a, &b| distance(a).total_cmp(&distance(b))).unwrap()
+    };
+    let near_axis = if near > t_min { Some(face_axis(near)) } else { None };
+
+    let start = o + d * near - bounds.min;
+    let mut cell = [0usize; 3];
+    let mut step = [0isize; 3];
+    let mut t_next = [f32::INFINITY; 3];
+    let mut t_delta = [f32::INFINITY; 3];
+    for a in 0..3 {
+        cell[a] = (start[a].floor().max(0.0) as usize).min(grid.size[a] - 1);
+        if d[a] != 0.0 {
+            step[a] = if d[a] > 0.0 { 1 } else { -1 };
+            let boundary = bounds.min[a] + (cell[a] as isize + step[a].max(0)) as f32;
+            t_next[a] = (boundary - o[a]) / d[a];
+            t_delta[a] = (1.0 / d[a]).abs();
+        }
+    }
+
+    let voxel = |cell: [usize; 3]| grid.get(cell[0], cell[1], cell[2]);
+    // rays coming from outside of the grid start in the empty space around it
+    let from_outside = near_axis.is_some();
+    let inside = !from_outside && voxel(cell) != EMPTY;
+    // where the ray crosses into the current cell, none if it starts in it
+    let mut entry = near_axis.map(|axis| (near, axis));
+    let mut previous = cell;
+    loop {
+        if (voxel(cell) != EMPTY) != inside {
+            if let Some((t, axis)) = entry.filter(|&(t, _)| t > t_min) {
+                let solid = if inside { previous } else { cell };
+                // normal faces the ray when it enters the solid and looks along it when leaving
+                let sign = if inside { step[axis] } else { -step[axis] } as f32;
+                return Some(voxel_hit(grid, materials, solid, ray, t, axis, sign));
+            }
+        }
+        let axis = if t_next[0] < t_next[1] && t_next[0] < t_next[2] {
+            0
+        } else if t_next[1] < t_next[2] {
+            1
+        } else {
+            2
+        };
+        let next = cell[axis] as isize + step[axis];
+        if t_next[axis] > far || next < 0 || next >= grid.size[axis] as isize {
+            // rays from inside leave the solid where they leave the grid
+            return if inside && far > t_min && far < t_max {
+                let far_axis = face_axis(far);
+                let sign = if d[far_axis] > 0.0 { 1.0 } else { -1.0 };
+                Some(voxel_hit(grid, materials, cell, ray, far, far_axis, sign))
+            } else {
+                None
+            };
+        }
+        previous = cell;
+        cell[axis] = next as usize;
+        entry = Some((t_next[axis], axis));
+        t_next[axis] += t_delta[axis];
+    }
+}
+
+fn voxel_hit<'a>(
+    grid: &VoxelGrid,
+    materials: &[Material<'a>],
+    cell: [usize; 3],
+    ray: &Ray,
+    t: f32,
+    axis: usize,
+    sign: f32
+) -> HitInfo<'a>
+{
+    let bounds = grid.bounds();
+    let mut p = ray.get_point_at(t);
+    // the point is put exactly onto the face it was found on
+    p[axis] = bounds.min[axis] + cell[axis] as f32 + if sign > 0.0 { 1.0 } else { 0.0 };
+    let mut n = vec3(0.0, 0.0, 0.0);
+    n[axis] = sign;
+    let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+    let local = p - bounds.min;
+    let uv = vec2(local[u_axis] - cell[u_axis] as f32, local[v_axis] - cell[v_axis] as f32);
+    let (mut dpdu, mut dpdv) = (Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+    dpdu[u_axis] = 1.0;
+    dpdv[v_axis] = 1.0;
+    let index = grid.get(cell[0], cell[1], cell[2]) as usize;
+    HitInfo {
+        t,
+        p,
+        n,
+        ng: n,
+        p_error: abs(p) * gamma(7),
+        material: materials.get(index - 1).copied().unwrap_or(MISSING_MATERIAL),
+        uv: Some(uv),
+        dpdu,
+        dpdv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATERIALS: [Material<'static>; 1] = [MISSING_MATERIAL];
+
+    fn corner_voxel() -> VoxelGrid {
+        let mut grid = VoxelGrid::dense([2, 2, 2]);
+        grid.set(0, 0, 0, 1);
+        grid
+    }
+
+    #[test]
+    fn ray_from_outside_hits_front_face_of_first_voxel() {
+        let ray = Ray { origin: vec3(-5.0, -0.5, -0.5), direction: vec3(1.0, 0.0, 0.0) };
+        let hit_info = test_ray_voxels_intersection(&corner_voxel(), &MATERIALS, &ray, 0.0, 100.0).unwrap();
+        assert!((hit_info.t - 4.0).abs() < 1e-5);
+        assert_eq!(hit_info.n, vec3(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn ray_from_inside_hits_back_face() {
+        let ray = Ray { origin: vec3(-0.5, -0.5, -0.5), direction: vec3(1.0, 0.0, 0.0) };
+        let hit_info = test_ray_voxels_intersection(&corner_voxel(), &MATERIALS, &ray, 0.0, 100.0).unwrap();
+        assert!((hit_info.t - 0.5).abs() < 1e-5);
+        assert_eq!(hit_info.n, vec3(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn ray_through_empty_voxels_misses() {
+        let ray = Ray { origin: vec3(-5.0, 0.5, 0.5), direction: vec3(1.0, 0.0, 0.0) };
+        assert!(test_ray_voxels_intersection(&corner_voxel(), &MATERIALS, &ray, 0.0, 100.0).is_none());
+    }
+
+    #[test]
+    fn values_without_material_get_missing_one() {
+        let mut grid = VoxelGrid::sparse([2, 2, 2]);
+        grid.set(1, 1, 1, 7);
+        let ray = Ray { origin: vec3(0.5, 0.5, 5.0), direction: vec3(0.0, 0.0, -1.0) };
+        let hit_info = test_ray_voxels_intersection(&grid, &[], &ray, 0.0, 100.0).unwrap();
+        assert!((hit_info.t - 4.0).abs() < 1e-5);
+        assert_eq!(hit_info.n, vec3(0.0, 0.0, 1.0));
+    }
+}
@@ -1,47 +1,70 @@
 use{
     crate::{
         shape::Shape,
-        ray::{HitTestable, HitInfo, Ray}
+        ray::{HitTestable, HitInfo, Ray},
+        bvh::Bvh
     }
 };
 use crate::aabb::AaBb;
 
 pub struct World<'a> {
     shapes: &'a[Shape<'a>],
-    bouning_boxes: Vec<Option<AaBb>>
+    bvh: Bvh,
+    // shapes which don't provide AaBb can't be put in bvh, so they are tested one by one
+    unbounded_shapes: Vec<usize>
 }
 
 impl<'a> World<'a> {
     pub fn construct(shapes: &'a[Shape<'a>]) -> Self {
-        let mut aabb_companions = Vec::with_capacity(shapes.len());
-        for i in 0..shapes.len() {
-            aabb_companions.push(shapes[i].into())
+        let mut bounded_shapes = Vec::with_capacity(shapes.len());
+        let mut unbounded_shapes = Vec::new();
+        for (i, shape) in shapes.iter().enumerate() {
+            let aabb: Option<AaBb> = (*shape).into();
+            match aabb {
+                Some(aabb) => bounded_shapes.push((i, aabb)),
+                None => unbounded_shapes.push(i)
+            }
         }
         World {
             shapes,
-            bouning_boxes: aabb_companions
+            bvh: Bvh::build(&bounded_shapes),
+            unbounded_shapes
         }
     }
+
+    /// Box enclosing every shape of the world which has bounds
+    pub fn bounds(&self) -> Option<AaBb> {
+        self.bvh.bounds()
+    }
+
+    /// Tells if every shape of the world has bounds
+    pub fn is_bounded(&self) -> bool {
+        self.unbounded_shapes.is_empty()
+    }
 }
 
 impl HitTestable for World<'_> {
-    fn hit_test(&self, ray: &Ray) -> Option<HitInfo> {
+    fn hit_test(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitInfo<'_>> {
         let mut nearest_hit: Option<HitInfo> = None;
-        for i in 0..self.shapes.len() {
-            let aabb_is_hit = self.bouning_boxes[i]
-                .map(|b| b.is_hit(ray))
-                .unwrap_or(true);
-
-            if !aabb_is_hit { continue; }
-            let hit = ray.hit_test(&self.shapes[i]);
-            if let Some(hit_i) = hit {
-                match nearest_hit {
-                    None => nearest_hit = hit,
-                    Some(hit_info) if hit_info.t > hit_i.t => nearest_hit = hit,
-                    _ => {}
-                };
+        let mut t_max = t_max;
+        for &i in &self.unbounded_shapes {
+            if let Some(hit_info) = ray.hit_test(&self.shapes[i], t_min, t_max) {
+                t_max = hit_info.t;
+                nearest_hit = Some(hit_info);
             }
         }
+        self.bvh.traverse(ray, t_min, t_max, |i, t_max| {
+            let hit = ray.hit_test(&self.shapes[i], t_min, t_max);
+            if hit.is_some() {
+                nearest_hit = hit;
+            }
+            hit.map(|hit_info| hit_info.t)
+        });
         nearest_hit
     }
-}
\ No newline at end of file
+
+    fn occluded(&self, ray: &Ray, t_max: f32) -> bool {
+        self.unbounded_shapes.iter().any(|&i| self.shapes[i].occluded(ray, t_max)) ||
+            self.bvh.any(ray, 0.0, t_max, |i| self.shapes[i].occluded(ray, t_max))
+    }
+}